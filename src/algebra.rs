@@ -0,0 +1,45 @@
+use super::Error;
+
+fn check_lengths(a: &[f64], b: &[f64]) -> Result<(), Error> {
+    if a.len() != b.len() {
+        return Err(Error::Series(format!(
+            "components must have the same length, got {} and {}",
+            a.len(),
+            b.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Adds two components element-wise.
+pub fn add_components(a: &[f64], b: &[f64]) -> Result<Vec<f64>, Error> {
+    check_lengths(a, b)?;
+    Ok(a.iter().zip(b).map(|(x, y)| x + y).collect())
+}
+
+/// Subtracts the second component from the first element-wise, such as to
+/// remove this year's fitted seasonal from last year's observations.
+pub fn subtract_components(a: &[f64], b: &[f64]) -> Result<Vec<f64>, Error> {
+    check_lengths(a, b)?;
+    Ok(a.iter().zip(b).map(|(x, y)| x - y).collect())
+}
+
+/// Sums any number of components element-wise.
+pub fn sum_components<S: AsRef<[f64]>>(components: &[S]) -> Result<Vec<f64>, Error> {
+    let Some(first) = components.first() else {
+        return Ok(Vec::new());
+    };
+    let mut sum = first.as_ref().to_vec();
+    for component in &components[1..] {
+        check_lengths(&sum, component.as_ref())?;
+        for (s, v) in sum.iter_mut().zip(component.as_ref()) {
+            *s += v;
+        }
+    }
+    Ok(sum)
+}
+
+/// Multiplies a component by a factor.
+pub fn scale_component(component: &[f64], factor: f64) -> Vec<f64> {
+    component.iter().map(|v| v * factor).collect()
+}
@@ -0,0 +1,208 @@
+use super::seasonal_model::{mad, median};
+
+/// An observation whose remainder is unusually large.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Anomaly {
+    pub(crate) index: usize,
+    pub(crate) observed: f64,
+    pub(crate) expected: f64,
+    pub(crate) z_score: f64,
+}
+
+impl Anomaly {
+    /// Returns the index of the observation.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the observed value.
+    pub fn observed(&self) -> f64 {
+        self.observed
+    }
+
+    /// Returns the expected value (the seasonal and trend components).
+    pub fn expected(&self) -> f64 {
+        self.expected
+    }
+
+    /// Returns the robust z-score of the remainder.
+    pub fn z_score(&self) -> f64 {
+        self.z_score
+    }
+
+    /// Returns whether the observation is above the expected value.
+    pub fn is_high(&self) -> bool {
+        self.z_score > 0.0
+    }
+}
+
+/// Remainder outlier flags from the interquartile range rule.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Outliers {
+    pub(crate) flags: Vec<bool>,
+    pub(crate) scores: Vec<f64>,
+}
+
+impl Outliers {
+    /// Returns whether each observation is an outlier.
+    pub fn flags(&self) -> &[bool] {
+        &self.flags
+    }
+
+    /// Returns the distance of each remainder outside the quartiles, in
+    /// interquartile ranges.
+    ///
+    /// The score is negative below the lower quartile, positive above the
+    /// upper quartile, and zero between them.
+    pub fn scores(&self) -> &[f64] {
+        &self.scores
+    }
+
+    /// Returns the indices of the outliers.
+    pub fn indices(&self) -> Vec<usize> {
+        self.flags
+            .iter()
+            .enumerate()
+            .filter(|&(_, &f)| f)
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+// Linearly interpolated quantile of sorted values
+pub(crate) fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let h = (sorted.len() - 1) as f64 * q;
+    let lo = h.floor() as usize;
+    let hi = h.ceil() as usize;
+    sorted[lo] + (h - lo as f64) * (sorted[hi] - sorted[lo])
+}
+
+// Tukey's fences on the remainder, scaled by the interquartile range
+pub(crate) fn outliers(remainder: &[f64], k_iqr: f64) -> Outliers {
+    let mut sorted = remainder
+        .iter()
+        .copied()
+        .filter(|v| !v.is_nan())
+        .collect::<Vec<f64>>();
+    sorted.sort_unstable_by(|a, b| a.total_cmp(b));
+    let (q1, q3) = if sorted.is_empty() {
+        (0.0, 0.0)
+    } else {
+        (quantile(&sorted, 0.25), quantile(&sorted, 0.75))
+    };
+    let iqr = q3 - q1;
+
+    let scores = remainder
+        .iter()
+        .map(|&r| {
+            let excess = if r < q1 {
+                r - q1
+            } else if r > q3 {
+                r - q3
+            } else {
+                0.0
+            };
+            if r.is_nan() || excess == 0.0 {
+                excess
+            } else if iqr > 0.0 {
+                excess / iqr
+            } else {
+                excess.signum() * f64::INFINITY
+            }
+        })
+        .collect::<Vec<f64>>();
+    let flags = scores.iter().map(|s| s.abs() > k_iqr).collect();
+    Outliers { flags, scores }
+}
+
+// Acklam's rational approximation of the inverse standard normal CDF
+#[allow(clippy::excessive_precision)]
+pub(crate) fn norm_ppf(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e1,
+        2.209460984245205e2,
+        -2.759285104469687e2,
+        1.383577518672690e2,
+        -3.066479806614716e1,
+        2.506628277459239e0,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e1,
+        1.615858368580409e2,
+        -1.556989798598866e2,
+        6.680131188771972e1,
+        -1.328068155288572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-3,
+        -3.223964580411365e-1,
+        -2.400758277161838e0,
+        -2.549732539343734e0,
+        4.374664141464968e0,
+        2.938163982698783e0,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-3,
+        3.224671290700398e-1,
+        2.445134137142996e0,
+        3.754408661907416e0,
+    ];
+
+    let plow = 0.02425;
+    if p < plow {
+        let q = (-2.0 * p.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    } else if p <= 1.0 - plow {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        -norm_ppf(1.0 - p)
+    }
+}
+
+// Robust z-scores of the remainder, using the median and scaled MAD
+pub(crate) fn z_scores(remainder: &[f64]) -> Vec<f64> {
+    let finite = remainder
+        .iter()
+        .copied()
+        .filter(|v| !v.is_nan())
+        .collect::<Vec<f64>>();
+    let center = median(&finite);
+    let scale = mad(&finite);
+    remainder
+        .iter()
+        .map(|r| {
+            if scale > 0.0 {
+                (r - center) / scale
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn anomalies(
+    seasonal: &[f64],
+    trend: &[f64],
+    remainder: &[f64],
+    alpha: f64,
+) -> Vec<Anomaly> {
+    let threshold = norm_ppf(1.0 - alpha / 2.0);
+    z_scores(remainder)
+        .into_iter()
+        .enumerate()
+        .filter(|(_, z)| z.abs() > threshold)
+        .map(|(i, z)| {
+            let expected = seasonal[i] + trend[i];
+            Anomaly {
+                index: i,
+                observed: expected + remainder[i],
+                expected,
+                z_score: z,
+            }
+        })
+        .collect()
+}
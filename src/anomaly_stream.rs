@@ -0,0 +1,100 @@
+use super::{Error, SlidingStl, StlParams};
+
+/// The score of a new observation against the current decomposition.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnomalyScore {
+    pub(crate) index: usize,
+    pub(crate) observed: f64,
+    pub(crate) expected: f64,
+    pub(crate) z_score: f64,
+}
+
+impl AnomalyScore {
+    /// Returns the index of the observation, counting from the first one pushed.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the observed value.
+    pub fn observed(&self) -> f64 {
+        self.observed
+    }
+
+    /// Returns the expected value (the seasonal cycle and extrapolated trend).
+    pub fn expected(&self) -> f64 {
+        self.expected
+    }
+
+    /// Returns the residual divided by the robust scale of the remainder.
+    pub fn z_score(&self) -> f64 {
+        self.z_score
+    }
+}
+
+/// Scores observations as they arrive against a decomposition of the
+/// preceding window.
+///
+/// Each new observation is compared with the most recent seasonal cycle plus
+/// the trend extrapolated one step, before being added to the window.
+#[derive(Clone, Debug)]
+pub struct AnomalyStream {
+    window: SlidingStl,
+    count: usize,
+}
+
+impl AnomalyStream {
+    /// Creates a new stream with default parameters.
+    pub fn new(window_len: usize, period: usize) -> Result<Self, Error> {
+        Self::with_params(window_len, period, StlParams::new())
+    }
+
+    /// Creates a new stream with the given parameters.
+    ///
+    /// The window must hold at least two periods, and the parameters must be
+    /// valid for a series of the window length.
+    pub fn with_params(window_len: usize, period: usize, params: StlParams) -> Result<Self, Error> {
+        if let Some(err) = params.validate(window_len, period).into_iter().next() {
+            return Err(err);
+        }
+        Ok(Self {
+            window: SlidingStl::with_params(window_len, period, params)?,
+            count: 0,
+        })
+    }
+
+    /// Scores an observation and adds it to the window.
+    ///
+    /// Returns `None` until the window holds two periods of observations.
+    pub fn push(&mut self, value: f64) -> Result<Option<AnomalyScore>, Error> {
+        let score = if self.window.len() < 2 * self.window.period() {
+            None
+        } else {
+            Some(self.window.current()?)
+        };
+        let score = score.map(|result| {
+            let model = result.seasonal_model();
+            let tail = model.trend_tail();
+            let slope = if tail.len() > 1 {
+                (tail[tail.len() - 1] - tail[0]) / (tail.len() - 1) as f64
+            } else {
+                0.0
+            };
+            let expected =
+                model.seasonal_cycle()[model.next_phase()] + tail[tail.len() - 1] + slope;
+            let scale = model.remainder_scale();
+            AnomalyScore {
+                index: self.count,
+                observed: value,
+                expected,
+                z_score: if scale > 0.0 {
+                    (value - expected) / scale
+                } else {
+                    0.0
+                },
+            }
+        });
+        self.window.push(value);
+        self.count += 1;
+        Ok(score)
+    }
+}
@@ -0,0 +1,230 @@
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyCapsule;
+use std::ffi::{CStr, c_char, c_void};
+
+// The structs of the Arrow C data and stream interfaces
+
+#[repr(C)]
+struct ArrowSchema {
+    format: *const c_char,
+    name: *const c_char,
+    metadata: *const c_char,
+    flags: i64,
+    n_children: i64,
+    children: *mut *mut ArrowSchema,
+    dictionary: *mut ArrowSchema,
+    release: Option<unsafe extern "C" fn(*mut ArrowSchema)>,
+    private_data: *mut c_void,
+}
+
+#[repr(C)]
+struct ArrowArray {
+    length: i64,
+    null_count: i64,
+    offset: i64,
+    n_buffers: i64,
+    n_children: i64,
+    buffers: *mut *const c_void,
+    children: *mut *mut ArrowArray,
+    dictionary: *mut ArrowArray,
+    release: Option<unsafe extern "C" fn(*mut ArrowArray)>,
+    private_data: *mut c_void,
+}
+
+#[repr(C)]
+struct ArrowArrayStream {
+    get_schema: Option<unsafe extern "C" fn(*mut ArrowArrayStream, *mut ArrowSchema) -> i32>,
+    get_next: Option<unsafe extern "C" fn(*mut ArrowArrayStream, *mut ArrowArray) -> i32>,
+    get_last_error: Option<unsafe extern "C" fn(*mut ArrowArrayStream) -> *const c_char>,
+    release: Option<unsafe extern "C" fn(*mut ArrowArrayStream)>,
+    private_data: *mut c_void,
+}
+
+impl ArrowSchema {
+    fn empty() -> Self {
+        Self {
+            format: std::ptr::null(),
+            name: std::ptr::null(),
+            metadata: std::ptr::null(),
+            flags: 0,
+            n_children: 0,
+            children: std::ptr::null_mut(),
+            dictionary: std::ptr::null_mut(),
+            release: None,
+            private_data: std::ptr::null_mut(),
+        }
+    }
+}
+
+impl Drop for ArrowSchema {
+    fn drop(&mut self) {
+        if let Some(release) = self.release {
+            unsafe { release(self) };
+        }
+    }
+}
+
+impl ArrowArray {
+    fn empty() -> Self {
+        Self {
+            length: 0,
+            null_count: 0,
+            offset: 0,
+            n_buffers: 0,
+            n_children: 0,
+            buffers: std::ptr::null_mut(),
+            children: std::ptr::null_mut(),
+            dictionary: std::ptr::null_mut(),
+            release: None,
+            private_data: std::ptr::null_mut(),
+        }
+    }
+}
+
+impl Drop for ArrowArray {
+    fn drop(&mut self) {
+        if let Some(release) = self.release {
+            unsafe { release(self) };
+        }
+    }
+}
+
+/// Returns whether the object exports an Arrow C stream, as polars Series and
+/// DataFrames do
+pub(crate) fn is_arrow_stream(obj: &Bound<'_, PyAny>) -> PyResult<bool> {
+    obj.hasattr("__arrow_c_stream__")
+}
+
+/// Reads a numeric Arrow C stream to floats, with nulls as NaN
+///
+/// A table (a stream of structs, e.g. a polars DataFrame) must have a single
+/// column.
+pub(crate) fn read_stream(obj: &Bound<'_, PyAny>) -> PyResult<Vec<f64>> {
+    let capsule = obj.call_method0("__arrow_c_stream__")?;
+    let capsule = capsule.downcast::<PyCapsule>()?;
+    if capsule.name()? != Some(c"arrow_array_stream") {
+        return Err(PyTypeError::new_err(
+            "__arrow_c_stream__ must return an arrow_array_stream capsule",
+        ));
+    }
+    // the capsule keeps ownership of the stream and releases it when dropped
+    let stream = capsule.pointer() as *mut ArrowArrayStream;
+
+    unsafe {
+        let last_error = |stream: *mut ArrowArrayStream| -> PyErr {
+            let message = (*stream)
+                .get_last_error
+                .map(|f| f(stream))
+                .filter(|p| !p.is_null())
+                .map(|p| CStr::from_ptr(p).to_string_lossy().into_owned())
+                .unwrap_or_else(|| "unknown error".to_string());
+            PyValueError::new_err(format!("failed to read Arrow stream: {}", message))
+        };
+
+        let mut schema = ArrowSchema::empty();
+        let get_schema = (*stream)
+            .get_schema
+            .ok_or_else(|| PyValueError::new_err("Arrow stream is released"))?;
+        if get_schema(stream, &mut schema) != 0 {
+            return Err(last_error(stream));
+        }
+        let is_table = CStr::from_ptr(schema.format).to_bytes() == b"+s";
+        let column = if is_table {
+            if schema.n_children != 1 {
+                return Err(PyValueError::new_err("DataFrame must have a single column"));
+            }
+            &**schema.children
+        } else {
+            &schema
+        };
+        let format = CStr::from_ptr(column.format).to_bytes().to_vec();
+
+        let get_next = (*stream)
+            .get_next
+            .ok_or_else(|| PyValueError::new_err("Arrow stream is released"))?;
+        let mut series = Vec::new();
+        loop {
+            let mut array = ArrowArray::empty();
+            if get_next(stream, &mut array) != 0 {
+                return Err(last_error(stream));
+            }
+            // the end of the stream is marked by a released array
+            if array.release.is_none() {
+                break;
+            }
+            // a column of a table is offset by the table's offset too
+            if is_table {
+                let column = &**array.children;
+                let offset = (array.offset + column.offset) as usize;
+                append_array(column, offset, array.length as usize, &format, &mut series)?;
+            } else {
+                append_array(
+                    &array,
+                    array.offset as usize,
+                    array.length as usize,
+                    &format,
+                    &mut series,
+                )?;
+            }
+        }
+        Ok(series)
+    }
+}
+
+// Appends the values of a primitive array, with nulls as NaN
+unsafe fn append_array(
+    array: &ArrowArray,
+    offset: usize,
+    len: usize,
+    format: &[u8],
+    series: &mut Vec<f64>,
+) -> PyResult<()> {
+    if len == 0 {
+        return Ok(());
+    }
+    if array.n_buffers < 2 {
+        return Err(PyTypeError::new_err(
+            "series must be a primitive Arrow array",
+        ));
+    }
+    let validity = unsafe { *array.buffers } as *const u8;
+    let data = unsafe { *array.buffers.add(1) };
+
+    macro_rules! read {
+        ($t:ty) => {{
+            let values =
+                unsafe { std::slice::from_raw_parts((data as *const $t).add(offset), len) };
+            series.extend(values.iter().map(|&v| v as f64));
+        }};
+    }
+    match format {
+        b"g" => read!(f64),
+        b"f" => read!(f32),
+        b"l" => read!(i64),
+        b"i" => read!(i32),
+        b"s" => read!(i16),
+        b"c" => read!(i8),
+        b"L" => read!(u64),
+        b"I" => read!(u32),
+        b"S" => read!(u16),
+        b"C" => read!(u8),
+        _ => {
+            return Err(PyTypeError::new_err(format!(
+                "series must be numeric, found Arrow format '{}'",
+                String::from_utf8_lossy(format)
+            )));
+        }
+    }
+
+    if !validity.is_null() && array.null_count != 0 {
+        let start = series.len() - len;
+        for (i, v) in series[start..].iter_mut().enumerate() {
+            let bit = offset + i;
+            if unsafe { *validity.add(bit / 8) } >> (bit % 8) & 1 == 0 {
+                *v = f64::NAN;
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,183 @@
+use std::env;
+use std::process;
+
+use axum::extract::Json;
+use axum::http::StatusCode;
+use axum::routing::post;
+use axum::{Router, serve};
+use serde::{Deserialize, Serialize};
+use stl_rust::{StlParams, StlResult};
+use tokio::net::TcpListener;
+use tokio::task;
+
+const USAGE: &str = "usage:
+  stl-serve [--addr HOST:PORT]
+
+endpoints (JSON in and out, null for missing values):
+  POST /decompose  {\"series\": [[...], ...], \"period\": N, \"robust\": false}
+  POST /anomalies  {\"series\": [[...], ...], \"period\": N, \"robust\": false, \"alpha\": 0.05}";
+
+#[derive(Deserialize)]
+struct Request {
+    series: Vec<Vec<Option<f64>>>,
+    period: usize,
+    #[serde(default)]
+    robust: bool,
+    #[serde(default = "default_alpha")]
+    alpha: f64,
+}
+
+fn default_alpha() -> f64 {
+    0.05
+}
+
+#[derive(Serialize)]
+struct Decomposition {
+    seasonal: Vec<f64>,
+    trend: Vec<f64>,
+    remainder: Vec<f64>,
+    weights: Vec<f64>,
+    seasonal_strength: f64,
+    trend_strength: f64,
+}
+
+#[derive(Serialize)]
+struct Anomaly {
+    index: usize,
+    observed: f64,
+    expected: f64,
+    z_score: f64,
+    direction: &'static str,
+}
+
+#[derive(Serialize)]
+struct Response<T> {
+    results: Vec<T>,
+}
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+type Reply<T> = Result<Json<Response<T>>, (StatusCode, Json<ErrorResponse>)>;
+
+fn bad_request(error: String) -> (StatusCode, Json<ErrorResponse>) {
+    (StatusCode::BAD_REQUEST, Json(ErrorResponse { error }))
+}
+
+// Fits every series of the request with the batch engine, off the async
+// runtime since the fits are CPU-bound
+async fn fit(
+    request: Request,
+) -> Result<(Request, Vec<StlResult>), (StatusCode, Json<ErrorResponse>)> {
+    task::spawn_blocking(move || {
+        if !(request.alpha > 0.0 && request.alpha < 1.0) {
+            return Err(bad_request("alpha must be between 0 and 1".into()));
+        }
+        let series = request
+            .series
+            .iter()
+            .map(|s| s.iter().map(|v| v.unwrap_or(f64::NAN)).collect())
+            .collect::<Vec<Vec<f64>>>();
+        let results = StlParams::new()
+            .robust(request.robust)
+            .fit_batch(&series, request.period)
+            .map_err(|e| bad_request(e.to_string()))?;
+        Ok((request, results))
+    })
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: e.to_string(),
+            }),
+        )
+    })?
+}
+
+async fn decompose(Json(request): Json<Request>) -> Reply<Decomposition> {
+    let (_, results) = fit(request).await?;
+    let results = results
+        .into_iter()
+        .map(|result| {
+            let seasonal_strength = result.seasonal_strength();
+            let trend_strength = result.trend_strength();
+            let (seasonal, trend, remainder, weights) = result.into_parts();
+            Decomposition {
+                seasonal,
+                trend,
+                remainder,
+                weights,
+                seasonal_strength,
+                trend_strength,
+            }
+        })
+        .collect();
+    Ok(Json(Response { results }))
+}
+
+async fn anomalies(Json(request): Json<Request>) -> Reply<Vec<Anomaly>> {
+    let (request, results) = fit(request).await?;
+    let results = results
+        .iter()
+        .map(|result| {
+            result
+                .anomalies(request.alpha)
+                .iter()
+                .map(|anomaly| Anomaly {
+                    index: anomaly.index(),
+                    observed: anomaly.observed(),
+                    expected: anomaly.expected(),
+                    z_score: anomaly.z_score(),
+                    direction: if anomaly.is_high() { "high" } else { "low" },
+                })
+                .collect()
+        })
+        .collect();
+    Ok(Json(Response { results }))
+}
+
+fn parse_args() -> Result<String, String> {
+    let mut args = env::args().skip(1);
+    let mut addr = "127.0.0.1:8080".to_string();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--addr" => addr = args.next().ok_or("--addr requires a value")?,
+            "-h" | "--help" => return Err(String::new()),
+            _ => return Err(format!("unknown argument: {arg}")),
+        }
+    }
+    Ok(addr)
+}
+
+#[tokio::main]
+async fn main() {
+    let addr = match parse_args() {
+        Ok(addr) => addr,
+        Err(message) => {
+            if !message.is_empty() {
+                eprintln!("error: {message}");
+            }
+            eprintln!("{USAGE}");
+            process::exit(2);
+        }
+    };
+
+    let app = Router::new()
+        .route("/decompose", post(decompose))
+        .route("/anomalies", post(anomalies));
+    let listener = match TcpListener::bind(&addr).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("error: {addr}: {e}");
+            process::exit(1);
+        }
+    };
+    eprintln!("listening on {addr}");
+    if let Err(e) = serve(listener, app).await {
+        eprintln!("error: {e}");
+        process::exit(1);
+    }
+}
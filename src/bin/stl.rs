@@ -0,0 +1,416 @@
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, ErrorKind, Write};
+use std::path::Path;
+use std::process;
+
+use plotters::prelude::*;
+use rayon::prelude::*;
+use stl_rust::{SlidingStl, Stl, StlParams, StlResult};
+
+const USAGE: &str = "usage:
+  stl decompose --period N [--robust] input.csv
+  stl anomalies --period N [--alpha A] input.csv
+  stl plot --period N [--robust] [-o output.svg] input.csv
+  stl stream --period N [--window W] [--robust] < values
+  stl batch --period N [--robust] [--alpha A] [-o DIR] input.csv|'*.csv'...";
+
+struct Args {
+    command: String,
+    inputs: Vec<String>,
+    output: Option<String>,
+    period: usize,
+    window: Option<usize>,
+    alpha: f64,
+    robust: bool,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = env::args().skip(1);
+    let command = args.next().ok_or("missing command")?;
+    let mut inputs = Vec::new();
+    let mut output = None;
+    let mut period = None;
+    let mut window = None;
+    let mut alpha = 0.05;
+    let mut robust = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--period" => {
+                let value = args.next().ok_or("--period requires a value")?;
+                period = Some(value.parse().map_err(|_| "invalid period")?);
+            }
+            "--window" => {
+                let value = args.next().ok_or("--window requires a value")?;
+                window = Some(value.parse().map_err(|_| "invalid window")?);
+            }
+            "--alpha" => {
+                let value = args.next().ok_or("--alpha requires a value")?;
+                alpha = value.parse().map_err(|_| "invalid alpha")?;
+            }
+            "-o" | "--output" => {
+                output = Some(args.next().ok_or("--output requires a value")?);
+            }
+            "--robust" => robust = true,
+            "-h" | "--help" => return Err(String::new()),
+            _ if arg.starts_with('-') => return Err(format!("unknown option: {arg}")),
+            _ => inputs.push(arg),
+        }
+    }
+
+    if !(alpha > 0.0 && alpha < 1.0) {
+        return Err("alpha must be between 0 and 1".into());
+    }
+
+    // the stream command reads stdin instead of a file, and the batch
+    // command reads any number of files
+    match command.as_str() {
+        "stream" if !inputs.is_empty() => return Err("stream reads from stdin".into()),
+        "stream" => {}
+        _ if inputs.is_empty() => return Err("missing input file".into()),
+        "batch" => {}
+        _ if inputs.len() > 1 => return Err("expected a single input file".into()),
+        _ => {}
+    }
+
+    Ok(Args {
+        command,
+        inputs,
+        output,
+        period: period.ok_or("missing --period")?,
+        window,
+        alpha,
+        robust,
+    })
+}
+
+// Parses a value, with empty fields and "nan" as missing
+fn parse_value(field: &str) -> Option<f64> {
+    if field.is_empty() || field.eq_ignore_ascii_case("nan") {
+        Some(f64::NAN)
+    } else {
+        field.parse().ok()
+    }
+}
+
+// Reads the first column as the timestamp and the last column as the value,
+// skipping rows whose value does not parse (such as a header)
+fn read_csv(path: &str) -> Result<(Vec<String>, Vec<f64>), String> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+    let mut timestamps = Vec::new();
+    let mut values = Vec::new();
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let fields = line.split(',').map(str::trim).collect::<Vec<&str>>();
+        let value = match parse_value(fields[fields.len() - 1]) {
+            Some(v) => v,
+            None => continue,
+        };
+        let timestamp = if fields.len() > 1 {
+            fields[0].to_string()
+        } else {
+            values.len().to_string()
+        };
+        timestamps.push(timestamp);
+        values.push(value);
+    }
+    Ok((timestamps, values))
+}
+
+// A series read from one column of a file
+struct Column {
+    name: String,
+    timestamps: Vec<String>,
+    values: Vec<f64>,
+}
+
+// Reads every column of a CSV as a series, except that the first column is
+// the timestamp when there are several. A first row that doesn't parse is the
+// header and names the columns, and other rows that don't parse are skipped.
+fn read_columns(path: &str) -> Result<Vec<Column>, String> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+    let stem = Path::new(path)
+        .file_stem()
+        .map_or("series".into(), |s| s.to_string_lossy().into_owned());
+    let rows = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| line.split(',').map(str::trim).collect::<Vec<&str>>())
+        .collect::<Vec<Vec<&str>>>();
+    let Some(width) = rows.first().map(Vec::len) else {
+        return Ok(Vec::new());
+    };
+    let first = usize::from(width > 1);
+
+    let header = rows[0][first..].iter().any(|f| parse_value(f).is_none());
+    let mut columns = (first..width)
+        .map(|j| Column {
+            name: match (header, width - first) {
+                (true, _) => format!("{stem}_{}", rows[0][j]),
+                (false, 1) => stem.clone(),
+                (false, _) => format!("{stem}_{j}"),
+            },
+            timestamps: Vec::new(),
+            values: Vec::new(),
+        })
+        .collect::<Vec<Column>>();
+    for row in &rows[usize::from(header)..] {
+        if row.len() != width {
+            continue;
+        }
+        let Some(values) = row[first..]
+            .iter()
+            .map(|f| parse_value(f))
+            .collect::<Option<Vec<f64>>>()
+        else {
+            continue;
+        };
+        for (column, value) in columns.iter_mut().zip(values) {
+            let timestamp = if first == 1 {
+                row[0].to_string()
+            } else {
+                column.values.len().to_string()
+            };
+            column.timestamps.push(timestamp);
+            column.values.push(value);
+        }
+    }
+    Ok(columns)
+}
+
+// Expands glob patterns, keeping arguments that match nothing so that a
+// missing file is reported by name
+fn expand_inputs(inputs: &[String]) -> Result<Vec<String>, String> {
+    let mut paths = Vec::new();
+    for input in inputs {
+        let matches = glob::glob(input)
+            .map_err(|e| format!("{input}: {e}"))?
+            .map(|path| path.map(|p| p.to_string_lossy().into_owned()))
+            .collect::<Result<Vec<String>, _>>()
+            .map_err(|e| e.to_string())?;
+        if matches.is_empty() {
+            paths.push(input.clone());
+        } else {
+            paths.extend(matches);
+        }
+    }
+    Ok(paths)
+}
+
+// Decomposes every column of every input on the rayon pool, writing each
+// decomposition to its own file in the output directory and a summary table
+// of strengths and anomaly counts to stdout
+fn batch(args: &Args) -> Result<(), String> {
+    let dir = Path::new(args.output.as_deref().unwrap_or("decomposed"));
+    fs::create_dir_all(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+    let mut columns = Vec::new();
+    for path in expand_inputs(&args.inputs)? {
+        columns.extend(read_columns(&path)?);
+    }
+
+    let mut params = StlParams::new();
+    params.robust(args.robust);
+    let summaries = columns
+        .par_iter()
+        .map(|column| {
+            let result = params
+                .fit(&column.values, args.period)
+                .map_err(|e| format!("{}: {e}", column.name))?;
+
+            let mut contents = String::from("timestamp,observed,seasonal,trend,remainder\n");
+            for (i, timestamp) in column.timestamps.iter().enumerate() {
+                contents.push_str(&format!(
+                    "{},{},{},{},{}\n",
+                    timestamp,
+                    column.values[i],
+                    result.seasonal()[i],
+                    result.trend()[i],
+                    result.remainder()[i]
+                ));
+            }
+            let path = dir.join(format!("{}.csv", column.name));
+            fs::write(&path, contents).map_err(|e| format!("{}: {e}", path.display()))?;
+
+            Ok(format!(
+                "{},{},{:.4},{:.4},{}",
+                column.name,
+                column.values.len(),
+                result.seasonal_strength(),
+                result.trend_strength(),
+                result.anomalies(args.alpha).len()
+            ))
+        })
+        .collect::<Result<Vec<String>, String>>()?;
+
+    println!("series,nobs,seasonal_strength,trend_strength,anomalies");
+    for summary in summaries {
+        println!("{summary}");
+    }
+    Ok(())
+}
+
+// Draws the observed series and the three components as stacked panels
+fn plot(path: &str, series: &[f64], result: &StlResult) -> Result<(), String> {
+    let root = SVGBackend::new(path, (1000, 800)).into_drawing_area();
+    root.fill(&WHITE).map_err(|e| e.to_string())?;
+    let panels = root.split_evenly((4, 1));
+    let components = [
+        ("observed", series),
+        ("trend", result.trend()),
+        ("seasonal", result.seasonal()),
+        ("remainder", result.remainder()),
+    ];
+
+    for (area, (name, values)) in panels.iter().zip(components) {
+        let finite = values.iter().copied().filter(|v| v.is_finite());
+        let (lo, hi) = finite.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+            (lo.min(v), hi.max(v))
+        });
+        let (lo, hi) = if lo < hi {
+            (lo, hi)
+        } else {
+            (lo - 1.0, lo + 1.0)
+        };
+
+        let mut chart = ChartBuilder::on(area)
+            .caption(name, ("sans-serif", 16))
+            .margin(8)
+            .x_label_area_size(24)
+            .y_label_area_size(48)
+            .build_cartesian_2d(0..values.len(), lo..hi)
+            .map_err(|e| e.to_string())?;
+        chart
+            .configure_mesh()
+            .disable_mesh()
+            .draw()
+            .map_err(|e| e.to_string())?;
+        chart
+            .draw_series(LineSeries::new(
+                values
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, v)| v.is_finite())
+                    .map(|(i, v)| (i, *v)),
+                &BLUE,
+            ))
+            .map_err(|e| e.to_string())?;
+    }
+
+    root.present().map_err(|e| e.to_string())
+}
+
+// Decomposes a sliding window of the values read so far after each line of
+// stdin, writing the components of the newest value as soon as it arrives.
+// Lines that don't parse are skipped, and the components are left empty
+// until the window holds two periods.
+fn stream(args: &Args) -> Result<(), String> {
+    let window = args.window.unwrap_or(4 * args.period);
+    let mut params = StlParams::new();
+    params.robust(args.robust);
+    let mut sliding =
+        SlidingStl::with_params(window, args.period, params).map_err(|e| e.to_string())?;
+
+    let mut stdout = io::stdout().lock();
+    let mut write = |line: String| match writeln!(stdout, "{line}").and_then(|_| stdout.flush()) {
+        // a closed pipe, as when piping into head, ends the stream quietly
+        Err(e) if e.kind() == ErrorKind::BrokenPipe => Ok(false),
+        Err(e) => Err(e.to_string()),
+        Ok(()) => Ok(true),
+    };
+
+    write("observed,seasonal,trend,remainder".into())?;
+    for line in io::stdin().lock().lines() {
+        let line = line.map_err(|e| e.to_string())?;
+        let Some(value) = line
+            .split(',')
+            .next_back()
+            .and_then(|f| parse_value(f.trim()))
+        else {
+            continue;
+        };
+        sliding.push(value);
+
+        let row = if sliding.len() >= 2 * args.period {
+            let result = sliding.current().map_err(|e| e.to_string())?;
+            let last = sliding.len() - 1;
+            format!(
+                "{},{},{},{}",
+                value,
+                result.seasonal()[last],
+                result.trend()[last],
+                result.remainder()[last]
+            )
+        } else {
+            format!("{value},,,")
+        };
+        if !write(row)? {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn run(args: Args) -> Result<(), String> {
+    if args.command == "stream" {
+        return stream(&args);
+    }
+    if args.command == "batch" {
+        return batch(&args);
+    }
+    let (timestamps, series) = read_csv(&args.inputs[0])?;
+    let result = Stl::params()
+        .robust(args.robust)
+        .fit(&series, args.period)
+        .map_err(|e| e.to_string())?;
+
+    match args.command.as_str() {
+        "decompose" => {
+            println!("timestamp,observed,seasonal,trend,remainder");
+            for (i, timestamp) in timestamps.iter().enumerate() {
+                println!(
+                    "{},{},{},{},{}",
+                    timestamp,
+                    series[i],
+                    result.seasonal()[i],
+                    result.trend()[i],
+                    result.remainder()[i]
+                );
+            }
+        }
+        "anomalies" => {
+            println!("timestamp,observed,expected,z_score,direction");
+            for anomaly in result.anomalies(args.alpha) {
+                println!(
+                    "{},{},{},{:.3},{}",
+                    timestamps[anomaly.index()],
+                    anomaly.observed(),
+                    anomaly.expected(),
+                    anomaly.z_score(),
+                    if anomaly.is_high() { "high" } else { "low" }
+                );
+            }
+        }
+        "plot" => {
+            let output = args.output.as_deref().unwrap_or("decomposition.svg");
+            plot(output, &series, &result)?
+        }
+        command => return Err(format!("unknown command: {command}")),
+    }
+    Ok(())
+}
+
+fn main() {
+    let result = parse_args().and_then(run);
+    if let Err(message) = result {
+        if !message.is_empty() {
+            eprintln!("error: {message}");
+        }
+        eprintln!("{USAGE}");
+        process::exit(2);
+    }
+}
@@ -0,0 +1,60 @@
+use super::minimize::golden_section;
+
+/// Applies the Box-Cox transformation.
+///
+/// A `lambda` of zero is the natural log.
+pub fn boxcox(series: &[f64], lambda: f64) -> Vec<f64> {
+    if lambda != 0.0 {
+        series
+            .iter()
+            .map(|yi| (yi.powf(lambda) - 1.0) / lambda)
+            .collect()
+    } else {
+        series.iter().map(|yi| yi.ln()).collect()
+    }
+}
+
+/// Reverses the Box-Cox transformation.
+pub fn inv_boxcox(series: &[f64], lambda: f64) -> Vec<f64> {
+    if lambda != 0.0 {
+        series
+            .iter()
+            .map(|yi| (lambda * yi + 1.0).powf(1.0 / lambda))
+            .collect()
+    } else {
+        series.iter().map(|yi| yi.exp()).collect()
+    }
+}
+
+// Coefficient of variation of sd / mean^(1 - lambda) across
+// non-overlapping periods, as in the forecast package
+fn guerrero_cv(x: &[f64], lambda: f64, period: usize) -> f64 {
+    let nyr = x.len() / period;
+    let start = x.len() - nyr * period;
+    let rat = x[start..]
+        .chunks(period)
+        .map(|c| {
+            let values = c
+                .iter()
+                .copied()
+                .filter(|v| !v.is_nan())
+                .collect::<Vec<f64>>();
+            let m = values.iter().sum::<f64>() / values.len() as f64;
+            let sd = (values.iter().map(|v| (v - m).powi(2)).sum::<f64>()
+                / (values.len() as f64 - 1.0))
+                .sqrt();
+            sd / m.powf(1.0 - lambda)
+        })
+        .collect::<Vec<f64>>();
+    let m = rat.iter().sum::<f64>() / rat.len() as f64;
+    let sd = (rat.iter().map(|v| (v - m).powi(2)).sum::<f64>() / (rat.len() as f64 - 1.0)).sqrt();
+    sd / m
+}
+
+/// Estimates the Box-Cox lambda with Guerrero's method.
+///
+/// The coefficient of variation is minimized over `[0, 1]` with golden-section search.
+pub fn boxcox_lambda_guerrero(series: &[f64], period: usize) -> f64 {
+    let period = period.max(2);
+    golden_section(|lambda| guerrero_cv(series, lambda, period), 1e-5)
+}
@@ -0,0 +1,159 @@
+//! An in-process cache of STL results, keyed by content.
+//!
+//! When enabled, [`StlParams::fit`](crate::StlParams::fit) serves repeated
+//! fits of the same data, parameters, and period from memory. Fits with
+//! custom smoothers are never cached. The cache is disabled by default.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::StlResult;
+
+// The full content of a fit, compared on every hit so that hash collisions
+// can't serve another fit's result
+#[derive(Hash, PartialEq, Eq)]
+pub(crate) struct Key {
+    series: Vec<u64>,
+    params: String,
+    period: usize,
+}
+
+struct Entry {
+    result: StlResult,
+    inserted: Instant,
+    last_used: u64,
+}
+
+struct Cache {
+    capacity: usize,
+    ttl: Option<Duration>,
+    entries: HashMap<Arc<Key>, Entry>,
+    // keys in order of use, with the clock of the use; a key is queued again
+    // on every use, and earlier uses are skipped when evicting
+    order: VecDeque<(Arc<Key>, u64)>,
+    clock: u64,
+}
+
+static CACHE: Mutex<Option<Cache>> = Mutex::new(None);
+
+fn with_cache<R, F: FnOnce(&mut Cache) -> R>(f: F) -> R {
+    f(CACHE.lock().unwrap().get_or_insert_with(|| Cache {
+        capacity: 0,
+        ttl: None,
+        entries: HashMap::new(),
+        order: VecDeque::new(),
+        clock: 0,
+    }))
+}
+
+/// Sets the maximum number of cached results, and how long a result stays
+/// valid after it's computed.
+///
+/// When full, the least recently used result is evicted. A capacity of zero
+/// disables the cache and drops all results.
+pub fn configure(capacity: usize, ttl: Option<Duration>) {
+    with_cache(|cache| {
+        cache.capacity = capacity;
+        cache.ttl = ttl;
+        while cache.entries.len() > capacity {
+            cache.evict();
+        }
+    })
+}
+
+/// Drops all cached results.
+pub fn clear() {
+    with_cache(|cache| {
+        cache.entries.clear();
+        cache.order.clear();
+    });
+}
+
+/// Returns the number of cached results.
+pub fn len() -> usize {
+    with_cache(|cache| cache.entries.len())
+}
+
+/// Returns whether no results are cached.
+pub fn is_empty() -> bool {
+    len() == 0
+}
+
+impl Cache {
+    fn is_current(&self, key: &Key, clock: u64) -> bool {
+        self.entries.get(key).is_some_and(|e| e.last_used == clock)
+    }
+
+    fn evict(&mut self) {
+        while let Some((key, clock)) = self.order.pop_front() {
+            if self.is_current(&key, clock) {
+                self.entries.remove(&key);
+                return;
+            }
+        }
+    }
+
+    // Marks a use of the entry at the key
+    fn touch(&mut self, key: &Arc<Key>) {
+        self.clock += 1;
+        if let Some(entry) = self.entries.get_mut(key) {
+            entry.last_used = self.clock;
+        }
+        self.order.push_back((key.clone(), self.clock));
+        // drop earlier uses once they outnumber the entries, which keeps
+        // the amortized cost of a use constant
+        if self.order.len() > 2 * self.entries.len() + 16 {
+            let order = std::mem::take(&mut self.order);
+            self.order = order
+                .into_iter()
+                .filter(|(k, c)| self.is_current(k, *c))
+                .collect();
+        }
+    }
+}
+
+pub(crate) fn enabled() -> bool {
+    with_cache(|cache| cache.capacity > 0)
+}
+
+// Keys the series by bit pattern, so NaN and signed zeros are distinct keys
+pub(crate) fn key(series: &[f64], params: &str, period: usize) -> Key {
+    Key {
+        series: series.iter().map(|v| v.to_bits()).collect(),
+        params: params.to_string(),
+        period,
+    }
+}
+
+pub(crate) fn get(key: &Key) -> Option<StlResult> {
+    with_cache(|cache| {
+        let (stored, entry) = cache.entries.get_key_value(key)?;
+        if cache.ttl.is_some_and(|ttl| entry.inserted.elapsed() > ttl) {
+            cache.entries.remove(key);
+            return None;
+        }
+        let stored = stored.clone();
+        cache.touch(&stored);
+        Some(cache.entries[&stored].result.clone())
+    })
+}
+
+pub(crate) fn insert(key: Key, result: &StlResult) {
+    with_cache(|cache| {
+        if cache.capacity == 0 {
+            return;
+        }
+        if !cache.entries.contains_key(&key) && cache.entries.len() >= cache.capacity {
+            cache.evict();
+        }
+        let key = Arc::new(key);
+        let entry = Entry {
+            result: result.clone(),
+            inserted: Instant::now(),
+            last_used: 0,
+        };
+        cache.entries.insert(key.clone(), entry);
+        cache.touch(&key);
+    })
+}
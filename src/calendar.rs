@@ -0,0 +1,92 @@
+const WEEKDAYS: [&str; 6] = ["mon", "tue", "wed", "thu", "fri", "sat"];
+
+// Days since 1970-01-01 in the proleptic Gregorian calendar
+pub(crate) fn days_from_civil(year: i32, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year } as i64;
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let m = month as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+pub(crate) fn days_in_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = month_at(year, month, 1);
+    (days_from_civil(next_year, next_month, 1) - days_from_civil(year, month, 1)) as u32
+}
+
+// Calendar month and year of observation t, counting months from the start
+fn month_at(start_year: i32, start_month: u32, months: usize) -> (i32, u32) {
+    let m = start_month as i64 - 1 + months as i64;
+    (
+        start_year + m.div_euclid(12) as i32,
+        m.rem_euclid(12) as u32 + 1,
+    )
+}
+
+// Trading-day contrasts for monthly (period 12) or quarterly (period 4) data:
+// the number of each weekday from Monday to Saturday minus the number of Sundays
+pub(crate) fn trading_day_regressors(
+    start_year: i32,
+    start_month: u32,
+    period: usize,
+    len: usize,
+) -> Vec<(String, Vec<f64>)> {
+    let step = 12 / period;
+    let mut columns = vec![Vec::new(); 6];
+    for t in 0..len {
+        let (y0, m0) = month_at(start_year, start_month, t * step);
+        let (y1, m1) = month_at(start_year, start_month, (t + 1) * step);
+        let first = days_from_civil(y0, m0, 1);
+        let last = days_from_civil(y1, m1, 1);
+
+        let mut counts = [0.0; 7];
+        for day in first..last {
+            // 1970-01-01 was a Thursday; index 0 is Monday
+            counts[(day + 3).rem_euclid(7) as usize] += 1.0;
+        }
+        for (d, column) in columns.iter_mut().enumerate() {
+            column.push(counts[d] - counts[6]);
+        }
+    }
+    WEEKDAYS
+        .iter()
+        .map(|d| d.to_string())
+        .zip(columns)
+        .collect()
+}
+
+// Number of the given dates falling in each monthly (period 12) or quarterly
+// (period 4) observation, less its mean at that phase of the cycle so the
+// average holiday effect stays with the seasonal component
+pub(crate) fn holiday_regressor(
+    dates: &[(i32, u32, u32)],
+    start_year: i32,
+    start_month: u32,
+    period: usize,
+    len: usize,
+) -> Vec<f64> {
+    let step = 12 / period as i64;
+    let mut column = vec![0.0; len];
+    for &(year, month, _) in dates {
+        let months = (year - start_year) as i64 * 12 + month as i64 - start_month as i64;
+        if months < 0 {
+            continue;
+        }
+        if let Some(v) = column.get_mut((months / step) as usize) {
+            *v += 1.0;
+        }
+    }
+
+    let mut means = vec![(0.0, 0); period];
+    for (t, v) in column.iter().enumerate() {
+        means[t % period].0 += v;
+        means[t % period].1 += 1;
+    }
+    for (t, v) in column.iter_mut().enumerate() {
+        let (sum, count) = means[t % period];
+        *v -= sum / count as f64;
+    }
+    column
+}
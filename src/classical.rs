@@ -0,0 +1,158 @@
+// Classical decomposition by moving averages, following statsmodels'
+// seasonal_decompose with two-sided filters and trend extrapolation.
+
+use super::{Error, FitReport, StlResult};
+
+/// Whether the components add or multiply to form the series.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum DecompositionModel {
+    /// The series is the sum of the components.
+    #[default]
+    Additive,
+    /// The series is the product of the components.
+    Multiplicative,
+}
+
+/// Decomposes a series with centered moving averages, like statsmodels'
+/// `seasonal_decompose`.
+///
+/// This is much faster than STL and serves as a baseline. The seasonal
+/// component is the same in every cycle, and for the multiplicative model the
+/// seasonal component and remainder are factors around 1.
+pub fn classical_decompose(
+    series: &[f64],
+    period: usize,
+    model: DecompositionModel,
+) -> Result<StlResult, Error> {
+    let n = series.len();
+    if period < 2 {
+        return Err(Error::Parameter("period must be at least 2".to_string()));
+    }
+    if n < period * 2 {
+        return Err(Error::Series(
+            "series has less than two periods".to_string(),
+        ));
+    }
+    if series.iter().any(|v| v.is_nan()) {
+        return Err(Error::Series(
+            "classical decomposition does not support missing values".to_string(),
+        ));
+    }
+    let multiplicative = model == DecompositionModel::Multiplicative;
+    if multiplicative && series.iter().any(|&v| v <= 0.0) {
+        return Err(Error::Series(
+            "multiplicative seasonality is not appropriate for zero and negative values"
+                .to_string(),
+        ));
+    }
+
+    let (seasonal, trend, remainder) = classical(series, period, multiplicative);
+    Ok(StlResult {
+        seasonal,
+        trend,
+        remainder,
+        weights: vec![1.0; n],
+        period,
+        // the seasonal averages span every cycle
+        seasonal_length: n | 1,
+        seasonal_degree: 0,
+        trend_length: period | 1,
+        trend_degree: 0,
+        partial_cycle: n % period,
+        regression: None,
+        report: FitReport::default(),
+        residual_seasonality: None,
+        clipped: Vec::new(),
+        params: None,
+    })
+}
+
+// centered moving average; even periods use a 2 x period filter
+pub(crate) fn cma(y: &[f64], np: usize) -> Vec<f64> {
+    let n = y.len();
+    let half = np / 2;
+    let mut trend = vec![f64::NAN; n];
+    for (i, t) in trend.iter_mut().enumerate().take(n - half).skip(half) {
+        *t = if np % 2 == 1 {
+            y[i - half..=i + half].iter().sum::<f64>() / np as f64
+        } else {
+            let inner = y[i - half + 1..i + half].iter().sum::<f64>();
+            (inner + 0.5 * (y[i - half] + y[i + half])) / np as f64
+        };
+    }
+    trend
+}
+
+// least squares line through the npoints values at the given indices
+fn line(trend: &[f64], idx: impl Iterator<Item = usize> + Clone) -> (f64, f64) {
+    let m = idx.clone().count() as f64;
+    let mx = idx.clone().map(|i| i as f64).sum::<f64>() / m;
+    let my = idx.clone().map(|i| trend[i]).sum::<f64>() / m;
+    let sxy = idx
+        .clone()
+        .map(|i| (i as f64 - mx) * (trend[i] - my))
+        .sum::<f64>();
+    let sxx = idx.map(|i| (i as f64 - mx).powi(2)).sum::<f64>();
+    let b = if sxx > 0.0 { sxy / sxx } else { 0.0 };
+    (my - b * mx, b)
+}
+
+pub(crate) fn extrapolate(trend: &mut [f64], half: usize, npoints: usize) {
+    let n = trend.len();
+    let (a, b) = line(trend, half..half + npoints);
+    for (i, t) in trend.iter_mut().enumerate().take(half) {
+        *t = a + b * i as f64;
+    }
+    let (a, b) = line(trend, n - half - npoints..n - half);
+    for (i, t) in trend.iter_mut().enumerate().skip(n - half) {
+        *t = a + b * i as f64;
+    }
+}
+
+pub(crate) fn classical(
+    y: &[f64],
+    np: usize,
+    multiplicative: bool,
+) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+    let n = y.len();
+    let mut trend = cma(y, np);
+    extrapolate(&mut trend, np / 2, np.min(n - 2 * (np / 2)));
+
+    let detrended = y
+        .iter()
+        .zip(&trend)
+        .map(|(v, t)| if multiplicative { v / t } else { v - t })
+        .collect::<Vec<f64>>();
+
+    let mut averages = vec![0.0; np];
+    for (j, avg) in averages.iter_mut().enumerate() {
+        let values = detrended
+            .iter()
+            .skip(j)
+            .step_by(np)
+            .filter(|v| !v.is_nan())
+            .collect::<Vec<&f64>>();
+        *avg = values.iter().copied().sum::<f64>() / values.len() as f64;
+    }
+    let mean = averages.iter().sum::<f64>() / np as f64;
+    for avg in averages.iter_mut() {
+        if multiplicative {
+            *avg /= mean;
+        } else {
+            *avg -= mean;
+        }
+    }
+
+    let seasonal = (0..n).map(|i| averages[i % np]).collect::<Vec<f64>>();
+    let remainder = (0..n)
+        .map(|i| {
+            if multiplicative {
+                y[i] / seasonal[i] / trend[i]
+            } else {
+                y[i] - seasonal[i] - trend[i]
+            }
+        })
+        .collect::<Vec<f64>>();
+
+    (seasonal, trend, remainder)
+}
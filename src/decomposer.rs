@@ -0,0 +1,129 @@
+use super::{Error, Mstl, MstlParams, MstlResult, Stl, StlParams, StlResult, X11, X11Params};
+
+/// A seasonal-trend decomposition method.
+pub trait Decomposer {
+    /// The result of a decomposition.
+    type Output: Decomposition;
+
+    /// Decomposes a time series with the given seasonal periods.
+    fn decompose(&self, series: &[f64], periods: &[usize]) -> Result<Self::Output, Error>;
+}
+
+/// The components of a seasonal-trend decomposition.
+pub trait Decomposition {
+    /// Returns the seasonal components, one per period.
+    fn seasonal_components(&self) -> Vec<&[f64]>;
+
+    /// Returns the trend component.
+    fn trend(&self) -> &[f64];
+
+    /// Returns the remainder.
+    fn remainder(&self) -> &[f64];
+
+    /// Returns the seasonal strength of each seasonal component.
+    fn seasonal_strengths(&self) -> Vec<f64>;
+
+    /// Returns the trend strength.
+    fn trend_strength(&self) -> f64;
+}
+
+fn single_period(periods: &[usize]) -> Result<usize, Error> {
+    match periods {
+        [period] => Ok(*period),
+        _ => Err(Error::Parameter(
+            "stl requires exactly one period".to_string(),
+        )),
+    }
+}
+
+impl Decomposer for Stl {
+    type Output = StlResult;
+
+    fn decompose(&self, series: &[f64], periods: &[usize]) -> Result<StlResult, Error> {
+        Stl::fit(series, single_period(periods)?)
+    }
+}
+
+impl Decomposer for StlParams {
+    type Output = StlResult;
+
+    fn decompose(&self, series: &[f64], periods: &[usize]) -> Result<StlResult, Error> {
+        self.fit(series, single_period(periods)?)
+    }
+}
+
+impl Decomposer for X11 {
+    type Output = StlResult;
+
+    fn decompose(&self, series: &[f64], periods: &[usize]) -> Result<StlResult, Error> {
+        X11::fit(series, single_period(periods)?)
+    }
+}
+
+impl Decomposer for X11Params {
+    type Output = StlResult;
+
+    fn decompose(&self, series: &[f64], periods: &[usize]) -> Result<StlResult, Error> {
+        self.fit(series, single_period(periods)?)
+    }
+}
+
+impl Decomposer for Mstl {
+    type Output = MstlResult;
+
+    fn decompose(&self, series: &[f64], periods: &[usize]) -> Result<MstlResult, Error> {
+        Mstl::fit(series, periods)
+    }
+}
+
+impl Decomposer for MstlParams {
+    type Output = MstlResult;
+
+    fn decompose(&self, series: &[f64], periods: &[usize]) -> Result<MstlResult, Error> {
+        self.fit(series, periods)
+    }
+}
+
+impl Decomposition for StlResult {
+    fn seasonal_components(&self) -> Vec<&[f64]> {
+        vec![self.seasonal()]
+    }
+
+    fn trend(&self) -> &[f64] {
+        StlResult::trend(self)
+    }
+
+    fn remainder(&self) -> &[f64] {
+        StlResult::remainder(self)
+    }
+
+    fn seasonal_strengths(&self) -> Vec<f64> {
+        vec![self.seasonal_strength()]
+    }
+
+    fn trend_strength(&self) -> f64 {
+        StlResult::trend_strength(self)
+    }
+}
+
+impl Decomposition for MstlResult {
+    fn seasonal_components(&self) -> Vec<&[f64]> {
+        self.seasonal().iter().map(|s| s.as_slice()).collect()
+    }
+
+    fn trend(&self) -> &[f64] {
+        MstlResult::trend(self)
+    }
+
+    fn remainder(&self) -> &[f64] {
+        MstlResult::remainder(self)
+    }
+
+    fn seasonal_strengths(&self) -> Vec<f64> {
+        self.seasonal_strength()
+    }
+
+    fn trend_strength(&self) -> f64 {
+        MstlResult::trend_strength(self)
+    }
+}
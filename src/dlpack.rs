@@ -0,0 +1,223 @@
+use pyo3::exceptions::PyBufferError;
+use pyo3::ffi;
+use pyo3::prelude::*;
+use std::ffi::{CStr, c_void};
+
+// The structs of the DLPack interface, unversioned and versioned (1.0)
+
+#[repr(C)]
+struct DLDevice {
+    device_type: i32,
+    device_id: i32,
+}
+
+#[repr(C)]
+struct DLDataType {
+    code: u8,
+    bits: u8,
+    lanes: u16,
+}
+
+#[repr(C)]
+struct DLTensor {
+    data: *mut c_void,
+    device: DLDevice,
+    ndim: i32,
+    dtype: DLDataType,
+    shape: *mut i64,
+    strides: *mut i64,
+    byte_offset: u64,
+}
+
+#[repr(C)]
+struct DLManagedTensor {
+    dl_tensor: DLTensor,
+    manager_ctx: *mut c_void,
+    deleter: Option<unsafe extern "C" fn(*mut DLManagedTensor)>,
+}
+
+#[repr(C)]
+struct DLPackVersion {
+    major: u32,
+    minor: u32,
+}
+
+#[repr(C)]
+struct DLManagedTensorVersioned {
+    version: DLPackVersion,
+    manager_ctx: *mut c_void,
+    deleter: Option<unsafe extern "C" fn(*mut DLManagedTensorVersioned)>,
+    flags: u64,
+    dl_tensor: DLTensor,
+}
+
+/// The CPU device type and device id
+pub(crate) const CPU: (i32, i32) = (1, 0);
+const FLOAT: u8 = 2;
+
+const READ_ONLY: u64 = 1 << 0;
+const IS_COPIED: u64 = 1 << 1;
+
+const NAME: &CStr = c"dltensor";
+const VERSIONED_NAME: &CStr = c"dltensor_versioned";
+
+// Keeps the owner of the data, or a copy of it, alive until the consumer is
+// done with it
+struct Context {
+    shape: [i64; 1],
+    _owner: PyObject,
+    // u64 words, so that the copy is aligned for any float width
+    copy: Vec<u64>,
+}
+
+unsafe fn drop_context(context: *mut c_void) {
+    unsafe {
+        let context = Box::from_raw(context as *mut Context);
+        // the consumer may call the deleter without holding the GIL
+        Python::with_gil(|_| drop(context));
+    }
+}
+
+unsafe extern "C" fn deleter(tensor: *mut DLManagedTensor) {
+    unsafe {
+        let tensor = Box::from_raw(tensor);
+        drop_context(tensor.manager_ctx);
+    }
+}
+
+unsafe extern "C" fn versioned_deleter(tensor: *mut DLManagedTensorVersioned) {
+    unsafe {
+        let tensor = Box::from_raw(tensor);
+        drop_context(tensor.manager_ctx);
+    }
+}
+
+unsafe extern "C" fn capsule_destructor(capsule: *mut ffi::PyObject) {
+    unsafe {
+        // a consumed capsule is renamed, and the consumer calls the deleter
+        if ffi::PyCapsule_IsValid(capsule, NAME.as_ptr()) == 1 {
+            let tensor = ffi::PyCapsule_GetPointer(capsule, NAME.as_ptr()) as *mut DLManagedTensor;
+            if let Some(deleter) = (*tensor).deleter {
+                deleter(tensor);
+            }
+        }
+    }
+}
+
+unsafe extern "C" fn versioned_capsule_destructor(capsule: *mut ffi::PyObject) {
+    unsafe {
+        if ffi::PyCapsule_IsValid(capsule, VERSIONED_NAME.as_ptr()) == 1 {
+            let tensor = ffi::PyCapsule_GetPointer(capsule, VERSIONED_NAME.as_ptr())
+                as *mut DLManagedTensorVersioned;
+            if let Some(deleter) = (*tensor).deleter {
+                deleter(tensor);
+            }
+        }
+    }
+}
+
+/// Exports a read-only 1-dimensional float buffer as a DLPack capsule,
+/// keeping `owner` alive while the tensor is in use
+///
+/// Consumers of DLPack 1.0 get the buffer without copying, flagged read-only.
+/// Older consumers have no way to know the tensor is read-only, so they get a
+/// copy if `copy` allows it and a `BufferError` otherwise, as NumPy does for
+/// read-only arrays. With `copy` true, every consumer gets a copy.
+///
+/// # Safety
+///
+/// `data` must point to `len` floats of `bits` bits that stay valid and
+/// unchanged while `owner` is alive.
+pub(crate) unsafe fn export(
+    owner: &Bound<'_, PyAny>,
+    data: *const c_void,
+    len: usize,
+    bits: u8,
+    max_version: Option<(u32, u32)>,
+    copy: Option<bool>,
+) -> PyResult<PyObject> {
+    let py = owner.py();
+    let versioned = max_version.is_some_and(|(major, _)| major >= 1);
+    let copied = copy == Some(true) || (!versioned && copy.is_none());
+    if !versioned && !copied {
+        return Err(PyBufferError::new_err(
+            "components are read-only and can only be exported without copying to DLPack 1.0 consumers",
+        ));
+    }
+
+    let mut words = Vec::new();
+    if copied {
+        let bytes = len * bits as usize / 8;
+        words = vec![0u64; bytes.div_ceil(8)];
+        // SAFETY: the caller guarantees `data` holds `bytes` bytes, and the
+        // copy has room for them
+        unsafe {
+            std::ptr::copy_nonoverlapping(data as *const u8, words.as_mut_ptr() as *mut u8, bytes);
+        }
+    }
+    let context = Box::into_raw(Box::new(Context {
+        shape: [len as i64],
+        _owner: owner.clone().unbind(),
+        copy: words,
+    }));
+    let dl_tensor = DLTensor {
+        data: if copied {
+            unsafe { (*context).copy.as_mut_ptr() as *mut c_void }
+        } else {
+            data as *mut c_void
+        },
+        device: DLDevice {
+            device_type: CPU.0,
+            device_id: CPU.1,
+        },
+        ndim: 1,
+        dtype: DLDataType {
+            code: FLOAT,
+            bits,
+            lanes: 1,
+        },
+        shape: unsafe { (*context).shape.as_mut_ptr() },
+        strides: std::ptr::null_mut(),
+        byte_offset: 0,
+    };
+
+    unsafe {
+        let capsule = if versioned {
+            let tensor = Box::into_raw(Box::new(DLManagedTensorVersioned {
+                version: DLPackVersion { major: 1, minor: 0 },
+                manager_ctx: context as *mut c_void,
+                deleter: Some(versioned_deleter),
+                flags: if copied { IS_COPIED } else { READ_ONLY },
+                dl_tensor,
+            }));
+            let capsule = ffi::PyCapsule_New(
+                tensor as *mut c_void,
+                VERSIONED_NAME.as_ptr(),
+                Some(versioned_capsule_destructor),
+            );
+            if capsule.is_null() {
+                versioned_deleter(tensor);
+            }
+            capsule
+        } else {
+            let tensor = Box::into_raw(Box::new(DLManagedTensor {
+                dl_tensor,
+                manager_ctx: context as *mut c_void,
+                deleter: Some(deleter),
+            }));
+            let capsule = ffi::PyCapsule_New(
+                tensor as *mut c_void,
+                NAME.as_ptr(),
+                Some(capsule_destructor),
+            );
+            if capsule.is_null() {
+                deleter(tensor);
+            }
+            capsule
+        };
+        if capsule.is_null() {
+            return Err(PyErr::fetch(py));
+        }
+        Ok(PyObject::from_owned_ptr(py, capsule))
+    }
+}
@@ -2,9 +2,55 @@ use std::error;
 use std::fmt;
 
 #[derive(Debug, Eq, PartialEq)]
+#[non_exhaustive]
 pub enum Error {
     Parameter(String),
     Series(String),
+    Io(String),
+    Format(String),
+}
+
+impl Error {
+    /// Returns a numeric code for the kind of error.
+    ///
+    /// The codes are stable across releases and safe to match on across an FFI
+    /// boundary:
+    ///
+    /// | Code | Variant     |
+    /// |------|-------------|
+    /// | 1    | `Parameter` |
+    /// | 2    | `Series`    |
+    /// | 3    | `Io`        |
+    /// | 4    | `Format`    |
+    ///
+    /// Codes are never reused, and new variants get new codes.
+    pub fn code(&self) -> u32 {
+        match self {
+            Error::Parameter(_) => 1,
+            Error::Series(_) => 2,
+            Error::Io(_) => 3,
+            Error::Format(_) => 4,
+        }
+    }
+
+    /// Returns whether the same call may succeed with different data or on retry.
+    ///
+    /// Invalid parameters fail for any series and malformed saved models or
+    /// results fail on every read, while series errors (such as too few
+    /// observations) can go away as data arrives and I/O errors may be transient.
+    pub fn is_recoverable(&self) -> bool {
+        match self {
+            Error::Parameter(_) | Error::Format(_) => false,
+            Error::Series(_) | Error::Io(_) => true,
+        }
+    }
+
+    /// Returns the error message.
+    pub fn message(&self) -> &str {
+        match self {
+            Error::Parameter(err) | Error::Series(err) | Error::Io(err) | Error::Format(err) => err,
+        }
+    }
 }
 
 impl error::Error for Error {}
@@ -14,6 +60,8 @@ impl fmt::Display for Error {
         match *self {
             Error::Parameter(ref err) => write!(f, "{}", err.as_str()),
             Error::Series(ref err) => write!(f, "{}", err.as_str()),
+            Error::Io(ref err) => write!(f, "{}", err.as_str()),
+            Error::Format(ref err) => write!(f, "{}", err.as_str()),
         }
     }
 }
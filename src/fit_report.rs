@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+/// Counts of the work done by a fit, for tuning jumps and loop counts.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FitReport {
+    pub(crate) loess_evaluations: usize,
+    pub(crate) interpolated_points: usize,
+    pub(crate) inner_iterations: usize,
+    pub(crate) robustness_iterations: usize,
+    pub(crate) seasonal_time: Duration,
+    pub(crate) low_pass_time: Duration,
+    pub(crate) trend_time: Duration,
+}
+
+impl FitReport {
+    /// Returns the number of points where a local regression was fitted.
+    pub fn loess_evaluations(&self) -> usize {
+        self.loess_evaluations
+    }
+
+    /// Returns the number of points linearly interpolated between loess
+    /// evaluations because of the jumps.
+    pub fn interpolated_points(&self) -> usize {
+        self.interpolated_points
+    }
+
+    /// Returns the total number of passes of the inner loop.
+    pub fn inner_iterations(&self) -> usize {
+        self.inner_iterations
+    }
+
+    /// Returns the number of robustness iterations.
+    pub fn robustness_iterations(&self) -> usize {
+        self.robustness_iterations
+    }
+
+    /// Returns the time spent smoothing the cycle-subseries.
+    pub fn seasonal_time(&self) -> Duration {
+        self.seasonal_time
+    }
+
+    /// Returns the time spent in the low-pass filter.
+    pub fn low_pass_time(&self) -> Duration {
+        self.low_pass_time
+    }
+
+    /// Returns the time spent smoothing the trend.
+    pub fn trend_time(&self) -> Duration {
+        self.trend_time
+    }
+}
@@ -0,0 +1,193 @@
+use super::minimize::golden_section;
+
+/// A method for forecasting the seasonally adjusted series.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ForecastMethod {
+    /// Repeats the last adjusted value.
+    Naive,
+    /// Repeats the last period of adjusted values.
+    SeasonalNaive,
+    /// Extends the line through the first and last adjusted values.
+    Drift,
+    /// Simple exponential smoothing, with the smoothing parameter chosen to
+    /// minimize the one-step-ahead squared error.
+    Ses,
+    /// Holt's linear exponential smoothing, with the level and slope
+    /// smoothing parameters chosen to minimize the one-step-ahead squared error.
+    Holt,
+    /// An ARIMA(p, d, q) model, differenced `d` times and fitted by the
+    /// Hannan-Rissanen regression, with a mean when `d` is 0 and a drift when
+    /// `d` is 1.
+    #[cfg(feature = "arima")]
+    Arima { p: usize, d: usize, q: usize },
+}
+
+// The seasonally adjusted series, falling back to the trend where the
+// observation is missing
+pub(crate) fn adjusted_series(trend: &[f64], remainder: &[f64]) -> Vec<f64> {
+    trend
+        .iter()
+        .zip(remainder)
+        .map(|(t, r)| if r.is_nan() { *t } else { t + r })
+        .collect()
+}
+
+pub(crate) fn forecast_adjusted(
+    adjusted: &[f64],
+    period: usize,
+    horizon: usize,
+    method: ForecastMethod,
+) -> Vec<f64> {
+    let n = adjusted.len();
+    let last = adjusted[n - 1];
+    match method {
+        ForecastMethod::Naive => vec![last; horizon],
+        ForecastMethod::SeasonalNaive => (0..horizon)
+            .map(|h| adjusted[n - period + h % period])
+            .collect(),
+        ForecastMethod::Drift => {
+            let slope = if n > 1 {
+                (last - adjusted[0]) / (n - 1) as f64
+            } else {
+                0.0
+            };
+            (1..=horizon).map(|h| last + h as f64 * slope).collect()
+        }
+        ForecastMethod::Ses => {
+            let alpha = golden_section(|a| holt(adjusted, a, None).2, 1e-6);
+            let (level, _, _) = holt(adjusted, alpha, None);
+            vec![level; horizon]
+        }
+        ForecastMethod::Holt => {
+            // coordinate descent over the two smoothing parameters
+            let (mut alpha, mut beta) = (0.5, 0.1);
+            for _ in 0..5 {
+                alpha = golden_section(|a| holt(adjusted, a, Some(beta)).2, 1e-6);
+                beta = golden_section(|b| holt(adjusted, alpha, Some(b)).2, 1e-6);
+            }
+            let (level, slope, _) = holt(adjusted, alpha, Some(beta));
+            (1..=horizon).map(|h| level + h as f64 * slope).collect()
+        }
+        #[cfg(feature = "arima")]
+        ForecastMethod::Arima { p, d, q } => arima(adjusted, p, d, q, horizon),
+    }
+}
+
+#[cfg(feature = "arima")]
+fn arima(y: &[f64], p: usize, d: usize, q: usize, horizon: usize) -> Vec<f64> {
+    use super::regression::ols;
+
+    // difference d times, remembering the last value at each order
+    let mut z = y.to_vec();
+    let mut lasts = Vec::with_capacity(d);
+    for _ in 0..d {
+        lasts.push(z.last().copied().unwrap_or(0.0));
+        z = z.windows(2).map(|w| w[1] - w[0]).collect();
+    }
+    let mean = if d < 2 && !z.is_empty() {
+        z.iter().sum::<f64>() / z.len() as f64
+    } else {
+        0.0
+    };
+    z.iter_mut().for_each(|v| *v -= mean);
+    let n = z.len();
+
+    // innovations from a long autoregression stand in for the unobserved errors
+    let mut errors = vec![0.0; n];
+    let mut skip = p;
+    if q > 0 {
+        let m = (p + q)
+            .max(((n as f64).ln().powi(2)) as usize)
+            .min(n / 4)
+            .max(1);
+        let target = (0..n)
+            .map(|t| if t < m { f64::NAN } else { z[t] })
+            .collect::<Vec<f64>>();
+        let columns = (1..=m)
+            .map(|lag| {
+                (0..n)
+                    .map(|t| if t < lag { 0.0 } else { z[t - lag] })
+                    .collect()
+            })
+            .collect::<Vec<Vec<f64>>>();
+        let beta = ols(&target, &columns);
+        for t in m..n {
+            errors[t] = z[t] - (1..=m).map(|lag| beta[lag - 1] * z[t - lag]).sum::<f64>();
+        }
+        skip = skip.max(m + q);
+    }
+
+    // regress z on its lags and the lags of the innovations, leaving out the
+    // first rows where a lag is unavailable
+    let target = (0..n)
+        .map(|t| if t < skip { f64::NAN } else { z[t] })
+        .collect::<Vec<f64>>();
+    let mut columns = Vec::with_capacity(p + q);
+    for lag in 1..=p {
+        columns.push(
+            (0..n)
+                .map(|t| if t < lag { 0.0 } else { z[t - lag] })
+                .collect(),
+        );
+    }
+    for lag in 1..=q {
+        columns.push(
+            (0..n)
+                .map(|t| if t < lag { 0.0 } else { errors[t - lag] })
+                .collect(),
+        );
+    }
+    let beta = ols(&target, &columns);
+    let (phi, theta) = beta.split_at(p);
+
+    // forecast with the estimated innovations, and future ones at zero
+    let predict = |z: &[f64], errors: &[f64], t: usize| {
+        let ar = (1..=p)
+            .filter(|&lag| t >= lag)
+            .map(|lag| phi[lag - 1] * z[t - lag])
+            .sum::<f64>();
+        let ma = (1..=q)
+            .filter(|&lag| t >= lag)
+            .map(|lag| theta[lag - 1] * errors[t - lag])
+            .sum::<f64>();
+        ar + ma
+    };
+    for t in n..n + horizon {
+        errors.push(0.0);
+        let v = predict(&z, &errors, t);
+        z.push(v);
+    }
+
+    // undo the mean and the differencing
+    let mut forecast = z[n..].iter().map(|v| v + mean).collect::<Vec<f64>>();
+    for last in lasts.into_iter().rev() {
+        let mut level = last;
+        for v in forecast.iter_mut() {
+            level += *v;
+            *v = level;
+        }
+    }
+    forecast
+}
+
+// Runs Holt's method, or simple exponential smoothing without a slope
+// parameter, returning the final level, final slope, and one-step-ahead SSE
+fn holt(y: &[f64], alpha: f64, beta: Option<f64>) -> (f64, f64, f64) {
+    let mut level = y[0];
+    let mut slope = match (beta, y.len()) {
+        (Some(_), n) if n > 1 => y[1] - y[0],
+        _ => 0.0,
+    };
+    let mut sse = 0.0;
+    for &v in &y[1..] {
+        let error = v - (level + slope);
+        sse += error * error;
+        let previous = level;
+        level += slope + alpha * error;
+        if let Some(beta) = beta {
+            slope += beta * (level - previous - slope);
+        }
+    }
+    (level, slope, sse)
+}
@@ -0,0 +1,81 @@
+use std::f64::consts::PI;
+
+use super::regression::ols;
+
+/// A Fourier harmonic of the seasonal cycle.
+///
+/// At phase `t` of the cycle, the harmonic contributes
+/// `amplitude * cos(2 * pi * order * t / period - phase)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Harmonic {
+    pub(crate) order: usize,
+    pub(crate) amplitude: f64,
+    pub(crate) phase: f64,
+}
+
+impl Harmonic {
+    /// Returns the number of cycles per period.
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    /// Returns the amplitude.
+    pub fn amplitude(&self) -> f64 {
+        self.amplitude
+    }
+
+    /// Returns the phase shift in radians.
+    pub fn phase(&self) -> f64 {
+        self.phase
+    }
+}
+
+// Discrete Fourier coefficients of one cycle, for orders 1..=k up to the Nyquist order
+pub(crate) fn harmonics(cycle: &[f64], k: usize) -> Vec<Harmonic> {
+    let p = cycle.len();
+    (1..=k.min(p / 2))
+        .map(|j| {
+            let (mut a, mut b) = (0.0, 0.0);
+            for (t, v) in cycle.iter().enumerate() {
+                let w = 2.0 * PI * (j * t) as f64 / p as f64;
+                a += v * w.cos();
+                b += v * w.sin();
+            }
+            // the Nyquist term has no sine part and half the weight
+            let scale = if 2 * j == p { 1.0 } else { 2.0 } / p as f64;
+            let (a, b) = (a * scale, b * scale);
+            Harmonic {
+                order: j,
+                amplitude: a.hypot(b),
+                phase: b.atan2(a),
+            }
+        })
+        .collect()
+}
+
+// Least-squares fit of k Fourier terms to y, with an intercept and a linear
+// term absorbing level and drift; returns only the seasonal part of the fit
+pub(crate) fn fourier_fit(y: &[f64], period: usize, k: usize) -> Vec<f64> {
+    let n = y.len();
+    let mut columns = vec![vec![1.0; n], (0..n).map(|t| t as f64 / n as f64).collect()];
+    for j in 1..=k {
+        let w = |t: usize| 2.0 * PI * (j * t) as f64 / period as f64;
+        columns.push((0..n).map(|t| w(t).cos()).collect());
+        // the sine of the Nyquist term is zero at every integer phase
+        if 2 * j != period {
+            columns.push((0..n).map(|t| w(t).sin()).collect());
+        }
+    }
+
+    let beta = ols(y, &columns);
+    (0..n)
+        .map(|t| {
+            columns
+                .iter()
+                .zip(&beta)
+                .skip(2)
+                .map(|(x, b)| x[t] * b)
+                .sum()
+        })
+        .collect()
+}
@@ -0,0 +1,112 @@
+/// Cross-correlation of the remainder with each component.
+///
+/// A trend too stiff to follow the series, or a seasonal component too
+/// smooth to follow a changing pattern, leaves part of that component in the
+/// remainder, where it shows up as correlation with the component at lag 0
+/// or at the seasonal lags.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Leakage {
+    pub(crate) labels: Vec<String>,
+    pub(crate) lags: Vec<usize>,
+    pub(crate) correlations: Vec<Vec<f64>>,
+    pub(crate) threshold: f64,
+}
+
+impl Leakage {
+    /// Returns the label of each component tested, like `trend` or
+    /// `seasonal_7`.
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    /// Returns the lags tested: 0 and the seasonal periods that are shorter
+    /// than the series.
+    pub fn lags(&self) -> &[usize] {
+        &self.lags
+    }
+
+    /// Returns the correlation of the remainder with each component at each
+    /// lag, with the component leading the remainder.
+    pub fn correlations(&self) -> &[Vec<f64>] {
+        &self.correlations
+    }
+
+    /// Returns the absolute correlation above which leakage is detected.
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    /// Returns the labels of the components whose absolute correlation with
+    /// the remainder exceeds the threshold at any lag.
+    pub fn leaking(&self) -> Vec<&str> {
+        self.labels
+            .iter()
+            .zip(&self.correlations)
+            .filter(|(_, c)| c.iter().any(|r| r.abs() > self.threshold))
+            .map(|(label, _)| label.as_str())
+            .collect()
+    }
+
+    /// Returns whether any component has leaked into the remainder.
+    pub fn detected(&self) -> bool {
+        !self.leaking().is_empty()
+    }
+}
+
+// The mean and sum of squared deviations, skipping missing values
+fn moments(values: &[f64]) -> (f64, f64) {
+    let observed = values.iter().filter(|v| !v.is_nan()).count();
+    let mean = values.iter().filter(|v| !v.is_nan()).sum::<f64>() / observed as f64;
+    let ss = values
+        .iter()
+        .filter(|v| !v.is_nan())
+        .map(|v| (v - mean).powi(2))
+        .sum::<f64>();
+    (mean, ss)
+}
+
+// Correlation of the remainder with the component `lag` steps earlier,
+// skipping pairs with a missing value
+fn cross_correlation(remainder: &[f64], component: &[f64], lag: usize) -> f64 {
+    let (rm, rss) = moments(remainder);
+    let (cm, css) = moments(component);
+    if !(rss > 0.0 && css > 0.0) {
+        return 0.0;
+    }
+    let sum = remainder[lag..]
+        .iter()
+        .zip(component)
+        .filter(|(r, c)| !r.is_nan() && !c.is_nan())
+        .map(|(r, c)| (r - rm) * (c - cm))
+        .sum::<f64>();
+    sum / (rss * css).sqrt()
+}
+
+pub(crate) fn leakage(
+    remainder: &[f64],
+    components: &[(String, &[f64])],
+    periods: &[usize],
+) -> Leakage {
+    let mut lags = vec![0];
+    lags.extend(periods.iter().copied().filter(|&p| p < remainder.len()));
+    lags.sort_unstable();
+    lags.dedup();
+
+    let correlations = components
+        .iter()
+        .map(|(_, component)| {
+            lags.iter()
+                .map(|&lag| cross_correlation(remainder, component, lag))
+                .collect()
+        })
+        .collect();
+
+    // a two-sided 1% test for each lag under white noise
+    let observed = remainder.iter().filter(|v| !v.is_nan()).count();
+    Leakage {
+        labels: components.iter().map(|(label, _)| label.clone()).collect(),
+        lags,
+        correlations,
+        threshold: 2.58 / (observed as f64).sqrt(),
+    }
+}
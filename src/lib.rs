@@ -1,23 +1,338 @@
-use pyo3::exceptions::PyValueError;
+use numpy::{PyArray1, PyArrayMethods, PyUntypedArray, PyUntypedArrayMethods};
+use pyo3::exceptions::{PyBufferError, PyTypeError, PyUserWarning, PyValueError};
 use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList, PySlice};
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::CString;
 
+mod algebra;
+mod anomaly;
+mod anomaly_stream;
+mod arrow;
+mod boxcox;
+pub mod cache;
+mod calendar;
+mod classical;
+mod decomposer;
+mod dlpack;
 mod error;
+mod fit_report;
+mod forecast;
+mod harmonic;
+mod leakage;
+mod loess_diagnostics;
+mod minimize;
+mod missing;
 mod mstl;
 mod mstl_impl;
 mod mstl_params;
 mod mstl_result;
+#[cfg(feature = "parallel")]
+mod parallel;
+pub mod profiles;
+mod regression;
+mod residual_seasonality;
+mod seasonal_model;
+#[cfg(feature = "serde")]
+mod serialize;
+mod sliding;
+mod smoother;
 mod stl;
 mod stl_impl;
 mod stl_params;
 mod stl_result;
+mod strength;
+pub mod synth;
+mod time_series;
+mod turning_point;
+mod x11;
 
+pub use algebra::{add_components, scale_component, subtract_components, sum_components};
+pub use boxcox::{boxcox, boxcox_lambda_guerrero, inv_boxcox};
+pub use classical::{DecompositionModel, classical_decompose};
+pub use decomposer::{Decomposer, Decomposition};
 pub use error::Error;
+pub use fit_report::FitReport;
+pub use leakage::Leakage;
+pub use missing::fill_gaps;
 pub use mstl::{Mstl, MstlParams, MstlResult};
-pub use stl::{Stl, StlParams, StlResult};
+#[cfg(feature = "parallel")]
+pub use parallel::{clear_thread_pool, set_num_threads, set_thread_pool, with_thread_pool};
+pub use regression::RegressionEffect;
+pub use residual_seasonality::ResidualSeasonality;
+pub use stl::{
+    AggFn, Anomaly, AnomalyScore, AnomalyStream, Components, CountModel, ForecastMethod, Harmonic, Loess, LoessDiagnostics, Monotone, NonNegative, Outliers, PartialCycle, Row, SeasonalModel, Shared, SlidingStl, Smoother, Stl, StlParams, StlResult, TurningPoint,
+};
+pub use time_series::TimeSeries;
+pub use strength::{StrengthMethod, StrengthSummary, Variance, component_strength, seasonal_strengths, strength_summary, trend_strengths};
+pub use x11::{SeasonalFilter, X11, X11Params};
+
+/// Version of the layout written by PySTLResult.to_dict
+const RESULT_DICT_VERSION: u32 = 1;
+
+/// Dates as (year, month, day)
+type Dates = Vec<(i32, u32, u32)>;
+
+/// Series data, either held as a float64 NumPy array or converted to a Vec
+enum SeriesData {
+    Array(Py<PyArray1<f64>>),
+    Vec(Vec<f64>),
+}
+
+impl SeriesData {
+    fn len(&self, py: Python) -> usize {
+        match self {
+            SeriesData::Array(array) => array.bind(py).len(),
+            SeriesData::Vec(data) => data.len(),
+        }
+    }
+}
+
+/// Converts a sequence of numbers to float64 data, mapping None, pandas NA and
+/// Arrow nulls to NaN, without copying float64 NumPy arrays or DLPack tensors
+fn series_data(obj: &Bound<'_, PyAny>) -> PyResult<SeriesData> {
+    // without numpy loaded the object can't be an array, and checking
+    // would try to import it
+    let numpy_loaded = PyModule::import(obj.py(), "sys")?
+        .getattr("modules")?
+        .contains("numpy")?;
+
+    // float64 arrays are kept and read directly from the buffer
+    if numpy_loaded && let Ok(array) = obj.downcast::<PyArray1<f64>>() {
+        return Ok(SeriesData::Array(array.clone().unbind()));
+    }
+
+    // other dtypes are cast by numpy in a single pass
+    if numpy_loaded && let Ok(array) = obj.downcast::<PyUntypedArray>() {
+        if array.ndim() != 1 {
+            return Err(PyValueError::new_err("series must be 1-dimensional"));
+        }
+        return series_data(&obj.call_method1("astype", ("float64",))?);
+    }
+
+    // tensors from other libraries (torch, jax, cupy on the CPU) are viewed
+    // as NumPy arrays through DLPack without copying
+    if obj.hasattr("__dlpack__")? {
+        let array = PyModule::import(obj.py(), "numpy")?.call_method1("from_dlpack", (obj,))?;
+        return series_data(&array);
+    }
+
+    // polars Series and DataFrames (and other Arrow producers) are read
+    // through the Arrow C stream interface, while pandas objects use to_numpy
+    let is_pandas = obj.get_type().module()?.to_cow()?.starts_with("pandas");
+    if !is_pandas && arrow::is_arrow_stream(obj)? {
+        return arrow::read_stream(obj).map(SeriesData::Vec);
+    }
+
+    // pandas nullable dtypes convert directly with NA as NaN
+    if obj.hasattr("to_numpy")? {
+        let kwargs = PyDict::new(obj.py());
+        kwargs.set_item("dtype", "float64")?;
+        kwargs.set_item("na_value", f64::NAN)?;
+        if let Ok(array) = obj.call_method("to_numpy", (), Some(&kwargs)) {
+            return series_data(&array);
+        }
+    }
+
+    if let Ok(series) = obj.extract::<Vec<f64>>() {
+        return Ok(SeriesData::Vec(series));
+    }
+
+    obj.try_iter()?
+        .map(|item| {
+            let item = item?;
+            if item.is_none() || item.get_type().name()? == "NAType" {
+                Ok(f64::NAN)
+            } else {
+                item.extract::<f64>()
+            }
+        })
+        .collect::<PyResult<Vec<f64>>>()
+        .map(SeriesData::Vec)
+}
+
+/// Converts a sequence of numbers to floats, mapping None and pandas NA to NaN
+fn extract_series(obj: &Bound<'_, PyAny>) -> PyResult<Vec<f64>> {
+    match series_data(obj)? {
+        SeriesData::Array(array) => {
+            let array = array.bind(obj.py()).readonly();
+            Ok(match array.as_slice() {
+                Ok(slice) => slice.to_vec(),
+                Err(_) => array.as_array().to_vec(),
+            })
+        }
+        SeriesData::Vec(data) => Ok(data),
+    }
+}
+
+/// A period given as a number of observations or a frequency alias
+#[derive(FromPyObject)]
+enum PeriodArg {
+    Length(usize),
+    Alias(String),
+}
+
+/// A skipping value given as a number of points or a fraction of the window length
+#[derive(Clone, Copy, FromPyObject)]
+enum JumpArg {
+    Points(usize),
+    Fraction(f64),
+}
+
+/// Sets a skipping value with the setter for its kind
+fn set_jump(
+    params: &mut StlParams,
+    jump: JumpArg,
+    points: fn(&mut StlParams, usize) -> &mut StlParams,
+    fraction: fn(&mut StlParams, f64) -> &mut StlParams,
+) -> &mut StlParams {
+    match jump {
+        JumpArg::Points(jump) => points(params, jump),
+        JumpArg::Fraction(jump) => fraction(params, jump),
+    }
+}
+
+/// A Box-Cox lambda given as a number or "auto"
+#[derive(Clone, FromPyObject)]
+enum LambdaArg {
+    Value(f64),
+    Named(String),
+}
+
+/// A Box-Cox shift given as a number or "auto"
+#[derive(Clone, FromPyObject)]
+enum ShiftArg {
+    Value(f64),
+    Named(String),
+}
+
+/// Applies a shift argument to MSTL parameters
+fn set_shift(params: &mut MstlParams, shift: Option<&ShiftArg>) -> PyResult<()> {
+    match shift {
+        None => {}
+        Some(ShiftArg::Value(shift)) => {
+            params.shift(*shift);
+        }
+        Some(ShiftArg::Named(name)) if name == "auto" => {
+            params.auto_shift(true);
+        }
+        Some(ShiftArg::Named(_)) => {
+            return Err(PyValueError::new_err("shift must be a float or 'auto'"));
+        }
+    }
+    Ok(())
+}
+
+/// Applies a lambda argument to MSTL parameters
+fn set_lambda(params: &mut MstlParams, lmbda: Option<&LambdaArg>) -> PyResult<()> {
+    match lmbda {
+        None => {}
+        Some(LambdaArg::Value(lambda)) => {
+            params.lambda(*lambda);
+        }
+        Some(LambdaArg::Named(name)) if name == "auto" => {
+            params.auto_lambda();
+        }
+        Some(LambdaArg::Named(_)) => {
+            return Err(PyValueError::new_err("lmbda must be a float or 'auto'"));
+        }
+    }
+    Ok(())
+}
+
+/// Strips pandas anchors and start/end markers, e.g. "W-SUN" -> "W", "MS" -> "M"
+fn normalize_freq(freq: &str) -> String {
+    let freq = freq.split('-').next().unwrap_or("").to_uppercase();
+    let freq = freq.trim_start_matches('1');
+    match freq {
+        "MS" | "ME" | "BM" | "BMS" => "M",
+        "QS" | "QE" | "BQ" | "BQS" => "Q",
+        "A" | "AS" | "Y" | "YS" | "YE" | "BA" | "BY" => "A",
+        f => f,
+    }
+    .to_string()
+}
+
+/// Number of observations per seasonal cycle for data of the given frequency
+/// (same defaults as statsmodels)
+fn freq_to_period(freq: &str) -> Option<usize> {
+    match normalize_freq(freq).as_str() {
+        "A" => Some(1),
+        "Q" => Some(4),
+        "M" => Some(12),
+        "W" => Some(52),
+        "D" => Some(7),
+        "B" => Some(5),
+        "H" => Some(24),
+        _ => None,
+    }
+}
+
+/// Number of observations of frequency freq in one cycle
+fn cycle_length(cycle: &str, freq: &str) -> Option<usize> {
+    match (normalize_freq(cycle).as_str(), normalize_freq(freq).as_str()) {
+        ("D", "H") => Some(24),
+        ("W", "H") => Some(168),
+        ("W", "D") => Some(7),
+        ("W", "B") => Some(5),
+        ("A", "H") => Some(8760),
+        ("A", "D") => Some(365),
+        ("A", "B") => Some(260),
+        ("A", "W") => Some(52),
+        ("A", "M") => Some(12),
+        ("A", "Q") => Some(4),
+        ("Q", "M") => Some(3),
+        _ => None,
+    }
+}
+
+/// Resolves a period argument, using freq as the data frequency for aliases
+fn resolve_period(period: PeriodArg, freq: Option<&str>) -> PyResult<usize> {
+    match period {
+        PeriodArg::Length(period) => Ok(period),
+        PeriodArg::Alias(alias) => match freq {
+            Some(freq) => cycle_length(&alias, freq).ok_or_else(|| {
+                PyValueError::new_err(format!(
+                    "unable to determine the period of {} for {} data",
+                    alias, freq
+                ))
+            }),
+            None => freq_to_period(&alias).ok_or_else(|| {
+                PyValueError::new_err(format!("unable to determine period from freq {}", alias))
+            }),
+        },
+    }
+}
+
+/// Infers the period from the frequency of a pandas index, if any
+fn infer_period(endog: &Bound<'_, PyAny>) -> PyResult<Option<usize>> {
+    if !endog.hasattr("index")? {
+        return Ok(None);
+    }
+    let index = endog.getattr("index")?;
+    if !index.hasattr("freqstr")? {
+        return Ok(None);
+    }
+    Ok(index
+        .getattr("freqstr")?
+        .extract::<Option<String>>()?
+        .and_then(|freq| freq_to_period(&freq)))
+}
+
+/// Returns the index of a pandas Series
+fn pandas_index(obj: &Bound<'_, PyAny>) -> PyResult<Option<PyObject>> {
+    let ty = obj.get_type();
+    if ty.name()? == "Series" && ty.module()?.to_cow()?.starts_with("pandas") {
+        Ok(Some(obj.getattr("index")?.unbind()))
+    } else {
+        Ok(None)
+    }
+}
 
 #[pyclass]
 pub struct STL {
-    data: Vec<f64>,
+    data: SeriesData,
+    index: Option<PyObject>,
     period: usize,
     seasonal: Option<usize>,
     trend: Option<usize>,
@@ -26,20 +341,155 @@ pub struct STL {
     trend_deg: i32,
     low_pass_deg: i32,
     robust: bool,
-    seasonal_jump: Option<usize>,
-    trend_jump: Option<usize>,
-    low_pass_jump: Option<usize>,
+    seasonal_jump: Option<JumpArg>,
+    trend_jump: Option<JumpArg>,
+    low_pass_jump: Option<JumpArg>,
     inner_loops: Option<usize>,
     outer_loops: Option<usize>,
+    strict: bool,
+    partial_cycle: PartialCycle,
+    trading_day: Option<(i32, u32)>,
+    holidays: Vec<(String, Dates)>,
+    regressors: Vec<(String, Vec<f64>)>,
+    start: Option<(i32, u32)>,
+}
+
+/// Emits a Python warning for each suspicious parameter setting
+fn warn_params(py: Python, params: &StlParams, period: usize) -> PyResult<()> {
+    let category = py.get_type::<PyUserWarning>();
+    for warning in params.warnings(period) {
+        PyErr::warn(py, &category, &CString::new(warning)?, 1)?;
+    }
+    Ok(())
+}
+
+/// Parses the policy for a trailing partial cycle
+fn parse_partial_cycle(partial_cycle: &str) -> PyResult<PartialCycle> {
+    match partial_cycle {
+        "allow" => Ok(PartialCycle::Allow),
+        "strict" => Ok(PartialCycle::Strict),
+        "truncate" => Ok(PartialCycle::Truncate),
+        "pad" => Ok(PartialCycle::PadWithTrend),
+        _ => Err(PyValueError::new_err(
+            "partial_cycle must be 'allow', 'strict', 'truncate', or 'pad'",
+        )),
+    }
+}
+
+/// Parses the method for forecasting the seasonally adjusted series
+#[cfg_attr(not(feature = "arima"), allow(unused_variables))]
+fn parse_forecast_method(method: &str, order: (usize, usize, usize)) -> PyResult<ForecastMethod> {
+    match method {
+        "naive" => Ok(ForecastMethod::Naive),
+        "snaive" => Ok(ForecastMethod::SeasonalNaive),
+        "drift" => Ok(ForecastMethod::Drift),
+        "ses" => Ok(ForecastMethod::Ses),
+        "holt" => Ok(ForecastMethod::Holt),
+        #[cfg(feature = "arima")]
+        "arima" => {
+            let (p, d, q) = order;
+            Ok(ForecastMethod::Arima { p, d, q })
+        }
+        _ => Err(PyValueError::new_err(
+            "method must be 'naive', 'snaive', 'drift', 'ses', 'holt', or 'arima' (with the arima feature)",
+        )),
+    }
+}
+
+/// Parses a variance estimator for strengths, with the fraction trimmed from each tail
+fn parse_variance(variance: &str, trim: f64) -> PyResult<Variance> {
+    match variance {
+        "sample" => Ok(Variance::Sample),
+        "mad" => Ok(Variance::Mad),
+        "trimmed" if (0.0..0.5).contains(&trim) => Ok(Variance::Trimmed(trim)),
+        "trimmed" => Err(PyValueError::new_err("trim must be at least 0 and less than 0.5")),
+        _ => Err(PyValueError::new_err("variance must be 'sample', 'mad' or 'trimmed'")),
+    }
+}
+
+/// Parses a strength formula and variance estimator, which only applies to the variance ratio
+fn parse_strength_args(variance: &str, trim: f64, method: &str) -> PyResult<(Variance, StrengthMethod)> {
+    let variance = parse_variance(variance, trim)?;
+    let method = match method {
+        "variance_ratio" => StrengthMethod::VarianceRatio,
+        "statsmodels" => StrengthMethod::Statsmodels,
+        "ftest" => StrengthMethod::FTest,
+        _ => return Err(PyValueError::new_err("method must be 'variance_ratio', 'statsmodels' or 'ftest'")),
+    };
+    if method != StrengthMethod::VarianceRatio && variance != Variance::Sample {
+        return Err(PyValueError::new_err("variance only applies to the 'variance_ratio' method"));
+    }
+    Ok((variance, method))
+}
+
+/// Returns whether statsmodels compatibility is requested
+fn parse_compat(compat: Option<&str>) -> PyResult<bool> {
+    match compat {
+        None => Ok(false),
+        Some("statsmodels") => Ok(true),
+        Some(_) => Err(PyValueError::new_err("compat must be None or 'statsmodels'")),
+    }
+}
+
+/// Checks parameters the way statsmodels' STL does, with the same messages
+#[allow(clippy::too_many_arguments)]
+fn validate_statsmodels(
+    period: usize,
+    seasonal: usize,
+    trend: Option<usize>,
+    low_pass: Option<usize>,
+    seasonal_deg: i32,
+    trend_deg: i32,
+    low_pass_deg: i32,
+    jumps: [Option<JumpArg>; 3],
+) -> PyResult<()> {
+    let odd = |v: usize| v >= 3 && v % 2 == 1;
+    if period < 2 {
+        return Err(PyValueError::new_err("period must be a positive integer >= 2"));
+    }
+    if !odd(seasonal) {
+        return Err(PyValueError::new_err("seasonal must be an odd positive integer >= 3"));
+    }
+    if let Some(trend) = trend
+        && (!odd(trend) || trend <= period)
+    {
+        return Err(PyValueError::new_err(
+            "trend must be an odd positive integer >= 3 where trend > period",
+        ));
+    }
+    if let Some(low_pass) = low_pass
+        && (!odd(low_pass) || low_pass <= period)
+    {
+        return Err(PyValueError::new_err(
+            "low_pass must be an odd positive integer >= 3 where low_pass > period",
+        ));
+    }
+    for (name, deg) in [
+        ("seasonal_deg", seasonal_deg),
+        ("trend_deg", trend_deg),
+        ("low_pass_deg", low_pass_deg),
+    ] {
+        if deg != 0 && deg != 1 {
+            return Err(PyValueError::new_err(format!("{} must be either 0 or 1", name)));
+        }
+    }
+    for (name, jump) in ["seasonal_jump", "trend_jump", "low_pass_jump"].iter().zip(jumps) {
+        if let Some(JumpArg::Points(0)) = jump {
+            return Err(PyValueError::new_err(format!("{} must be a positive integer", name)));
+        }
+    }
+    Ok(())
 }
 
 #[pymethods]
 impl STL {
     #[new]
+    #[allow(clippy::too_many_arguments)]
     #[pyo3(signature = (
         endog, 
         *, 
         period=None, 
+        freq=None,
         seasonal=7,
         trend=None, 
         low_pass=None, 
@@ -47,13 +497,21 @@ impl STL {
         trend_deg=1, 
         low_pass_deg=1, 
         robust=false, 
-        seasonal_jump=1, 
-        trend_jump=1, 
-        low_pass_jump=1
+        seasonal_jump=JumpArg::Points(1), 
+        trend_jump=JumpArg::Points(1), 
+        low_pass_jump=JumpArg::Points(1),
+        compat=None,
+        partial_cycle="allow",
+        trading_day=None,
+        holidays=None,
+        regressors=None,
+        start=None
     ))]
     fn new(
-        endog: Vec<f64>,
-        period: Option<usize>,
+        py: Python,
+        endog: &Bound<'_, PyAny>,
+        period: Option<PeriodArg>,
+        freq: Option<&str>,
         seasonal: usize,
         trend: Option<usize>,
         low_pass: Option<usize>,
@@ -61,26 +519,76 @@ impl STL {
         trend_deg: i32,
         low_pass_deg: i32,
         robust: bool,
-        seasonal_jump: Option<usize>,
-        trend_jump: Option<usize>,
-        low_pass_jump: Option<usize>,
+        seasonal_jump: Option<JumpArg>,
+        trend_jump: Option<JumpArg>,
+        low_pass_jump: Option<JumpArg>,
+        compat: Option<&str>,
+        partial_cycle: &str,
+        trading_day: Option<(i32, u32)>,
+        holidays: Option<&Bound<'_, PyDict>>,
+        regressors: Option<&Bound<'_, PyDict>>,
+        start: Option<(i32, u32)>,
     ) -> PyResult<Self> {
+        let strict = parse_compat(compat)?;
+        let partial_cycle = parse_partial_cycle(partial_cycle)?;
+
         // If period is not provided, try to infer it or raise an error
-        let period = period.ok_or_else(|| {
-            PyValueError::new_err("Period must be specified for ndarray input")
-        })?;
+        let period = match period {
+            Some(period) => resolve_period(period, freq)?,
+            None => match freq.and_then(freq_to_period) {
+                Some(period) => period,
+                None => infer_period(endog)?.ok_or_else(|| {
+                    PyValueError::new_err(if strict {
+                        "Unable to determine period from endog"
+                    } else {
+                        "Period must be specified for ndarray input"
+                    })
+                })?,
+            },
+        };
+
+        if strict {
+            validate_statsmodels(
+                period,
+                seasonal,
+                trend,
+                low_pass,
+                seasonal_deg,
+                trend_deg,
+                low_pass_deg,
+                [seasonal_jump, trend_jump, low_pass_jump],
+            )?;
+        }
+
+        // holidays and regressors keep the order they were given in
+        let holidays: Vec<(String, Dates)> = holidays
+            .map(|h| h.iter().map(|(k, v)| PyResult::Ok((k.extract()?, v.extract()?))).collect())
+            .transpose()?
+            .unwrap_or_default();
+        let regressors: Vec<(String, Vec<f64>)> = regressors
+            .map(|r| r.iter().map(|(k, v)| PyResult::Ok((k.extract()?, v.extract()?))).collect())
+            .transpose()?
+            .unwrap_or_default();
+        if start.is_none() && !holidays.is_empty() {
+            return Err(PyValueError::new_err("holidays requires start=(year, month)"));
+        }
+
+        let index = pandas_index(endog)?;
+        let endog = series_data(endog)?;
+        let nobs = endog.len(py);
 
         // Validate that we have at least 2 complete cycles
-        if endog.len() < period * 2 {
+        if nobs < period * 2 {
             return Err(PyValueError::new_err(format!(
                 "endog must have 2 complete cycles requires {} observations. endog only has {} observation(s)",
                 period * 2,
-                endog.len()
+                nobs
             )));
         }
 
         Ok(Self {
             data: endog,
+            index,
             period,
             seasonal: Some(seasonal),
             trend,
@@ -94,14 +602,28 @@ impl STL {
             low_pass_jump,
             inner_loops: None,
             outer_loops: None,
+            strict,
+            partial_cycle,
+            trading_day,
+            holidays,
+            regressors,
+            start,
         })
     }
 
     /// Fit with GIL release for better multi-threading performance
     #[pyo3(signature = (inner_iter=None, outer_iter=None))]
     fn fit(&self, py: Python, inner_iter: Option<usize>, outer_iter: Option<usize>) -> PyResult<PySTLResult> {
-        // Clone data needed for computation
-        let data = self.data.clone();
+        // Arrays are copied while holding the GIL, since Python threads can
+        // write to them while the fit runs without it
+        let copied;
+        let data: &[f64] = match &self.data {
+            SeriesData::Array(a) => {
+                copied = a.bind(py).readonly().as_array().to_vec();
+                &copied
+            }
+            SeriesData::Vec(data) => data,
+        };
         let period = self.period;
         let seasonal_length = self.seasonal.unwrap();
         let trend = self.trend;
@@ -115,63 +637,86 @@ impl STL {
         let low_pass_jump = self.low_pass_jump;
         let inner_loops = self.inner_loops;
         let outer_loops = self.outer_loops;
+        let strict = self.strict;
+        let partial_cycle = self.partial_cycle;
 
-        // Release GIL during computation
-        let result = py.allow_threads(|| {
-            let mut params = StlParams::new();
+        let mut params = StlParams::new();
+        params.partial_cycle(partial_cycle);
+        if let Some((start_year, start_month)) = self.trading_day {
+            params.trading_day(start_year, start_month);
+        }
+        if let Some((start_year, start_month)) = self.start {
+            for (name, dates) in &self.holidays {
+                params.holiday(name, start_year, start_month, dates);
+            }
+        }
+        for (name, values) in &self.regressors {
+            params.regressor(name, values);
+        }
 
-            // Set seasonal length (use provided value, not period default)
-            params.seasonal_length(seasonal_length);
+        // Set seasonal length (use provided value, not period default)
+        params.seasonal_length(seasonal_length);
 
-            // Set trend length with statsmodels default calculation
-            if let Some(trend) = trend {
-                params.trend_length(trend);
-            } else {
-                let seasonal_len = if seasonal_length % 2 == 0 { 
-                    seasonal_length + 1 
-                } else { 
-                    seasonal_length 
-                };
-                
-                let trend_len = ((1.5 * period as f64) / (1.0 - 1.5 / seasonal_len as f64)).ceil() as usize;
-                let trend_len = if trend_len % 2 == 0 { trend_len + 1 } else { trend_len };
-                params.trend_length(trend_len.max(3));
-            }
+        // Set trend length with statsmodels default calculation
+        if let Some(trend) = trend {
+            params.trend_length(trend);
+        } else {
+            let seasonal_len = if seasonal_length.is_multiple_of(2) { 
+                seasonal_length + 1 
+            } else { 
+                seasonal_length 
+            };
+            
+            let trend_len = ((1.5 * period as f64) / (1.0 - 1.5 / seasonal_len as f64)).ceil() as usize;
+            let trend_len = if trend_len.is_multiple_of(2) { trend_len + 1 } else { trend_len };
+            params.trend_length(trend_len.max(3));
+        }
 
-            // Set low pass length (default is smallest odd number >= period)
-            if let Some(low_pass) = low_pass {
-                params.low_pass_length(low_pass);
-            } else {
-                let low_pass_len = if period % 2 == 0 { period + 1 } else { period };
-                params.low_pass_length(low_pass_len);
-            }
+        // Set low pass length (default is smallest odd number >= period,
+        // or > period to match statsmodels)
+        if let Some(low_pass) = low_pass {
+            params.low_pass_length(low_pass);
+        } else if strict {
+            let low_pass_len = period + 1;
+            let low_pass_len = if low_pass_len.is_multiple_of(2) { low_pass_len + 1 } else { low_pass_len };
+            params.low_pass_length(low_pass_len);
+        } else {
+            let low_pass_len = if period.is_multiple_of(2) { period + 1 } else { period };
+            params.low_pass_length(low_pass_len);
+        }
 
-            // Set degrees
-            params.seasonal_degree(seasonal_deg);
-            params.trend_degree(trend_deg);
-            params.low_pass_degree(low_pass_deg);
+        // Set degrees
+        params.seasonal_degree(seasonal_deg);
+        params.trend_degree(trend_deg);
+        params.low_pass_degree(low_pass_deg);
 
-            params.seasonal_jump(seasonal_jump.unwrap_or(1));
-            params.trend_jump(trend_jump.unwrap_or(1));
-            params.low_pass_jump(low_pass_jump.unwrap_or(1));
+        let one = JumpArg::Points(1);
+        set_jump(&mut params, seasonal_jump.unwrap_or(one), StlParams::seasonal_jump, StlParams::seasonal_jump_fraction);
+        set_jump(&mut params, trend_jump.unwrap_or(one), StlParams::trend_jump, StlParams::trend_jump_fraction);
+        set_jump(&mut params, low_pass_jump.unwrap_or(one), StlParams::low_pass_jump, StlParams::low_pass_jump_fraction);
 
-            // Set robustness
-            params.robust(robust);
+        // Set robustness
+        params.robust(robust);
 
-            let inner_loops_val = inner_iter.or(inner_loops).unwrap_or(
-                if robust { 2 } else { 5 }
-            );
-            let outer_loops_val = outer_iter.or(outer_loops).unwrap_or(
-                if robust { 15 } else { 0 }
-            );
-            
-            params.inner_loops(inner_loops_val);
-            params.outer_loops(outer_loops_val);
+        let inner_loops_val = inner_iter.or(inner_loops).unwrap_or(
+            if robust { 2 } else { 5 }
+        );
+        let outer_loops_val = outer_iter.or(outer_loops).unwrap_or(
+            if robust { 15 } else { 0 }
+        );
+        
+        params.inner_loops(inner_loops_val);
+        params.outer_loops(outer_loops_val);
 
-            params.fit(&data, period)
-        })?;
+        warn_params(py, &params, period)?;
+
+        // Release GIL during computation
+        let result = py.allow_threads(|| params.fit(data, period))?;
 
-        Ok(PySTLResult { inner: result })
+        Ok(PySTLResult {
+            inner: result,
+            index: self.index.as_ref().map(|index| index.clone_ref(py)),
+        })
     }
 
     #[getter]
@@ -180,8 +725,8 @@ impl STL {
     }
 
     #[getter]
-    fn nobs(&self) -> usize {
-        self.data.len()
+    fn nobs(&self, py: Python) -> usize {
+        self.data.len(py)
     }
 
     #[getter]
@@ -190,162 +735,1680 @@ impl STL {
     }
 }
 
-impl From<Error> for PyErr {
-    fn from(err: Error) -> PyErr {
-        PyValueError::new_err(err.to_string())
+/// The leakage check as a dict of plain lists
+fn leakage_dict<'py>(py: Python<'py>, check: &Leakage) -> PyResult<Bound<'py, PyDict>> {
+    let correlations = PyDict::new(py);
+    for (label, values) in check.labels().iter().zip(check.correlations()) {
+        correlations.set_item(label, values)?;
+    }
+    let dict = PyDict::new(py);
+    dict.set_item("lags", check.lags())?;
+    dict.set_item("correlations", correlations)?;
+    dict.set_item("threshold", check.threshold())?;
+    dict.set_item("leaking", check.leaking())?;
+    dict.set_item("detected", check.detected())?;
+    Ok(dict)
+}
+
+/// Parses a trend direction
+fn parse_monotone(direction: &str) -> PyResult<Monotone> {
+    match direction {
+        "increasing" => Ok(Monotone::Increasing),
+        "decreasing" => Ok(Monotone::Decreasing),
+        _ => Err(PyValueError::new_err("direction must be 'increasing' or 'decreasing'")),
+    }
+}
+
+/// Parses the components constrained to be non-negative
+fn parse_non_negative(component: &str) -> PyResult<NonNegative> {
+    match component {
+        "trend" => Ok(NonNegative::Trend),
+        "fitted" => Ok(NonNegative::Fitted),
+        _ => Err(PyValueError::new_err("non_negative must be 'trend' or 'fitted'")),
+    }
+}
+
+/// Parses the component shared by jointly decomposed series
+fn parse_shared(shared: &str) -> PyResult<Shared> {
+    match shared {
+        "trend" => Ok(Shared::Trend),
+        "seasonal" => Ok(Shared::Seasonal),
+        _ => Err(PyValueError::new_err("shared must be 'trend' or 'seasonal'")),
+    }
+}
+
+/// Builds the inner STL parameters from statsmodels-style keyword arguments
+fn stl_params_from_kwargs(kwargs: &Bound<'_, PyDict>) -> PyResult<StlParams> {
+    let mut params = StlParams::new();
+    for (key, value) in kwargs.iter() {
+        let key = key.extract::<String>()?;
+        match key.as_str() {
+            "trend" => params.trend_length(value.extract()?),
+            "low_pass" => params.low_pass_length(value.extract()?),
+            "seasonal_deg" => params.seasonal_degree(value.extract()?),
+            "trend_deg" => params.trend_degree(value.extract()?),
+            "low_pass_deg" => params.low_pass_degree(value.extract()?),
+            "robust" => params.robust(value.extract()?),
+            "seasonal_jump" => set_jump(
+                &mut params,
+                value.extract()?,
+                StlParams::seasonal_jump,
+                StlParams::seasonal_jump_fraction,
+            ),
+            "trend_jump" => set_jump(
+                &mut params,
+                value.extract()?,
+                StlParams::trend_jump,
+                StlParams::trend_jump_fraction,
+            ),
+            "low_pass_jump" => set_jump(
+                &mut params,
+                value.extract()?,
+                StlParams::low_pass_jump,
+                StlParams::low_pass_jump_fraction,
+            ),
+            "inner_iter" => params.inner_loops(value.extract()?),
+            "outer_iter" => params.outer_loops(value.extract()?),
+            "twicing" => params.twicing(value.extract()?),
+            "winsorize" => params.winsorize(value.extract()?),
+            "detrend" => params.detrend(value.extract()?),
+            "quantile" => params.quantile(value.extract()?),
+            "seasonal_penalty" => params.seasonal_penalty(value.extract()?),
+            "zero_sum_seasonal" => params.zero_sum_seasonal(value.extract()?),
+            "monotone_trend" => params.monotone_trend(parse_monotone(&value.extract::<String>()?)?),
+            "non_negative" => params.non_negative(parse_non_negative(&value.extract::<String>()?)?),
+            "seasonal" | "period" => {
+                return Err(PyValueError::new_err(format!(
+                    "{} cannot be set in stl_kwargs, use periods and windows instead",
+                    key
+                )));
+            }
+            _ => {
+                return Err(PyTypeError::new_err(format!(
+                    "unexpected keyword argument in stl_kwargs: '{}'",
+                    key
+                )));
+            }
+        };
     }
+    Ok(params)
 }
 
 #[pyclass]
-pub struct PySTLResult {
-    inner: StlResult,
+pub struct MSTL {
+    data: Vec<f64>,
+    periods: Vec<usize>,
+    windows: Option<Vec<usize>>,
+    lmbda: Option<LambdaArg>,
+    shift: Option<ShiftArg>,
+    iterate: usize,
+    stl_params: StlParams,
+    fourier_terms: Vec<(usize, usize)>,
+    final_trend: Option<(usize, i32)>,
+    period_names: HashMap<usize, String>,
+    damping: f64,
 }
 
 #[pymethods]
-impl PySTLResult {
-    #[getter]
-    fn seasonal(&self) -> Vec<f64> {
-        self.inner.seasonal().to_vec()
+impl MSTL {
+    #[new]
+    #[pyo3(signature = (endog, *, periods=None, windows=None, lmbda=None, shift=None, iterate=2, stl_kwargs=None, freq=None, fourier_terms=None, final_trend=None, period_names=None, damping=1.0))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        endog: &Bound<'_, PyAny>,
+        periods: Option<Vec<PeriodArg>>,
+        windows: Option<Vec<usize>>,
+        lmbda: Option<LambdaArg>,
+        shift: Option<ShiftArg>,
+        iterate: usize,
+        stl_kwargs: Option<&Bound<'_, PyDict>>,
+        freq: Option<&str>,
+        fourier_terms: Option<HashMap<usize, usize>>,
+        final_trend: Option<(usize, i32)>,
+        period_names: Option<HashMap<usize, String>>,
+        damping: f64,
+    ) -> PyResult<Self> {
+        let periods = match periods {
+            Some(periods) => periods
+                .into_iter()
+                .map(|p| resolve_period(p, freq))
+                .collect::<PyResult<Vec<usize>>>()?,
+            None => match freq.and_then(freq_to_period) {
+                Some(period) => vec![period],
+                None => vec![infer_period(endog)?.ok_or_else(|| {
+                    PyValueError::new_err("Periods must be specified for ndarray input")
+                })?],
+            },
+        };
+
+        // Validate lambda and shift before fitting
+        set_lambda(&mut Mstl::params(), lmbda.as_ref())?;
+        set_shift(&mut Mstl::params(), shift.as_ref())?;
+
+        Ok(Self {
+            data: extract_series(endog)?,
+            periods,
+            windows,
+            lmbda,
+            shift,
+            iterate,
+            stl_params: match stl_kwargs {
+                Some(kwargs) => stl_params_from_kwargs(kwargs)?,
+                None => StlParams::new(),
+            },
+            fourier_terms: fourier_terms.unwrap_or_default().into_iter().collect(),
+            final_trend,
+            period_names: period_names.unwrap_or_default(),
+            damping,
+        })
     }
 
-    #[getter]
-    fn trend(&self) -> Vec<f64> {
-        self.inner.trend().to_vec()
+    /// Fit with GIL release for better multi-threading performance
+    fn fit(&self, py: Python) -> PyResult<PyMstlResult> {
+        let mut params = Mstl::params();
+        params
+            .iterations(self.iterate)
+            .stl_params(self.stl_params.clone())
+            .damping(self.damping)
+            .keep_observed(true);
+        if let Some(windows) = &self.windows {
+            params.seasonal_lengths(windows);
+        }
+        for &(period, terms) in &self.fourier_terms {
+            params.fourier_terms(period, terms);
+        }
+        if let Some((length, degree)) = self.final_trend {
+            params.final_trend(length, degree);
+        }
+        for (&period, name) in &self.period_names {
+            params.period_name(period, name);
+        }
+        set_lambda(&mut params, self.lmbda.as_ref())?;
+        set_shift(&mut params, self.shift.as_ref())?;
+        for &period in &self.periods {
+            warn_params(py, &self.stl_params, period)?;
+        }
+
+        let result = py.allow_threads(|| params.fit(&self.data, &self.periods))?;
+        Ok(PyMstlResult { inner: result })
     }
 
     #[getter]
-    fn remainder(&self) -> Vec<f64> {
-        self.inner.remainder().to_vec()
+    fn periods(&self) -> Vec<usize> {
+        self.periods.clone()
     }
 
     #[getter]
-    fn resid(&self) -> Vec<f64> {
-        self.inner.remainder().to_vec()
+    fn nobs(&self) -> usize {
+        self.data.len()
     }
+}
 
-    #[getter]
-    fn weights(&self) -> Vec<f64> {
-        self.inner.weights().to_vec()
+impl From<Error> for PyErr {
+    fn from(err: Error) -> PyErr {
+        PyValueError::new_err(err.to_string())
     }
+}
+
+/// Sample standard deviation, skipping missing values
+fn std_dev(values: &[f64]) -> f64 {
+    let values = values.iter().copied().filter(|v| !v.is_nan()).collect::<Vec<f64>>();
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (values.len() as f64 - 1.0)).sqrt()
+}
+
+/// Formats results and parameters as a two-column text table
+fn summary_table(title: &str, results: &[(&str, String)], params: &[(&str, String)]) -> String {
+    const WIDTH: usize = 50;
+    let row = |(label, value): &(&str, String)| {
+        let label = format!("{}:", label);
+        format!("{}{:>width$}\n", label, value, width = WIDTH - label.len())
+    };
+    let title = format!("{:^WIDTH$}", title);
+    let mut table = format!("{}\n{}\n", title.trim_end(), "=".repeat(WIDTH));
+    table.extend(results.iter().map(row));
+    table.push_str(&"-".repeat(WIDTH));
+    table.push('\n');
+    table.extend(params.iter().map(row));
+    table.push_str(&"=".repeat(WIDTH));
+    table
+}
+
+/// Read-only view of a result component that numpy can wrap without copying
+#[pyclass(name = "Component", frozen)]
+pub struct PyComponent {
+    // keeps the result that owns the data alive
+    _owner: PyObject,
+    ptr: usize,
+    len: usize,
+}
 
-    fn seasonal_strength(&self) -> f64 {
-        self.inner.seasonal_strength()
+impl PyComponent {
+    fn new(owner: &Bound<'_, PyAny>, values: &[f64]) -> Self {
+        Self {
+            _owner: owner.clone().unbind(),
+            ptr: values.as_ptr() as usize,
+            len: values.len(),
+        }
     }
 
-    fn trend_strength(&self) -> f64 {
-        self.inner.trend_strength()
+    fn values(&self) -> &[f64] {
+        // SAFETY: the owner keeps the result alive, and results are never
+        // mutated after they are created, so the slice stays valid
+        unsafe { std::slice::from_raw_parts(self.ptr as *const f64, self.len) }
     }
+}
 
+#[pymethods]
+impl PyComponent {
     #[getter]
-    fn seasonal_component(&self) -> Vec<f64> {
-        self.inner.seasonal().to_vec()
+    fn __array_interface__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let interface = PyDict::new(py);
+        interface.set_item("shape", (self.len,))?;
+        interface.set_item("typestr", if cfg!(target_endian = "little") { "<f8" } else { ">f8" })?;
+        interface.set_item("data", (self.ptr, true))?;
+        interface.set_item("version", 3)?;
+        Ok(interface)
     }
 
-    #[getter]
-    fn trend_component(&self) -> Vec<f64> {
-        self.inner.trend().to_vec()
+    #[pyo3(signature = (*, stream=None, max_version=None, dl_device=None, copy=None))]
+    fn __dlpack__(
+        slf: &Bound<'_, Self>,
+        stream: Option<PyObject>,
+        max_version: Option<(u32, u32)>,
+        dl_device: Option<(i32, i32)>,
+        copy: Option<bool>,
+    ) -> PyResult<PyObject> {
+        let _ = stream;
+        if dl_device.is_some_and(|device| device != dlpack::CPU) {
+            return Err(PyBufferError::new_err("components can only be exported to the CPU"));
+        }
+        let this = slf.get();
+        // SAFETY: the component keeps the result alive, and results are never
+        // mutated after they are created
+        unsafe {
+            dlpack::export(slf.as_any(), this.ptr as *const std::ffi::c_void, this.len, 64, max_version, copy)
+        }
     }
 
-    #[getter]
-    fn nobs(&self) -> usize {
-        self.inner.seasonal().len()
+    fn __dlpack_device__(&self) -> (i32, i32) {
+        dlpack::CPU
     }
-}
 
-#[pyclass]
-pub struct PyMstlResult {
-    inner: MstlResult,
-}
+    fn __len__(&self) -> usize {
+        self.len
+    }
 
-#[pymethods]
-impl PyMstlResult {
-    #[getter]
-    fn seasonal(&self) -> Vec<Vec<f64>> {
-        self.inner.seasonal().iter().map(|s| s.to_vec()).collect()
+    fn __getitem__(&self, py: Python, index: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        if let Ok(slice) = index.downcast::<PySlice>() {
+            let indices = slice.indices(self.len as isize)?;
+            let values = (0..indices.slicelength)
+                .map(|i| self.values()[(indices.start + i as isize * indices.step) as usize])
+                .collect::<Vec<f64>>();
+            return Ok(values.into_pyobject(py)?.into_any().unbind());
+        }
+        let i: isize = index.extract()?;
+        let j = if i < 0 { i + self.len as isize } else { i };
+        if j < 0 || j >= self.len as isize {
+            return Err(pyo3::exceptions::PyIndexError::new_err("index out of range"));
+        }
+        Ok(self.values()[j as usize].into_pyobject(py)?.into_any().unbind())
     }
 
-    #[getter]
-    fn trend(&self) -> Vec<f64> {
-        self.inner.trend().to_vec()
+    fn __iter__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        PyList::new(py, self.values())?.try_iter().map(|it| it.into_any())
     }
 
-    #[getter]
-    fn remainder(&self) -> Vec<f64> {
-        self.inner.remainder().to_vec()
+    fn __eq__(&self, other: &Bound<'_, PyAny>) -> bool {
+        other.extract::<Vec<f64>>().is_ok_and(|other| other == self.values())
     }
 
-    fn seasonal_strength(&self) -> Vec<f64> {
-        self.inner.seasonal_strength()
+    fn __repr__(&self) -> String {
+        format!("Component({:?})", self.values())
     }
 
-    fn trend_strength(&self) -> f64 {
-        self.inner.trend_strength()
+    fn tolist(&self) -> Vec<f64> {
+        self.values().to_vec()
     }
 }
 
 #[pyclass]
-pub struct PyStlParams {
-    inner: StlParams,
+pub struct PySTLResult {
+    inner: StlResult,
+    index: Option<PyObject>,
 }
 
-#[pymethods]
-impl PyStlParams {
-    #[new]
-    fn new() -> Self {
-        Self {
-            inner: StlParams::new(),
+impl PySTLResult {
+    /// Returns a read-only pandas Series over the values with the input's
+    /// index, or a Component without one, so neither copies the values
+    fn component(slf: &Bound<'_, Self>, values: fn(&StlResult) -> &[f64], name: &str) -> PyResult<PyObject> {
+        let py = slf.py();
+        let this = slf.borrow();
+        let values = values(&this.inner);
+        let component = Bound::new(py, PyComponent::new(slf.as_any(), values))?;
+        match &this.index {
+            Some(index) => {
+                // a truncated partial cycle leaves fewer values than index labels
+                let index = index
+                    .bind(py)
+                    .get_item(PySlice::new(py, 0, values.len() as isize, 1))?;
+                let array = PyModule::import(py, "numpy")?.getattr("asarray")?.call1((component,))?;
+                let kwargs = PyDict::new(py);
+                kwargs.set_item("index", index)?;
+                kwargs.set_item("name", name)?;
+                kwargs.set_item("copy", false)?;
+                Ok(PyModule::import(py, "pandas")?
+                    .getattr("Series")?
+                    .call((array,), Some(&kwargs))?
+                    .unbind())
+            }
+            None => Ok(component.into_any().unbind()),
         }
     }
+}
 
-    fn seasonal_length(&mut self, length: usize) -> PyResult<()> {
-        self.inner.seasonal_length(length);
-        Ok(())
+#[pymethods]
+impl PySTLResult {
+    #[getter]
+    fn seasonal(slf: &Bound<'_, Self>) -> PyResult<PyObject> {
+        Self::component(slf, StlResult::seasonal, "season")
     }
 
-    fn trend_length(&mut self, length: usize) -> PyResult<()> {
-        self.inner.trend_length(length);
-        Ok(())
+    #[getter]
+    fn trend(slf: &Bound<'_, Self>) -> PyResult<PyObject> {
+        Self::component(slf, StlResult::trend, "trend")
     }
 
-    fn robust(&mut self, robust: bool) -> PyResult<()> {
-        self.inner.robust(robust);
-        Ok(())
+    #[getter]
+    fn remainder(slf: &Bound<'_, Self>) -> PyResult<PyObject> {
+        Self::component(slf, StlResult::remainder, "resid")
     }
 
-    fn fit(&self, py: Python, series: Vec<f64>, period: usize) -> PyResult<PySTLResult> {
-        let result = py.allow_threads(|| {
-            self.inner.fit(&series, period)
-        })?;
-        Ok(PySTLResult { inner: result })
+    #[getter]
+    fn resid(slf: &Bound<'_, Self>) -> PyResult<PyObject> {
+        Self::component(slf, StlResult::remainder, "resid")
     }
-}
 
-/// Convenience function for STL decomposition with GIL release
-#[pyfunction]
-fn stl_decompose(py: Python, series: Vec<f64>, period: usize) -> PyResult<PySTLResult> {
-    let result = py.allow_threads(|| {
-        Stl::fit(&series, period)
-    })?;
-    Ok(PySTLResult { inner: result })
-}
+    #[getter]
+    fn weights(slf: &Bound<'_, Self>) -> PyResult<PyObject> {
+        Self::component(slf, StlResult::weights, "robust_weight")
+    }
 
-/// Convenience function for MSTL decomposition with GIL release
+    /// A zero-copy, read-only view of "seasonal", "trend", "resid" or
+    /// "weights" that numpy and DLPack consumers can wrap without copying
+    fn view(slf: &Bound<'_, Self>, name: &str) -> PyResult<PyComponent> {
+        let this = slf.borrow();
+        let r = &this.inner;
+        let values = match name {
+            "seasonal" => r.seasonal(),
+            "trend" => r.trend(),
+            "resid" | "remainder" => r.remainder(),
+            "weights" => r.weights(),
+            _ => {
+                return Err(PyValueError::new_err(
+                    "name must be 'seasonal', 'trend', 'resid' or 'weights'",
+                ));
+            }
+        };
+        Ok(PyComponent::new(slf.as_any(), values))
+    }
+
+    /// Indices of the observations clipped by winsorization
+    #[getter]
+    fn clipped(&self) -> Vec<usize> {
+        self.inner.clipped().to_vec()
+    }
+
+    #[pyo3(signature = (variance="sample", trim=0.1, method="variance_ratio"))]
+    fn seasonal_strength(&self, variance: &str, trim: f64, method: &str) -> PyResult<f64> {
+        let (variance, method) = parse_strength_args(variance, trim, method)?;
+        Ok(match method {
+            StrengthMethod::VarianceRatio => self.inner.seasonal_strength_with(variance),
+            method => self.inner.seasonal_strength_by(method),
+        })
+    }
+
+    fn seasonal_strength_anova(&self) -> f64 {
+        self.inner.seasonal_strength_anova()
+    }
+
+    #[pyo3(signature = (n_surrogates=99, *, seed))]
+    fn seasonal_strength_pvalue(&self, py: Python, n_surrogates: usize, seed: u64) -> PyResult<f64> {
+        Ok(py.allow_threads(|| self.inner.seasonal_strength_pvalue(n_surrogates, seed))?)
+    }
+
+    #[pyo3(signature = (variance="sample", trim=0.1))]
+    fn trend_strength(&self, variance: &str, trim: f64) -> PyResult<f64> {
+        Ok(self.inner.trend_strength_with(parse_variance(variance, trim)?))
+    }
+
+    /// Formatted table of the fit and the parameters used
+    fn summary(&self) -> String {
+        let r = &self.inner;
+        summary_table(
+            "STL Decomposition Results",
+            &[
+                ("Number of Observations", r.seasonal().len().to_string()),
+                ("Period", r.period().to_string()),
+                ("Seasonal Strength", format!("{:.4}", r.seasonal_strength())),
+                ("Seasonal Strength (ANOVA)", format!("{:.4}", r.seasonal_strength_anova())),
+                ("Trend Strength", format!("{:.4}", r.trend_strength())),
+                ("Remainder Std", format!("{:.4}", std_dev(r.remainder()))),
+            ],
+            &[
+                ("Seasonal Length", r.seasonal_length.to_string()),
+                ("Seasonal Degree", r.seasonal_degree.to_string()),
+                ("Trend Length", r.trend_length.to_string()),
+                ("Trend Degree", r.trend_degree.to_string()),
+            ],
+        )
+    }
+
+    #[getter]
+    fn seasonal_component(slf: &Bound<'_, Self>) -> PyResult<PyObject> {
+        Self::component(slf, StlResult::seasonal, "season")
+    }
+
+    #[getter]
+    fn trend_component(slf: &Bound<'_, Self>) -> PyResult<PyObject> {
+        Self::component(slf, StlResult::trend, "trend")
+    }
+
+    #[getter]
+    fn nobs(&self) -> usize {
+        self.inner.seasonal().len()
+    }
+
+    /// Regression coefficients removed before decomposing, keyed by regressor name
+    #[getter]
+    fn regression_coefficients<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyDict>>> {
+        self.inner
+            .regression()
+            .map(|regression| {
+                let coefficients = PyDict::new(py);
+                for (name, coef) in regression.names().iter().zip(regression.coefficients()) {
+                    coefficients.set_item(name, coef)?;
+                }
+                Ok(coefficients)
+            })
+            .transpose()
+    }
+
+    #[getter]
+    fn regression_effect(slf: &Bound<'_, Self>) -> PyResult<Option<PyObject>> {
+        if slf.borrow().inner.regression().is_none() {
+            return Ok(None);
+        }
+        Self::component(slf, |r| r.regression().map_or(&[], |g| g.effect()), "regression").map(Some)
+    }
+
+    /// Counts and timings (in seconds) of the work done by the fit
+    #[getter]
+    fn fit_report<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let report = self.inner.fit_report();
+        let dict = PyDict::new(py);
+        dict.set_item("loess_evaluations", report.loess_evaluations())?;
+        dict.set_item("interpolated_points", report.interpolated_points())?;
+        dict.set_item("inner_iterations", report.inner_iterations())?;
+        dict.set_item("robustness_iterations", report.robustness_iterations())?;
+        dict.set_item("seasonal_time", report.seasonal_time().as_secs_f64())?;
+        dict.set_item("low_pass_time", report.low_pass_time().as_secs_f64())?;
+        dict.set_item("trend_time", report.trend_time().as_secs_f64())?;
+        Ok(dict)
+    }
+
+    /// Aggregates every factor observations of each component with "sum" or
+    /// "mean", dropping a trailing incomplete bucket; the factor must divide
+    /// the period and leave a period of at least 2
+    #[pyo3(signature = (factor, agg="sum"))]
+    fn resample(&self, factor: usize, agg: &str) -> PyResult<Self> {
+        let agg = match agg {
+            "sum" => AggFn::Sum,
+            "mean" => AggFn::Mean,
+            _ => return Err(PyValueError::new_err("agg must be 'sum' or 'mean'")),
+        };
+        Ok(Self { inner: self.inner.resample(factor, agg)?, index: None })
+    }
+
+    /// Autocorrelation of the remainder at the period and its harmonics, and
+    /// whether any exceeds the threshold for leftover seasonality
+    fn check_residual_seasonality<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let check = self.inner.check_residual_seasonality();
+        let dict = PyDict::new(py);
+        dict.set_item("lags", check.lags())?;
+        dict.set_item("acf", check.acf())?;
+        dict.set_item("threshold", check.threshold())?;
+        dict.set_item("detected", check.detected())?;
+        Ok(dict)
+    }
+
+    /// Correlation of the remainder with the trend and seasonal component at
+    /// lag 0 and the period, and the components that leaked into it
+    fn check_leakage<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        leakage_dict(py, &self.inner.check_leakage())
+    }
+
+    /// Plain lists and metadata that can be serialized as JSON (without the pandas index)
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let r = &self.inner;
+        let dict = PyDict::new(py);
+        dict.set_item("version", RESULT_DICT_VERSION)?;
+        dict.set_item("period", r.period)?;
+        dict.set_item("seasonal_length", r.seasonal_length)?;
+        dict.set_item("seasonal_degree", r.seasonal_degree)?;
+        dict.set_item("trend_length", r.trend_length)?;
+        dict.set_item("trend_degree", r.trend_degree)?;
+        dict.set_item("partial_cycle", r.partial_cycle)?;
+        dict.set_item("seasonal", r.seasonal())?;
+        dict.set_item("trend", r.trend())?;
+        dict.set_item("remainder", r.remainder())?;
+        dict.set_item("weights", r.weights())?;
+        if let Some(regression) = r.regression() {
+            let reg = PyDict::new(py);
+            reg.set_item("names", regression.names())?;
+            reg.set_item("coefficients", regression.coefficients())?;
+            reg.set_item("effect", regression.effect())?;
+            dict.set_item("regression", reg)?;
+        }
+        Ok(dict)
+    }
+
+    /// Rebuilds a result from the output of to_dict
+    #[staticmethod]
+    fn from_dict(data: &Bound<'_, PyDict>) -> PyResult<Self> {
+        fn item<'py, T: FromPyObject<'py>>(data: &Bound<'py, PyDict>, key: &str) -> PyResult<T> {
+            data.get_item(key)?
+                .ok_or_else(|| PyValueError::new_err(format!("missing key '{}'", key)))?
+                .extract()
+        }
+
+        let version: u32 = item(data, "version")?;
+        if version != RESULT_DICT_VERSION {
+            return Err(PyValueError::new_err(format!("unsupported version {}", version)));
+        }
+        let seasonal: Vec<f64> = item(data, "seasonal")?;
+        let trend: Vec<f64> = item(data, "trend")?;
+        let remainder: Vec<f64> = item(data, "remainder")?;
+        let weights: Vec<f64> = item(data, "weights")?;
+        let n = seasonal.len();
+        if trend.len() != n || remainder.len() != n || weights.len() != n {
+            return Err(PyValueError::new_err("components must have the same length"));
+        }
+        let period: usize = item(data, "period")?;
+        if period < 2 || period > n {
+            return Err(PyValueError::new_err("period does not match the components"));
+        }
+        let regression = match data.get_item("regression")? {
+            Some(reg) if !reg.is_none() => {
+                let reg = reg.downcast::<PyDict>()?;
+                let names: Vec<String> = item(reg, "names")?;
+                let coefficients: Vec<f64> = item(reg, "coefficients")?;
+                let effect: Vec<f64> = item(reg, "effect")?;
+                if coefficients.len() != names.len() || effect.len() != n {
+                    return Err(PyValueError::new_err("regression does not match the components"));
+                }
+                Some(RegressionEffect { names, coefficients, effect })
+            }
+            _ => None,
+        };
+        let inner = StlResult {
+            seasonal,
+            trend,
+            remainder,
+            weights,
+            period,
+            seasonal_length: item(data, "seasonal_length")?,
+            seasonal_degree: item(data, "seasonal_degree")?,
+            trend_length: item(data, "trend_length")?,
+            trend_degree: item(data, "trend_degree")?,
+            partial_cycle: item(data, "partial_cycle")?,
+            regression,
+            report: FitReport::default(),
+            residual_seasonality: None,
+            clipped: Vec::new(),
+            params: None,
+        };
+        inner.check_settings()?;
+        Ok(PySTLResult { inner, index: None })
+    }
+
+    #[getter]
+    fn partial_cycle_len(&self) -> usize {
+        self.inner.partial_cycle_len()
+    }
+
+    /// First k Fourier harmonics of the seasonal cycle as (order, amplitude, phase)
+    fn seasonal_harmonics(&self, k: usize) -> Vec<(usize, f64, f64)> {
+        self.inner
+            .seasonal_harmonics(k)
+            .iter()
+            .map(|h| (h.order(), h.amplitude(), h.phase()))
+            .collect()
+    }
+
+    /// Scale of the remainder at each observation, varying with the season
+    fn remainder_scale(&self) -> Vec<f64> {
+        self.inner.remainder_scale()
+    }
+
+    fn seasonal_cycle(&self) -> Vec<f64> {
+        self.inner.seasonal_cycle()
+    }
+
+    fn seasonally_adjust(&self, new_series: Vec<f64>, start_phase: usize) -> Vec<f64> {
+        self.inner.seasonally_adjust(&new_series, start_phase)
+    }
+
+    /// Forecasts steps past the end by forecasting the seasonally adjusted
+    /// series and adding back the seasonal cycle
+    #[pyo3(signature = (steps, method="naive", order=(1, 1, 0)))]
+    fn forecast(&self, steps: usize, method: &str, order: (usize, usize, usize)) -> PyResult<Vec<f64>> {
+        Ok(self.inner.forecast(steps, parse_forecast_method(method, order)?))
+    }
+
+    /// Adds the seasonal cycle back onto a forecast of the seasonally adjusted series
+    fn reseasonalize(&self, adjusted_forecast: Vec<f64>) -> Vec<f64> {
+        self.inner.reseasonalize(&adjusted_forecast)
+    }
+
+    fn seasonal_model(&self) -> PySeasonalModel {
+        PySeasonalModel {
+            inner: self.inner.seasonal_model(),
+        }
+    }
+
+    fn trend_slope(&self) -> Vec<f64> {
+        self.inner.trend_slope()
+    }
+
+    /// Returns (index, "peak" | "trough") pairs where the trend changes direction
+    #[pyo3(signature = (min_duration=1))]
+    fn trend_turning_points(&self, min_duration: usize) -> Vec<(usize, &'static str)> {
+        self.inner
+            .trend_turning_points(min_duration)
+            .into_iter()
+            .map(|p| match p {
+                TurningPoint::Peak(i) => (i, "peak"),
+                TurningPoint::Trough(i) => (i, "trough"),
+            })
+            .collect()
+    }
+
+    fn trend_diagnostics(&self) -> PyLoessDiagnostics {
+        PyLoessDiagnostics {
+            inner: self.inner.trend_diagnostics(),
+        }
+    }
+
+    fn seasonal_diagnostics(&self) -> PyLoessDiagnostics {
+        PyLoessDiagnostics {
+            inner: self.inner.seasonal_diagnostics(),
+        }
+    }
+}
+
+#[pyclass]
+pub struct PyLoessDiagnostics {
+    inner: LoessDiagnostics,
+}
+
+#[pymethods]
+impl PyLoessDiagnostics {
+    #[getter]
+    fn leverage(&self) -> Vec<f64> {
+        self.inner.leverage().to_vec()
+    }
+
+    #[getter]
+    fn equivalent_parameters(&self) -> f64 {
+        self.inner.equivalent_parameters()
+    }
+
+    #[getter]
+    fn residual_df(&self) -> f64 {
+        self.inner.residual_df()
+    }
+}
+
+#[pyclass]
+pub struct PySeasonalModel {
+    inner: SeasonalModel,
+}
+
+#[pymethods]
+impl PySeasonalModel {
+    #[getter]
+    fn period(&self) -> usize {
+        self.inner.period()
+    }
+
+    #[getter]
+    fn seasonal_cycle(&self) -> Vec<f64> {
+        self.inner.seasonal_cycle().to_vec()
+    }
+
+    #[getter]
+    fn next_phase(&self) -> usize {
+        self.inner.next_phase()
+    }
+
+    #[getter]
+    fn trend_tail(&self) -> Vec<f64> {
+        self.inner.trend_tail().to_vec()
+    }
+
+    #[getter]
+    fn remainder_scale(&self) -> f64 {
+        self.inner.remainder_scale()
+    }
+
+    fn seasonally_adjust(&self, new_series: Vec<f64>) -> Vec<f64> {
+        self.inner.seasonally_adjust(&new_series)
+    }
+
+    fn save(&self, path: std::path::PathBuf) -> PyResult<()> {
+        let file = std::fs::File::create(path).map_err(|e| Error::Io(e.to_string()))?;
+        Ok(self.inner.save(file)?)
+    }
+
+    #[staticmethod]
+    fn load(path: std::path::PathBuf) -> PyResult<Self> {
+        let file = std::fs::File::open(path).map_err(|e| Error::Io(e.to_string()))?;
+        Ok(Self {
+            inner: SeasonalModel::load(file)?,
+        })
+    }
+}
+
+#[pyclass]
+pub struct PyMstlResult {
+    inner: MstlResult,
+}
+
+#[pymethods]
+impl PyMstlResult {
+    /// Builds a result from its components, such as for tests
+    #[new]
+    #[pyo3(signature = (seasonal, trend, remainder, periods, *, windows=None, observed=None, lmbda=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        seasonal: Vec<Vec<f64>>,
+        trend: Vec<f64>,
+        remainder: Vec<f64>,
+        periods: Vec<usize>,
+        windows: Option<Vec<Option<usize>>>,
+        observed: Option<Vec<f64>>,
+        lmbda: Option<f64>,
+    ) -> PyResult<Self> {
+        let n = trend.len();
+        if remainder.len() != n || seasonal.iter().any(|s| s.len() != n) || observed.as_ref().is_some_and(|o| o.len() != n) {
+            return Err(PyValueError::new_err("components must have the same length"));
+        }
+        if seasonal.len() != periods.len() {
+            return Err(PyValueError::new_err("seasonal must have one component per period"));
+        }
+        let windows = windows.unwrap_or_else(|| vec![None; periods.len()]);
+        if windows.len() != periods.len() {
+            return Err(PyValueError::new_err("windows must have one value per period"));
+        }
+        Ok(PyMstlResult {
+            inner: MstlResult {
+                seasonal,
+                trend,
+                remainder,
+                stl_results: None,
+                names: vec![None; periods.len()],
+                periods,
+                windows,
+                lambda: lmbda,
+                shift: None,
+                observed,
+            },
+        })
+    }
+
+    #[getter]
+    fn seasonal(slf: &Bound<'_, Self>) -> Vec<PyComponent> {
+        let this = slf.borrow();
+        this.inner.seasonal().iter().map(|s| PyComponent::new(slf.as_any(), s)).collect()
+    }
+
+    #[getter]
+    fn trend(slf: &Bound<'_, Self>) -> PyComponent {
+        PyComponent::new(slf.as_any(), slf.borrow().inner.trend())
+    }
+
+    #[getter]
+    fn remainder(slf: &Bound<'_, Self>) -> PyComponent {
+        PyComponent::new(slf.as_any(), slf.borrow().inner.remainder())
+    }
+
+    /// A zero-copy, read-only view of "trend", "resid", "observed", or a
+    /// seasonal component by label (such as "seasonal_7")
+    fn view(slf: &Bound<'_, Self>, name: &str) -> PyResult<PyComponent> {
+        let this = slf.borrow();
+        let r = &this.inner;
+        let values = match name {
+            "trend" => Some(r.trend()),
+            "resid" | "remainder" => Some(r.remainder()),
+            "observed" => r.observed(),
+            _ => r.labels().iter().position(|l| l == name).map(|i| &r.seasonal()[i][..]),
+        };
+        match values {
+            Some(values) => Ok(PyComponent::new(slf.as_any(), values)),
+            None => Err(PyValueError::new_err(format!("no component named {}", name))),
+        }
+    }
+
+    /// The seasonal components keyed by period, or by name ("name"), falling
+    /// back to the period for unnamed ones
+    #[pyo3(signature = (key="period"))]
+    fn seasonal_dict<'py>(&self, py: Python<'py>, key: &str) -> PyResult<Bound<'py, PyDict>> {
+        if key != "period" && key != "name" {
+            return Err(PyValueError::new_err("key must be 'period' or 'name'"));
+        }
+        let r = &self.inner;
+        let dict = PyDict::new(py);
+        for ((s, &period), name) in r.seasonal().iter().zip(r.periods()).zip(r.period_names()) {
+            let component = s.to_vec();
+            match name {
+                Some(name) if key == "name" => dict.set_item(name, component)?,
+                _ => dict.set_item(period, component)?,
+            }
+        }
+        Ok(dict)
+    }
+
+    /// The seasonal component of a period, or None if it wasn't decomposed
+    fn seasonal_for(&self, period: usize) -> Option<Vec<f64>> {
+        self.inner.seasonal_for(period).map(|s| s.to_vec())
+    }
+
+    #[pyo3(signature = (variance="sample", trim=0.1, method="variance_ratio"))]
+    fn seasonal_strength(&self, variance: &str, trim: f64, method: &str) -> PyResult<Vec<f64>> {
+        let (variance, method) = parse_strength_args(variance, trim, method)?;
+        Ok(match method {
+            StrengthMethod::VarianceRatio => self.inner.seasonal_strength_with(variance),
+            method => self.inner.seasonal_strength_by(method),
+        })
+    }
+
+    fn seasonal_strength_anova(&self) -> Vec<f64> {
+        self.inner.seasonal_strength_anova()
+    }
+
+    /// Correlation of the remainder with the trend and each seasonal
+    /// component at lag 0 and the periods, and the components that leaked
+    /// into it
+    fn check_leakage<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        leakage_dict(py, &self.inner.check_leakage())
+    }
+
+    #[getter]
+    fn observed(&self) -> Option<Vec<f64>> {
+        self.inner.observed().map(|o| o.to_vec())
+    }
+
+    #[getter]
+    fn nobs(&self) -> usize {
+        self.inner.nobs()
+    }
+
+    #[getter]
+    fn periods(&self) -> Vec<usize> {
+        self.inner.periods().to_vec()
+    }
+
+    /// Length of the seasonal smoother for each period, or None for periods
+    /// modeled with Fourier terms
+    #[getter]
+    fn windows(&self) -> Vec<Option<usize>> {
+        self.inner.windows().to_vec()
+    }
+
+    #[getter]
+    fn labels(&self) -> Vec<String> {
+        self.inner.labels()
+    }
+
+    #[getter]
+    fn period_names(&self) -> Vec<Option<String>> {
+        self.inner.period_names().to_vec()
+    }
+
+    #[getter]
+    fn lmbda(&self) -> Option<f64> {
+        self.inner.lambda()
+    }
+
+    /// Offset added to the series before the Box-Cox transformation, if any
+    #[getter]
+    fn shift(&self) -> Option<f64> {
+        self.inner.shift()
+    }
+
+    /// Adds the seasonal components back onto a forecast of the seasonally
+    /// adjusted series, reversing any Box-Cox transformation
+    fn reseasonalize(&self, adjusted_forecast: Vec<f64>) -> Vec<f64> {
+        self.inner.reseasonalize(&adjusted_forecast)
+    }
+
+    #[getter]
+    fn stl_results(&self) -> Option<Vec<PySTLResult>> {
+        self.inner.stl_results().map(|results| {
+            results
+                .iter()
+                .map(|r| PySTLResult {
+                    inner: r.clone(),
+                    index: None,
+                })
+                .collect()
+        })
+    }
+
+    #[pyo3(signature = (variance="sample", trim=0.1))]
+    fn trend_strength(&self, variance: &str, trim: f64) -> PyResult<f64> {
+        Ok(self.inner.trend_strength_with(parse_variance(variance, trim)?))
+    }
+
+    /// Formatted table of the fit and the parameters used
+    fn summary(&self) -> String {
+        let r = &self.inner;
+        let list = |v: Vec<String>| v.join(", ");
+        let fmt = |v: &[f64]| list(v.iter().map(|v| format!("{:.4}", v)).collect());
+        summary_table(
+            "MSTL Decomposition Results",
+            &[
+                ("Number of Observations", r.nobs().to_string()),
+                ("Periods", list(r.periods().iter().map(|p| p.to_string()).collect())),
+                ("Seasonal Strength", fmt(&r.seasonal_strength())),
+                ("Seasonal Strength (ANOVA)", fmt(&r.seasonal_strength_anova())),
+                ("Trend Strength", format!("{:.4}", r.trend_strength())),
+                ("Remainder Std", format!("{:.4}", std_dev(r.remainder()))),
+            ],
+            &[(
+                "Box-Cox Lambda",
+                r.lambda().map_or("None".to_string(), |l| format!("{:.4}", l)),
+            )],
+        )
+    }
+}
+
+#[pyclass]
+pub struct PyStlParams {
+    inner: StlParams,
+}
+
+#[pymethods]
+impl PyStlParams {
+    #[new]
+    fn new() -> Self {
+        Self {
+            inner: StlParams::new(),
+        }
+    }
+
+    fn seasonal_length(&mut self, length: usize) -> PyResult<()> {
+        self.inner.seasonal_length(length);
+        Ok(())
+    }
+
+    fn trend_length(&mut self, length: usize) -> PyResult<()> {
+        self.inner.trend_length(length);
+        Ok(())
+    }
+
+    fn robust(&mut self, robust: bool) -> PyResult<()> {
+        self.inner.robust(robust);
+        Ok(())
+    }
+
+    /// Smooths the trend residuals again and adds them back
+    fn twicing(&mut self, twicing: bool) -> PyResult<()> {
+        self.inner.twicing(twicing);
+        Ok(())
+    }
+
+    /// Clips the given fraction of observations in each tail before fitting
+    fn winsorize(&mut self, fraction: f64) -> PyResult<()> {
+        self.inner.winsorize(fraction);
+        Ok(())
+    }
+
+    /// Removes a least squares line before fitting and adds it back to the trend
+    fn detrend(&mut self, detrend: bool) -> PyResult<()> {
+        self.inner.detrend(detrend);
+        Ok(())
+    }
+
+    /// Shrinks cycle-to-cycle variation of the seasonal component towards a periodic pattern
+    fn seasonal_penalty(&mut self, penalty: f64) -> PyResult<()> {
+        self.inner.seasonal_penalty(penalty);
+        Ok(())
+    }
+
+    /// Shifts the seasonal component so each full cycle sums to zero
+    fn zero_sum_seasonal(&mut self, zero_sum: bool) -> PyResult<()> {
+        self.inner.zero_sum_seasonal(zero_sum);
+        Ok(())
+    }
+
+    /// Constrains the trend to be "increasing" or "decreasing"
+    fn monotone_trend(&mut self, direction: &str) -> PyResult<()> {
+        self.inner.monotone_trend(parse_monotone(direction)?);
+        Ok(())
+    }
+
+    /// Constrains the "trend", or the trend and "fitted" values, to be non-negative
+    fn non_negative(&mut self, component: &str) -> PyResult<()> {
+        self.inner.non_negative(parse_non_negative(component)?);
+        Ok(())
+    }
+
+    /// Decomposes a quantile of the series instead of the mean
+    fn quantile(&mut self, quantile: f64) -> PyResult<()> {
+        self.inner.quantile(quantile);
+        Ok(())
+    }
+
+    /// Parameters registered under a name with register_profile
+    #[staticmethod]
+    fn from_profile(name: &str) -> PyResult<Self> {
+        Ok(Self {
+            inner: StlParams::from_profile(name)?,
+        })
+    }
+
+    fn fit(&self, py: Python, series: &Bound<'_, PyAny>, period: usize) -> PyResult<PySTLResult> {
+        let index = pandas_index(series)?;
+        let series = extract_series(series)?;
+        let result = py.allow_threads(|| {
+            self.inner.fit(&series, period)
+        })?;
+        Ok(PySTLResult { inner: result, index })
+    }
+
+    /// Decomposes counts on the log scale with a "poisson" or "negative_binomial"
+    /// model, the latter with the given size (dispersion) parameter
+    #[pyo3(signature = (series, period, family="poisson", size=None))]
+    fn fit_counts(&self, py: Python, series: &Bound<'_, PyAny>, period: usize, family: &str, size: Option<f64>) -> PyResult<PySTLResult> {
+        let model = match (family, size) {
+            ("poisson", None) => CountModel::Poisson,
+            ("negative_binomial", Some(size)) => CountModel::NegativeBinomial(size),
+            ("poisson", Some(_)) => return Err(PyValueError::new_err("size only applies to the negative_binomial family")),
+            ("negative_binomial", None) => return Err(PyValueError::new_err("negative_binomial requires size")),
+            _ => return Err(PyValueError::new_err("family must be 'poisson' or 'negative_binomial'")),
+        };
+        let index = pandas_index(series)?;
+        let series = extract_series(series)?;
+        let result = py.allow_threads(|| self.inner.fit_counts(&series, period, model))?;
+        Ok(PySTLResult { inner: result, index })
+    }
+
+    /// Decomposes several series of the same length together, sharing the trend or the seasonal component
+    #[pyo3(signature = (series, period, shared="trend"))]
+    fn fit_joint(&self, py: Python, series: Vec<Vec<f64>>, period: usize, shared: &str) -> PyResult<Vec<PySTLResult>> {
+        let shared = parse_shared(shared)?;
+        let results = py.allow_threads(|| self.inner.fit_joint(&series, period, shared))?;
+        Ok(results
+            .into_iter()
+            .map(|inner| PySTLResult { inner, index: None })
+            .collect())
+    }
+
+    /// Estimates one seasonal cycle pooled across many aligned series of the same kind
+    fn pooled_seasonal(&self, py: Python, series: Vec<Vec<f64>>, period: usize) -> PyResult<Vec<f64>> {
+        Ok(py.allow_threads(|| self.inner.pooled_seasonal(&series, period))?)
+    }
+
+    /// Decomposes many aligned series with a pooled seasonal cycle, estimating only the trend of each
+    fn fit_pooled(&self, py: Python, series: Vec<Vec<f64>>, period: usize) -> PyResult<Vec<PySTLResult>> {
+        let results = py.allow_threads(|| self.inner.fit_pooled(&series, period))?;
+        Ok(results
+            .into_iter()
+            .map(|inner| PySTLResult { inner, index: None })
+            .collect())
+    }
+}
+
+/// Many named series fitted with shared settings
+#[pyclass(name = "STLBatch")]
+pub struct PyStlBatch {
+    series: BTreeMap<String, Vec<f64>>,
+    period: usize,
+    params: StlParams,
+    max_len: Option<usize>,
+}
+
+impl PyStlBatch {
+    /// Drops the oldest observations beyond max_len
+    fn trim(&mut self, name: &str) {
+        if let (Some(max_len), Some(series)) = (self.max_len, self.series.get_mut(name))
+            && series.len() > max_len
+        {
+            series.drain(..series.len() - max_len);
+        }
+    }
+}
+
+#[pymethods]
+impl PyStlBatch {
+    #[new]
+    #[pyo3(signature = (period, params=None, max_len=None))]
+    fn new(period: usize, params: Option<&PyStlParams>, max_len: Option<usize>) -> PyResult<Self> {
+        if max_len.is_some_and(|max_len| max_len < period * 2) {
+            return Err(PyValueError::new_err("max_len must be at least two periods"));
+        }
+        Ok(Self {
+            series: BTreeMap::new(),
+            period,
+            params: params.map_or_else(StlParams::new, |p| p.inner.clone()),
+            max_len,
+        })
+    }
+
+    /// Adds a series, replacing any series with the same name
+    fn add(&mut self, name: String, series: &Bound<'_, PyAny>) -> PyResult<()> {
+        self.series.insert(name.clone(), extract_series(series)?);
+        self.trim(&name);
+        Ok(())
+    }
+
+    /// Appends new observations to a series, adding it if needed
+    fn append(&mut self, name: String, values: &Bound<'_, PyAny>) -> PyResult<()> {
+        let values = extract_series(values)?;
+        self.series.entry(name.clone()).or_default().extend(values);
+        self.trim(&name);
+        Ok(())
+    }
+
+    /// Removes a series, returning whether it was present
+    fn remove(&mut self, name: &str) -> bool {
+        self.series.remove(name).is_some()
+    }
+
+    #[getter]
+    fn names(&self) -> Vec<String> {
+        self.series.keys().cloned().collect()
+    }
+
+    fn __len__(&self) -> usize {
+        self.series.len()
+    }
+
+    fn __contains__(&self, name: &str) -> bool {
+        self.series.contains_key(name)
+    }
+
+    /// Fits all series, in parallel when built with the parallel feature,
+    /// returning results keyed by name
+    fn fit<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let series = self.series.values().collect::<Vec<&Vec<f64>>>();
+        let results = py.allow_threads(|| self.params.fit_batch(&series, self.period))?;
+        let dict = PyDict::new(py);
+        for (name, result) in self.series.keys().zip(results) {
+            dict.set_item(name, PySTLResult { inner: result, index: None })?;
+        }
+        Ok(dict)
+    }
+}
+
+/// Convenience function for STL decomposition with GIL release
+#[pyfunction]
+#[pyo3(signature = (series, period, freq=None))]
+fn stl_decompose(py: Python, series: &Bound<'_, PyAny>, period: PeriodArg, freq: Option<&str>) -> PyResult<PySTLResult> {
+    let index = pandas_index(series)?;
+    let series = extract_series(series)?;
+    let period = resolve_period(period, freq)?;
+    let result = py.allow_threads(|| {
+        Stl::fit(&series, period)
+    })?;
+    Ok(PySTLResult { inner: result, index })
+}
+
+/// Convenience function for weighted STL decomposition with GIL release
 #[pyfunction]
-fn mstl_decompose(py: Python, series: Vec<f64>, periods: Vec<usize>) -> PyResult<PyMstlResult> {
+#[pyo3(signature = (series, period, weights, freq=None))]
+fn stl_decompose_weighted(py: Python, series: &Bound<'_, PyAny>, period: PeriodArg, weights: &Bound<'_, PyAny>, freq: Option<&str>) -> PyResult<PySTLResult> {
+    let index = pandas_index(series)?;
+    let series = extract_series(series)?;
+    let period = resolve_period(period, freq)?;
+    let weights = extract_series(weights)?;
     let result = py.allow_threads(|| {
-        Mstl::fit(&series, &periods)
+        Stl::fit_with_weights(&series, period, &weights)
     })?;
+    Ok(PySTLResult { inner: result, index })
+}
+
+/// Decomposes a series with STL, classical, or X-11 style decomposition, additively
+/// or multiplicatively (multiplicative STL decomposes the log of the series)
+#[pyfunction]
+#[pyo3(signature = (series, period, model="additive", method="stl", freq=None, robust=false))]
+fn decompose(
+    py: Python,
+    series: &Bound<'_, PyAny>,
+    period: PeriodArg,
+    model: &str,
+    method: &str,
+    freq: Option<&str>,
+    robust: bool,
+) -> PyResult<PySTLResult> {
+    let index = pandas_index(series)?;
+    let series = extract_series(series)?;
+    let period = resolve_period(period, freq)?;
+
+    let multiplicative = match model {
+        "additive" => false,
+        "multiplicative" => true,
+        _ => return Err(PyValueError::new_err("model must be 'additive' or 'multiplicative'")),
+    };
+    if method != "stl" && method != "classical" && method != "x11" {
+        return Err(PyValueError::new_err("method must be 'stl', 'classical', or 'x11'"));
+    }
+    if multiplicative && series.iter().any(|&v| v <= 0.0) {
+        return Err(PyValueError::new_err(
+            "multiplicative seasonality is not appropriate for zero and negative values",
+        ));
+    }
+
+    let result = py.allow_threads(|| -> Result<StlResult, Error> {
+        let model = if multiplicative {
+            DecompositionModel::Multiplicative
+        } else {
+            DecompositionModel::Additive
+        };
+        if method == "classical" {
+            return classical_decompose(&series, period, model);
+        }
+        if method == "x11" {
+            return X11::params().model(model).fit(&series, period);
+        }
+
+        let mut params = StlParams::new();
+        params.robust(robust);
+        if !multiplicative {
+            return params.fit(&series, period);
+        }
+
+        let log = series.iter().map(|v| v.ln()).collect::<Vec<f64>>();
+        let result = params.fit(&log, period)?;
+        let exp = |v: &[f64]| v.iter().map(|v| v.exp()).collect::<Vec<f64>>();
+        Ok(StlResult {
+            seasonal: exp(result.seasonal()),
+            trend: exp(result.trend()),
+            remainder: exp(result.remainder()),
+            params: None,
+            ..result
+        })
+    })?;
+    Ok(PySTLResult { inner: result, index })
+}
+
+/// Classical decomposition by centered moving averages, like statsmodels' seasonal_decompose
+#[pyfunction(name = "classical_decompose")]
+#[pyo3(signature = (series, period, model="additive", freq=None))]
+fn py_classical_decompose(py: Python, series: &Bound<'_, PyAny>, period: PeriodArg, model: &str, freq: Option<&str>) -> PyResult<PySTLResult> {
+    let index = pandas_index(series)?;
+    let series = extract_series(series)?;
+    let period = resolve_period(period, freq)?;
+    let model = match model {
+        "additive" => DecompositionModel::Additive,
+        "multiplicative" => DecompositionModel::Multiplicative,
+        _ => return Err(PyValueError::new_err("model must be 'additive' or 'multiplicative'")),
+    };
+    let result = py.allow_threads(|| classical_decompose(&series, period, model))?;
+    Ok(PySTLResult { inner: result, index })
+}
+
+/// Box-Cox transformation of a series (lmbda=0 is the natural log)
+#[pyfunction(name = "boxcox")]
+fn py_boxcox(series: &Bound<'_, PyAny>, lmbda: f64) -> PyResult<Vec<f64>> {
+    let series = extract_series(series)?;
+    Ok(boxcox(&series, lmbda))
+}
+
+/// Inverse Box-Cox transformation
+#[pyfunction(name = "inv_boxcox")]
+fn py_inv_boxcox(series: &Bound<'_, PyAny>, lmbda: f64) -> PyResult<Vec<f64>> {
+    let series = extract_series(series)?;
+    Ok(inv_boxcox(&series, lmbda))
+}
+
+/// Box-Cox lambda estimated with Guerrero's method
+#[pyfunction(name = "boxcox_lambda_guerrero")]
+#[pyo3(signature = (series, period, freq=None))]
+fn py_boxcox_lambda_guerrero(series: &Bound<'_, PyAny>, period: PeriodArg, freq: Option<&str>) -> PyResult<f64> {
+    let series = extract_series(series)?;
+    let period = resolve_period(period, freq)?;
+    Ok(boxcox_lambda_guerrero(&series, period))
+}
+
+/// Convenience function for MSTL decomposition with GIL release
+#[pyfunction]
+#[pyo3(signature = (series, periods, keep_stl_results=false, freq=None, compat=None, lmbda=None, shift=None, stl_kwargs=None))]
+#[allow(clippy::too_many_arguments)]
+fn mstl_decompose(py: Python, series: &Bound<'_, PyAny>, periods: Vec<PeriodArg>, keep_stl_results: bool, freq: Option<&str>, compat: Option<&str>, lmbda: Option<LambdaArg>, shift: Option<ShiftArg>, stl_kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<PyMstlResult> {
+    let statsmodels = parse_compat(compat)?;
+    let series = extract_series(series)?;
+    let periods = periods
+        .into_iter()
+        .map(|p| resolve_period(p, freq))
+        .collect::<PyResult<Vec<usize>>>()?;
+    let mut params = Mstl::params();
+    if let Some(kwargs) = stl_kwargs {
+        params.stl_params(stl_params_from_kwargs(kwargs)?);
+    }
+    params
+        .keep_stl_results(keep_stl_results)
+        .keep_observed(true)
+        .statsmodels_compat(statsmodels);
+    set_lambda(&mut params, lmbda.as_ref())?;
+    set_shift(&mut params, shift.as_ref())?;
+    let result = py.allow_threads(|| params.fit(&series, &periods))?;
     Ok(PyMstlResult { inner: result })
 }
 
+/// MSTL decomposition of each column of a pandas or polars DataFrame, fitted
+/// in parallel, returning a DataFrame of each component keyed by its label
+#[pyfunction]
+#[pyo3(signature = (df, periods, *, freq=None, lmbda=None, shift=None, stl_kwargs=None))]
+fn mstl_decompose_frame<'py>(py: Python<'py>, df: &Bound<'py, PyAny>, periods: Vec<PeriodArg>, freq: Option<&str>, lmbda: Option<LambdaArg>, shift: Option<ShiftArg>, stl_kwargs: Option<&Bound<'py, PyDict>>) -> PyResult<Bound<'py, PyDict>> {
+    let periods = periods
+        .into_iter()
+        .map(|p| resolve_period(p, freq))
+        .collect::<PyResult<Vec<usize>>>()?;
+    let columns = df.getattr("columns")?.try_iter()?.collect::<PyResult<Vec<Bound<'py, PyAny>>>>()?;
+    let series = columns
+        .iter()
+        .map(|column| extract_series(&df.get_item(column)?))
+        .collect::<PyResult<Vec<Vec<f64>>>>()?;
+    let mut params = Mstl::params();
+    if let Some(kwargs) = stl_kwargs {
+        params.stl_params(stl_params_from_kwargs(kwargs)?);
+    }
+    set_lambda(&mut params, lmbda.as_ref())?;
+    set_shift(&mut params, shift.as_ref())?;
+    let results = py.allow_threads(|| params.fit_batch(&series, &periods))?;
+
+    // the components come back in the library of the input, with the pandas
+    // index kept
+    let is_polars = df.get_type().module()?.to_cow()?.starts_with("polars");
+    let frame = |component: &dyn Fn(&MstlResult) -> &[f64]| -> PyResult<Bound<'py, PyAny>> {
+        let data = PyDict::new(py);
+        for (column, result) in columns.iter().zip(&results) {
+            data.set_item(column, PyArray1::from_slice(py, component(result)))?;
+        }
+        if is_polars {
+            PyModule::import(py, "polars")?.getattr("DataFrame")?.call1((data,))
+        } else {
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("index", df.getattr("index")?)?;
+            PyModule::import(py, "pandas")?.getattr("DataFrame")?.call((data,), Some(&kwargs))
+        }
+    };
+
+    let labels = match results.first() {
+        Some(result) => result.labels(),
+        None => periods.iter().map(|p| format!("seasonal_{}", p)).collect(),
+    };
+    let dict = PyDict::new(py);
+    for (i, label) in labels.iter().enumerate() {
+        dict.set_item(label, frame(&|r| &r.seasonal()[i])?)?;
+    }
+    dict.set_item("trend", frame(&|r| r.trend())?)?;
+    dict.set_item("remainder", frame(&|r| r.remainder())?)?;
+    Ok(dict)
+}
+
+/// Sets the number of threads used for batch and MSTL fits
+#[cfg(feature = "parallel")]
+#[pyfunction(name = "set_num_threads")]
+fn py_set_num_threads(num_threads: usize) -> PyResult<()> {
+    Ok(set_num_threads(num_threads)?)
+}
+
+/// Restores rayon's global pool for batch and MSTL fits
+#[cfg(feature = "parallel")]
+#[pyfunction(name = "clear_thread_pool")]
+fn py_clear_thread_pool() {
+    clear_thread_pool();
+}
+
+/// Context manager running batch and MSTL fits on a pool of `num_threads` threads,
+/// restoring the previous setting on exit
+#[cfg(feature = "parallel")]
+#[pyclass(name = "ThreadPool")]
+pub struct PyThreadPool {
+    pool: std::sync::Arc<rayon::ThreadPool>,
+    previous: Vec<Option<std::sync::Arc<rayon::ThreadPool>>>,
+}
+
+#[cfg(feature = "parallel")]
+#[pymethods]
+impl PyThreadPool {
+    #[new]
+    fn new(num_threads: usize) -> PyResult<Self> {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(Self {
+            pool: std::sync::Arc::new(pool),
+            previous: Vec::new(),
+        })
+    }
+
+    fn __enter__(mut slf: PyRefMut<'_, Self>) -> PyRefMut<'_, Self> {
+        let previous = parallel::replace(Some(slf.pool.clone()));
+        slf.previous.push(previous);
+        slf
+    }
+
+    #[pyo3(signature = (*_args))]
+    fn __exit__(&mut self, _args: &Bound<'_, pyo3::types::PyTuple>) -> bool {
+        if let Some(previous) = self.previous.pop() {
+            parallel::replace(previous);
+        }
+        false
+    }
+
+    #[getter]
+    fn num_threads(&self) -> usize {
+        self.pool.current_num_threads()
+    }
+}
+
+/// Registers parameters under a name for StlParams.from_profile
+#[pyfunction]
+fn register_profile(name: &str, params: &PyStlParams) {
+    profiles::register(name, params.inner.clone());
+}
+
+/// Element-wise sum of two components of the same length
+#[pyfunction(name = "add_components")]
+fn py_add_components(a: &Bound<'_, PyAny>, b: &Bound<'_, PyAny>) -> PyResult<Vec<f64>> {
+    Ok(add_components(&extract_series(a)?, &extract_series(b)?)?)
+}
+
+/// Element-wise difference of two components of the same length
+#[pyfunction(name = "subtract_components")]
+fn py_subtract_components(a: &Bound<'_, PyAny>, b: &Bound<'_, PyAny>) -> PyResult<Vec<f64>> {
+    Ok(subtract_components(&extract_series(a)?, &extract_series(b)?)?)
+}
+
+/// Element-wise sum of any number of components of the same length
+#[pyfunction(name = "sum_components")]
+fn py_sum_components(components: Vec<Bound<'_, PyAny>>) -> PyResult<Vec<f64>> {
+    let components = components.iter().map(extract_series).collect::<PyResult<Vec<Vec<f64>>>>()?;
+    Ok(sum_components(&components)?)
+}
+
+/// A component multiplied by a factor
+#[pyfunction(name = "scale_component")]
+fn py_scale_component(component: &Bound<'_, PyAny>, factor: f64) -> PyResult<Vec<f64>> {
+    Ok(scale_component(&extract_series(component)?, factor))
+}
+
+/// Strength of a component (or sum of components) relative to the remainder
+#[pyfunction(name = "component_strength")]
+fn py_component_strength(component: &Bound<'_, PyAny>, remainder: &Bound<'_, PyAny>) -> PyResult<f64> {
+    let component = extract_series(component)?;
+    let remainder = extract_series(remainder)?;
+    if component.len() != remainder.len() {
+        return Err(PyValueError::new_err("component and remainder must have the same length"));
+    }
+    Ok(component_strength(&component, &remainder))
+}
+
+/// Seasonal strength of each result, computed without holding the GIL
+#[pyfunction(name = "seasonal_strengths")]
+fn py_seasonal_strengths(py: Python, results: Vec<PyRef<PySTLResult>>) -> Vec<f64> {
+    let results = results.iter().map(|r| &r.inner).collect::<Vec<&StlResult>>();
+    py.allow_threads(|| seasonal_strengths(&results))
+}
+
+/// Trend strength of each result, computed without holding the GIL
+#[pyfunction(name = "trend_strengths")]
+fn py_trend_strengths(py: Python, results: Vec<PyRef<PySTLResult>>) -> Vec<f64> {
+    let results = results.iter().map(|r| &r.inner).collect::<Vec<&StlResult>>();
+    py.allow_threads(|| trend_strengths(&results))
+}
+
+/// Count, mean, quantiles (min, quartiles, max), and histogram over [0, 1] of strengths
+#[pyfunction(name = "strength_summary")]
+#[pyo3(signature = (strengths, bins=10))]
+fn py_strength_summary<'py>(py: Python<'py>, strengths: Vec<f64>, bins: usize) -> PyResult<Bound<'py, PyDict>> {
+    let summary = strength_summary(&strengths, bins);
+    let dict = PyDict::new(py);
+    dict.set_item("count", summary.count())?;
+    dict.set_item("mean", summary.mean())?;
+    dict.set_item("quantiles", summary.quantiles().to_vec())?;
+    dict.set_item("histogram", summary.histogram())?;
+    Ok(dict)
+}
+
+/// Caches up to capacity results of identical fits in memory, each for at
+/// most ttl seconds; a capacity of 0 disables the cache
+#[pyfunction]
+#[pyo3(signature = (capacity, ttl=None))]
+fn configure_cache(capacity: usize, ttl: Option<f64>) -> PyResult<()> {
+    let ttl = ttl
+        .map(std::time::Duration::try_from_secs_f64)
+        .transpose()
+        .map_err(|_| PyValueError::new_err("ttl must be a non-negative number of seconds"))?;
+    cache::configure(capacity, ttl);
+    Ok(())
+}
+
+/// Drops all cached results
+#[pyfunction]
+fn clear_cache() {
+    cache::clear();
+}
+
+/// Fills missing observations with the fitted trend and seasonal components,
+/// plus resampled remainders when a seed is given
+#[pyfunction(name = "fill_gaps")]
+#[pyo3(signature = (series, result, seed=None))]
+fn py_fill_gaps(series: &Bound<'_, PyAny>, result: &PySTLResult, seed: Option<u64>) -> PyResult<Vec<f64>> {
+    let series = extract_series(series)?;
+    Ok(fill_gaps(&series, &result.inner, seed))
+}
+
+/// Returns (index, observed, expected, z_score) for each observation whose
+/// remainder is unusually large under a two-sided test at level alpha
+#[pyfunction]
+#[pyo3(signature = (result, alpha=0.05))]
+fn anomalies(result: &PySTLResult, alpha: f64) -> PyResult<Vec<(usize, f64, f64, f64)>> {
+    if !(alpha > 0.0 && alpha < 1.0) {
+        return Err(PyValueError::new_err("alpha must be between 0 and 1"));
+    }
+    Ok(result
+        .inner
+        .anomalies(alpha)
+        .iter()
+        .map(|a| (a.index(), a.observed(), a.expected(), a.z_score()))
+        .collect())
+}
+
+/// Indices of the observations whose remainder lies outside Tukey's fences
+#[pyfunction]
+#[pyo3(signature = (result, k_iqr=1.5))]
+fn outliers(result: &PySTLResult, k_iqr: f64) -> Vec<usize> {
+    result.inner.outliers(k_iqr).indices()
+}
+
+/// Groups objects of the module under a submodule, importable as
+/// stl_rs.<name>, while keeping them at the top level
+fn add_submodule(m: &Bound<'_, PyModule>, name: &str, items: &[&str]) -> PyResult<()> {
+    let py = m.py();
+    let submodule = PyModule::new(py, name)?;
+    for &item in items {
+        submodule.add(item, m.getattr(item)?)?;
+    }
+    m.add_submodule(&submodule)?;
+    PyModule::import(py, "sys")?
+        .getattr("modules")?
+        .set_item(format!("stl_rs.{}", name), submodule)
+}
+
 #[pymodule]
 fn stl_rs(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<STL>()?;
+    m.add_class::<MSTL>()?;
+    m.add_class::<PyComponent>()?;
     m.add_class::<PySTLResult>()?;
+    m.add_class::<PyLoessDiagnostics>()?;
+    m.add_class::<PySeasonalModel>()?;
     m.add_class::<PyMstlResult>()?;
     m.add_class::<PyStlParams>()?;
+    m.add_class::<PyStlBatch>()?;
     m.add_function(wrap_pyfunction!(stl_decompose, m)?)?;
+    m.add_function(wrap_pyfunction!(stl_decompose_weighted, m)?)?;
     m.add_function(wrap_pyfunction!(mstl_decompose, m)?)?;
+    m.add_function(wrap_pyfunction!(mstl_decompose_frame, m)?)?;
+    m.add_function(wrap_pyfunction!(decompose, m)?)?;
+    m.add_function(wrap_pyfunction!(py_boxcox, m)?)?;
+    m.add_function(wrap_pyfunction!(py_inv_boxcox, m)?)?;
+    m.add_function(wrap_pyfunction!(py_boxcox_lambda_guerrero, m)?)?;
+    m.add_function(wrap_pyfunction!(py_fill_gaps, m)?)?;
+    m.add_function(wrap_pyfunction!(register_profile, m)?)?;
+    m.add_function(wrap_pyfunction!(configure_cache, m)?)?;
+    m.add_function(wrap_pyfunction!(py_classical_decompose, m)?)?;
+    m.add_function(wrap_pyfunction!(py_component_strength, m)?)?;
+    m.add_function(wrap_pyfunction!(py_add_components, m)?)?;
+    m.add_function(wrap_pyfunction!(py_subtract_components, m)?)?;
+    m.add_function(wrap_pyfunction!(py_sum_components, m)?)?;
+    m.add_function(wrap_pyfunction!(py_scale_component, m)?)?;
+    m.add_function(wrap_pyfunction!(py_seasonal_strengths, m)?)?;
+    m.add_function(wrap_pyfunction!(py_trend_strengths, m)?)?;
+    m.add_function(wrap_pyfunction!(py_strength_summary, m)?)?;
+    m.add_function(wrap_pyfunction!(clear_cache, m)?)?;
+    #[cfg(feature = "parallel")]
+    {
+        m.add_class::<PyThreadPool>()?;
+        m.add("thread_pool", m.getattr("ThreadPool")?)?;
+        m.add_function(wrap_pyfunction!(py_set_num_threads, m)?)?;
+        m.add_function(wrap_pyfunction!(py_clear_thread_pool, m)?)?;
+    }
+
+    m.add_function(wrap_pyfunction!(anomalies, m)?)?;
+    m.add_function(wrap_pyfunction!(outliers, m)?)?;
+
+    add_submodule(m, "stl", &[
+        "STL", "PyStlParams", "STLBatch", "PySTLResult", "Component", "PyLoessDiagnostics", "PySeasonalModel",
+        "stl_decompose", "stl_decompose_weighted", "decompose", "classical_decompose", "register_profile",
+        "configure_cache", "clear_cache", "fill_gaps",
+    ])?;
+    add_submodule(m, "mstl", &[
+        "MSTL", "PyMstlResult", "mstl_decompose", "mstl_decompose_frame", "boxcox", "inv_boxcox",
+        "boxcox_lambda_guerrero",
+    ])?;
+    add_submodule(m, "anomaly", &["anomalies", "outliers"])?;
+    add_submodule(m, "features", &[
+        "component_strength", "seasonal_strengths", "trend_strengths", "strength_summary",
+        "add_components", "subtract_components", "sum_components", "scale_component",
+    ])?;
 
     m.add("__version__", "0.1.4")?;
     m.add("__author__", "Thomas Q")?;
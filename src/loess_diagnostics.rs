@@ -0,0 +1,25 @@
+/// Diagnostics for a loess smoother.
+#[derive(Clone, Debug)]
+pub struct LoessDiagnostics {
+    pub(crate) leverage: Vec<f64>,
+}
+
+impl LoessDiagnostics {
+    /// Returns the leverage of each observation.
+    ///
+    /// This is the diagonal of the smoother matrix, i.e. the weight an
+    /// observation receives in its own fitted value.
+    pub fn leverage(&self) -> &[f64] {
+        &self.leverage
+    }
+
+    /// Returns the equivalent number of parameters (the trace of the smoother matrix).
+    pub fn equivalent_parameters(&self) -> f64 {
+        self.leverage.iter().sum()
+    }
+
+    /// Returns the residual degrees of freedom.
+    pub fn residual_df(&self) -> f64 {
+        self.leverage.len() as f64 - self.equivalent_parameters()
+    }
+}
@@ -0,0 +1,25 @@
+// Minimizes a unimodal function on [0, 1] with golden-section search, stopping
+// once the bracket is narrower than the tolerance
+pub(crate) fn golden_section<F: Fn(f64) -> f64>(f: F, tolerance: f64) -> f64 {
+    let ratio = (5f64.sqrt() - 1.0) / 2.0;
+    let (mut a, mut b) = (0.0, 1.0);
+    let mut c = b - ratio * (b - a);
+    let mut d = a + ratio * (b - a);
+    let (mut fc, mut fd) = (f(c), f(d));
+    while b - a > tolerance {
+        if fc < fd {
+            b = d;
+            d = c;
+            fd = fc;
+            c = b - ratio * (b - a);
+            fc = f(c);
+        } else {
+            a = c;
+            c = d;
+            fc = fd;
+            d = a + ratio * (b - a);
+            fd = f(d);
+        }
+    }
+    (a + b) / 2.0
+}
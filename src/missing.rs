@@ -0,0 +1,83 @@
+use super::StlResult;
+use super::synth::Rng;
+
+// Missing observations (NaN) are filled by linear interpolation between the
+// nearest observed values and given zero weight, so they never influence a fit.
+pub(crate) fn fill_missing(y: &[f64]) -> Option<(Vec<f64>, Vec<f64>)> {
+    if !y.iter().any(|v| v.is_nan()) {
+        return None;
+    }
+
+    let n = y.len();
+    let mut filled = y.to_vec();
+    let mut weights = vec![1.0; n];
+
+    let mut last = None;
+    for i in 0..n {
+        if y[i].is_nan() {
+            weights[i] = 0.0;
+            continue;
+        }
+        match last {
+            Some(l) if l + 1 < i => {
+                let delta = (y[i] - y[l]) / ((i - l) as f64);
+                for (j, v) in filled.iter_mut().enumerate().take(i).skip(l + 1) {
+                    *v = y[l] + delta * ((j - l) as f64);
+                }
+            }
+            None => {
+                for v in filled.iter_mut().take(i) {
+                    *v = y[i];
+                }
+            }
+            _ => {}
+        }
+        last = Some(i);
+    }
+
+    if let Some(last) = last {
+        for v in filled.iter_mut().skip(last + 1) {
+            *v = y[last];
+        }
+    }
+
+    Some((filled, weights))
+}
+
+/// Fills missing observations (NaN) with the fitted trend and seasonal components.
+///
+/// `result` must come from a fit of the same series, such as one with the
+/// gaps left in. Any regression effect removed before decomposing is added
+/// back. With a seed, each filled value also gets a remainder drawn at random
+/// from the observed remainders, so the gaps have realistic noise. Missing
+/// observations past the end of the result are left as NaN.
+pub fn fill_gaps(series: &[f64], result: &StlResult, seed: Option<u64>) -> Vec<f64> {
+    let remainder = result
+        .remainder()
+        .iter()
+        .copied()
+        .filter(|v| !v.is_nan())
+        .collect::<Vec<f64>>();
+    let effect = result.regression().map(|r| r.effect());
+    let mut rng = seed.map(Rng);
+
+    series
+        .iter()
+        .enumerate()
+        .map(|(i, &v)| {
+            if !v.is_nan() || i >= result.trend().len() {
+                return v;
+            }
+            let mut fitted = result.seasonal()[i] + result.trend()[i];
+            if let Some(effect) = effect {
+                fitted += effect[i];
+            }
+            if let Some(rng) = rng.as_mut()
+                && !remainder.is_empty()
+            {
+                fitted += remainder[rng.index(remainder.len())];
+            }
+            fitted
+        })
+        .collect()
+}
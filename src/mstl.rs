@@ -21,7 +21,11 @@ pub use super::mstl_result::MstlResult;
 
 #[cfg(test)]
 mod tests {
-    use crate::{Error, Mstl, Stl};
+    use crate::synth::{Noise, SynthParams, Trend};
+    use crate::{
+        Decomposer, Decomposition, Error, Mstl, Stl, boxcox, boxcox_lambda_guerrero,
+        component_strength, inv_boxcox,
+    };
 
     fn assert_in_delta(exp: f64, act: f64) {
         assert!((exp - act).abs() < 0.001);
@@ -35,10 +39,10 @@ mod tests {
     }
 
     fn generate_series() -> Vec<f64> {
-        return vec![
+        vec![
             5.0, 9.0, 2.0, 9.0, 0.0, 6.0, 3.0, 8.0, 5.0, 8.0, 7.0, 8.0, 8.0, 0.0, 2.0, 5.0, 0.0,
             5.0, 6.0, 7.0, 3.0, 6.0, 1.0, 4.0, 4.0, 4.0, 3.0, 7.0, 5.0, 8.0,
-        ];
+        ]
     }
 
     #[test]
@@ -225,6 +229,66 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_shift() {
+        let series: Vec<f64> = generate_series().iter().map(|&v| v - 2.0).collect();
+        let err = Mstl::params()
+            .lambda(0.5)
+            .fit(&series, &[6, 10])
+            .unwrap_err();
+        assert!(matches!(err, Error::Series(_)));
+
+        let result = Mstl::params()
+            .lambda(0.0)
+            .auto_shift(true)
+            .fit(&series, &[6, 10])
+            .unwrap();
+        assert_eq!(Some(3.0), result.shift());
+        assert!(result.trend().iter().all(|v| v.is_finite()));
+        let shifted: Vec<f64> = series.iter().map(|&v| v + 3.0).collect();
+        let expected = Mstl::params().lambda(0.0).fit(&shifted, &[6, 10]).unwrap();
+        assert_elements_in_delta(expected.trend(), result.trend());
+
+        let forecast = result.reseasonalize(&result.trend()[..6]);
+        assert!(forecast.iter().all(|v| v.is_finite()));
+        assert_in_delta(
+            expected.reseasonalize(&expected.trend()[..6])[0] - 3.0,
+            forecast[0],
+        );
+
+        let result = Mstl::params().shift(3.0).fit(&series, &[6, 10]).unwrap();
+        assert_eq!(None, result.shift());
+    }
+
+    #[test]
+    fn test_damping() {
+        let wave = |i: usize, period: f64| (2.0 * std::f64::consts::PI * i as f64 / period).sin();
+        let series = (0..1200)
+            .map(|i| 3.0 * wave(i, 24.0) + 2.0 * wave(i, 25.0))
+            .collect::<Vec<f64>>();
+        // the error in the component of the longer period
+        let error = |damping: f64| {
+            let result = Mstl::params()
+                .damping(damping)
+                .fit(&series, &[24, 25])
+                .unwrap();
+            for (i, v) in series.iter().enumerate() {
+                let total = result.seasonal()[0][i]
+                    + result.seasonal()[1][i]
+                    + result.trend()[i]
+                    + result.remainder()[i];
+                assert_in_delta(*v, total);
+            }
+            (200..1000)
+                .map(|i| (result.seasonal()[1][i] - 2.0 * wave(i, 25.0)).powi(2))
+                .sum::<f64>()
+        };
+        assert!(error(0.8) < 0.9 * error(1.0));
+
+        let result = Mstl::params().damping(0.0).fit(&series, &[12, 16]);
+        assert!(matches!(result, Err(Error::Parameter(_))));
+    }
+
     #[test]
     fn test_lambda_zero() {
         let series: Vec<f64> = generate_series().iter().map(|&v| v + 1.0).collect();
@@ -331,6 +395,89 @@ mod tests {
         assert_in_delta(1.0, result.seasonal_strength()[0]);
     }
 
+    #[test]
+    fn test_final_trend() {
+        let series = generate_series();
+        let roughness = |trend: &[f64]| {
+            trend
+                .windows(3)
+                .map(|w| (w[2] - 2.0 * w[1] + w[0]).powi(2))
+                .sum::<f64>()
+        };
+
+        let plain = Mstl::fit(&series, &[6, 10]).unwrap();
+        let result = Mstl::params()
+            .final_trend(29, 1)
+            .fit(&series, &[6, 10])
+            .unwrap();
+        assert_eq!(plain.seasonal(), result.seasonal());
+        assert!(roughness(result.trend()) < roughness(plain.trend()));
+        for i in 0..series.len() {
+            let seasonal = result.seasonal().iter().map(|s| s[i]).sum::<f64>();
+            assert_in_delta(
+                series[i],
+                seasonal + result.trend()[i] + result.remainder()[i],
+            );
+        }
+
+        // a missing value only leaves its own remainder missing
+        let mut missing = series.clone();
+        missing[12] = f64::NAN;
+        let result = Mstl::params()
+            .final_trend(15, 1)
+            .fit(&missing, &[6, 10])
+            .unwrap();
+        assert!(result.trend().iter().all(|t| t.is_finite()));
+        assert_eq!(
+            vec![12],
+            (0..missing.len())
+                .filter(|&i| result.remainder()[i].is_nan())
+                .collect::<Vec<usize>>()
+        );
+
+        let result = Mstl::params().final_trend(8, 1).fit(&series, &[6, 10]);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::Parameter("final trend length must be odd and at least 3".to_string())
+        );
+    }
+
+    #[test]
+    fn test_extend() {
+        let series = (0..120)
+            .map(|t| {
+                let t = t as f64;
+                10.0 + 0.05 * t
+                    + 2.0 * (2.0 * std::f64::consts::PI * t / 6.0).sin()
+                    + (2.0 * std::f64::consts::PI * t / 10.0).cos()
+            })
+            .collect::<Vec<f64>>();
+        let params = Mstl::params();
+        let mut result = Mstl::params()
+            .keep_observed(true)
+            .fit(&series[..100], &[6, 10])
+            .unwrap();
+        let before = result.trend()[..70].to_vec();
+        result.extend(&series[100..], &params).unwrap();
+
+        assert_eq!(120, result.nobs());
+        assert_eq!(120, result.observed().unwrap().len());
+        // observations before the refit window are untouched
+        assert_eq!(before, result.trend()[..70]);
+        for i in 0..120 {
+            let seasonal = result.seasonal().iter().map(|s| s[i]).sum::<f64>();
+            assert_in_delta(
+                series[i],
+                seasonal + result.trend()[i] + result.remainder()[i],
+            );
+        }
+
+        let full = Mstl::fit(&series, &[6, 10]).unwrap();
+        for i in 100..120 {
+            assert!((full.trend()[i] - result.trend()[i]).abs() < 0.1);
+        }
+    }
+
     #[test]
     fn test_trend_strength() {
         let mut stl_params = Stl::params();
@@ -353,4 +500,285 @@ mod tests {
             .unwrap();
         assert_in_delta(1.0, result.trend_strength());
     }
+
+    #[test]
+    fn test_stl_results() {
+        let result = Mstl::params()
+            .keep_stl_results(true)
+            .fit(&generate_series(), &[6, 10])
+            .unwrap();
+        let stl_results = result.stl_results().unwrap();
+        assert_eq!(2, stl_results.len());
+        assert_eq!(6, stl_results[0].period());
+        assert_eq!(10, stl_results[1].period());
+        assert_elements_in_delta(result.seasonal()[0].as_slice(), stl_results[0].seasonal());
+        assert_elements_in_delta(result.trend(), stl_results[1].trend());
+    }
+
+    #[test]
+    fn test_stl_results_not_kept() {
+        let result = Mstl::fit(&generate_series(), &[6, 10]).unwrap();
+        assert!(result.stl_results().is_none());
+    }
+
+    #[test]
+    fn test_fit_batch() {
+        let series = vec![generate_series(), generate_series()];
+        let results = Mstl::params().fit_batch(&series, &[6, 10]).unwrap();
+        assert_eq!(2, results.len());
+        let expected = Mstl::fit(&generate_series(), &[6, 10]).unwrap();
+        for result in results {
+            assert_elements_in_delta(expected.trend(), result.trend());
+        }
+    }
+
+    fn decompose_with<D: Decomposer>(decomposer: &D, periods: &[usize]) -> Vec<usize> {
+        let result = decomposer.decompose(&generate_series(), periods).unwrap();
+        assert_eq!(30, result.trend().len());
+        assert_eq!(30, result.remainder().len());
+        assert_eq!(periods.len(), result.seasonal_strengths().len());
+        result
+            .seasonal_components()
+            .iter()
+            .map(|s| s.len())
+            .collect()
+    }
+
+    #[test]
+    fn test_decomposer() {
+        assert_eq!(vec![30], decompose_with(&Stl, &[7]));
+        assert_eq!(vec![30], decompose_with(&Stl::params(), &[7]));
+        assert_eq!(vec![30, 30], decompose_with(&Mstl, &[6, 10]));
+        assert_eq!(vec![30, 30], decompose_with(&Mstl::params(), &[6, 10]));
+    }
+
+    #[test]
+    fn test_decomposer_stl_periods() {
+        let result = Stl.decompose(&generate_series(), &[6, 10]);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::Parameter("stl requires exactly one period".to_string())
+        );
+    }
+
+    #[test]
+    fn test_statsmodels_compat() {
+        let mut stl_params = Stl::params();
+        stl_params
+            .seasonal_length(11)
+            .seasonal_degree(1)
+            .low_pass_length(7)
+            .seasonal_jump(1)
+            .trend_jump(1)
+            .low_pass_jump(1);
+        let expected = stl_params.fit(&generate_series(), 6).unwrap();
+        let result = Mstl::params()
+            .statsmodels_compat(true)
+            .fit(&generate_series(), &[6])
+            .unwrap();
+        assert_elements_in_delta(expected.seasonal(), &result.seasonal()[0]);
+        assert_elements_in_delta(expected.trend(), result.trend());
+    }
+
+    #[test]
+    fn test_metadata() {
+        let result = Mstl::params()
+            .keep_observed(true)
+            .fit(&generate_series(), &[10, 6])
+            .unwrap();
+        assert_eq!(30, result.nobs());
+        assert_eq!(&[10, 6], result.periods());
+        assert_eq!(&[Some(15), Some(11)], result.windows());
+        assert_eq!(vec!["seasonal_10", "seasonal_6"], result.labels());
+        assert_elements_in_delta(&generate_series(), result.observed().unwrap());
+
+        let check = result.check_leakage();
+        assert_eq!(&["trend", "seasonal_10", "seasonal_6"], check.labels());
+        assert_eq!(&[0, 6, 10], check.lags());
+        assert_eq!(3, check.correlations().len());
+    }
+
+    #[test]
+    fn test_component_strength() {
+        let series = generate_series();
+        let result = Mstl::fit(&series, &[6, 10]).unwrap();
+        let seasonal = result.seasonal();
+        assert_eq!(
+            result.seasonal_strength()[0],
+            component_strength(&seasonal[0], result.remainder())
+        );
+        let summed = seasonal[0]
+            .iter()
+            .zip(&seasonal[1])
+            .map(|(a, b)| a + b)
+            .collect::<Vec<f64>>();
+        let combined = component_strength(&summed, result.remainder());
+        assert!(combined >= result.seasonal_strength()[0].min(result.seasonal_strength()[1]));
+        assert!(combined <= 1.0);
+    }
+
+    #[test]
+    fn test_seasonal_for() {
+        let series = generate_series();
+        let result = Mstl::fit(&series, &[10, 6]).unwrap();
+        assert_eq!(&[10, 6], result.periods());
+        assert_eq!(Some(&result.seasonal()[0][..]), result.seasonal_for(10));
+        assert_eq!(Some(&result.seasonal()[1][..]), result.seasonal_for(6));
+        assert_eq!(None, result.seasonal_for(7));
+    }
+
+    #[test]
+    fn test_period_names() {
+        let series = generate_series();
+        let result = Mstl::params()
+            .period_name(6, "short")
+            .period_name(10, "long")
+            .period_name(6, "six")
+            .fit(&series, &[10, 6, 5])
+            .unwrap();
+        assert_eq!(
+            &[Some("long".to_string()), Some("six".to_string()), None],
+            result.period_names()
+        );
+        assert_eq!(
+            vec!["seasonal_long", "seasonal_six", "seasonal_5"],
+            result.labels()
+        );
+        assert_eq!(
+            Some(&result.seasonal()[0][..]),
+            result.seasonal_named("long")
+        );
+        assert_eq!(None, result.seasonal_named("short"));
+    }
+
+    #[test]
+    fn test_reseasonalize() {
+        let result = Mstl::params()
+            .lambda(0.5)
+            .fit(&generate_series(), &[6, 10])
+            .unwrap();
+        let forecast = result.reseasonalize(&[4.0, 5.0]);
+        // each step takes the seasonal value one cycle back, on the Box-Cox scale
+        let expected = (0..2)
+            .map(|h| {
+                let seasonal = result
+                    .seasonal()
+                    .iter()
+                    .zip(result.periods())
+                    .map(|(s, p)| s[30 + h - p])
+                    .sum::<f64>();
+                4.0 + h as f64 + seasonal
+            })
+            .collect::<Vec<f64>>();
+        assert_elements_in_delta(&inv_boxcox(&expected, 0.5), &forecast);
+    }
+
+    #[test]
+    fn test_observed_not_kept() {
+        let result = Mstl::fit(&generate_series(), &[6, 10]).unwrap();
+        assert!(result.observed().is_none());
+    }
+
+    #[test]
+    fn test_auto_lambda() {
+        let series = (0..60)
+            .map(|v| (1.0 + 0.1 * v as f64) * (2.0 + ((v % 6) as f64)))
+            .collect::<Vec<f64>>();
+        let result = Mstl::params().auto_lambda().fit(&series, &[6]).unwrap();
+        let lambda = result.lambda().unwrap();
+        assert!((0.0..0.2).contains(&lambda));
+
+        let result = Mstl::fit(&series, &[6]).unwrap();
+        assert!(result.lambda().is_none());
+    }
+
+    #[test]
+    fn test_boxcox() {
+        let series = [1.0, 2.0, 4.0, 8.0];
+        assert_elements_in_delta(&[0.0, 0.828427, 2.0, 3.656854], &boxcox(&series, 0.5));
+        assert_elements_in_delta(&series, &inv_boxcox(&boxcox(&series, 0.5), 0.5));
+        assert_elements_in_delta(&series, &inv_boxcox(&boxcox(&series, 0.0), 0.0));
+
+        let series = (0..60)
+            .map(|v| (1.0 + 0.1 * v as f64) * (2.0 + ((v % 6) as f64)))
+            .collect::<Vec<f64>>();
+        let lambda = boxcox_lambda_guerrero(&series, 6);
+        let result = Mstl::params().auto_lambda().fit(&series, &[6]).unwrap();
+        assert_in_delta(lambda, result.lambda().unwrap());
+    }
+
+    #[test]
+    fn test_synth() {
+        let mut params = SynthParams::new(168);
+        params
+            .seed(42)
+            .seasonality(7.0, 3.0)
+            .seasonality(24.0, 5.0)
+            .trend(Trend::Linear {
+                intercept: 10.0,
+                slope: 0.05,
+            })
+            .noise(Noise::Gaussian { sd: 0.1 })
+            .anomalies(2, 10.0)
+            .missing_block(100, 3);
+        let synth = params.generate();
+        assert_eq!(2, synth.anomalies().len());
+        assert_eq!(&[100, 101, 102], synth.missing());
+        assert_eq!(
+            format!("{:?}", synth.noise()),
+            format!("{:?}", params.generate().noise())
+        );
+
+        let clean = SynthParams::new(168)
+            .seed(42)
+            .seasonality(7.0, 3.0)
+            .seasonality(24.0, 5.0)
+            .trend(Trend::Linear {
+                intercept: 10.0,
+                slope: 0.05,
+            })
+            .noise(Noise::Gaussian { sd: 0.1 })
+            .generate();
+        let result = Mstl::fit(clean.values(), &[7, 24]).unwrap();
+        for (exp, act) in clean.seasonal()[1][24..144]
+            .iter()
+            .zip(&result.seasonal()[1][24..144])
+        {
+            assert!((exp - act).abs() < 1.0);
+        }
+    }
+
+    #[test]
+    fn test_fourier_terms() {
+        let synth = SynthParams::new(200)
+            .seed(7)
+            .seasonality(7.0, 2.0)
+            .seasonality(120.0, 5.0)
+            .trend(Trend::Linear {
+                intercept: 10.0,
+                slope: 0.01,
+            })
+            .noise(Noise::Gaussian { sd: 0.1 })
+            .generate();
+        let result = Mstl::params()
+            .fourier_terms(120, 2)
+            .fit(synth.values(), &[7, 120])
+            .unwrap();
+        assert_eq!(&[Some(11), None], result.windows());
+        for (exp, act) in synth.seasonal()[1].iter().zip(&result.seasonal()[1]) {
+            assert!((exp - act).abs() < 0.5);
+        }
+
+        let result = Mstl::params().fourier_terms(7, 4).fit(synth.values(), &[7]);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::Parameter("fourier terms must be between 1 and half the period".to_string())
+        );
+
+        let result = Mstl::params().fourier_terms(7, 3).fit(synth.values(), &[7]);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::Parameter("at least one period must use STL".to_string())
+        );
+    }
 }
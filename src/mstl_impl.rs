@@ -2,9 +2,10 @@
 // MSTL: A Seasonal-Trend Decomposition Algorithm for Time Series with Multiple Seasonal Patterns.
 // arXiv:2107.13462 [stat.AP]. https://doi.org/10.48550/arXiv.2107.13462
 
-use super::{Error, StlParams};
+use super::harmonic::fourier_fit;
+use super::{Error, StlParams, StlResult, boxcox};
 
-#[allow(clippy::type_complexity)]
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn mstl(
     x: &[f64],
     seas_ids: &[usize],
@@ -12,7 +13,20 @@ pub fn mstl(
     lambda: Option<f64>,
     swin: &Option<Vec<usize>>,
     stl_params: &StlParams,
-) -> Result<(Vec<f64>, Vec<f64>, Vec<Vec<f64>>), Error> {
+    keep_fits: bool,
+    statsmodels: bool,
+    fourier: &[(usize, usize)],
+    damping: f64,
+) -> Result<
+    (
+        Vec<f64>,
+        Vec<f64>,
+        Vec<Vec<f64>>,
+        Vec<Option<usize>>,
+        Option<Vec<StlResult>>,
+    ),
+    Error,
+> {
     let k = x.len();
 
     // keep track of indices instead of sorting seas_ids
@@ -27,9 +41,14 @@ pub fn mstl(
 
     let mut seasonality = Vec::with_capacity(seas_ids.len());
     let mut trend = Vec::new();
+    let mut fits = vec![None; seas_ids.len()];
+    let mut windows = vec![None; seas_ids.len()];
+    // the part of each seasonal component subtracted from the series, which
+    // moves only part of the way to each new fit before the last iteration
+    let mut removed = vec![Vec::new(); seas_ids.len()];
 
     let mut deseas = if let Some(lam) = lambda {
-        box_cox(x, lam)
+        boxcox(x, lam)
     } else {
         x.to_vec()
     };
@@ -40,13 +59,37 @@ pub fn mstl(
         }
 
         for j in 0..iterate {
+            let factor = if j + 1 < iterate { damping } else { 1.0 };
+            let subtract = |deseas: &mut [f64], seasonal: &[f64], removed: &mut Vec<f64>| {
+                removed.resize(seasonal.len(), 0.0);
+                for ((d, r), s) in deseas.iter_mut().zip(removed.iter_mut()).zip(seasonal) {
+                    *r += factor * (s - *r);
+                    *d -= *r;
+                }
+            };
+
             for (i, &idx) in indices.iter().enumerate() {
                 if j > 0 {
-                    for (d, s) in deseas.iter_mut().zip(&seasonality[idx]) {
-                        *d += s;
+                    for (d, r) in deseas.iter_mut().zip(&removed[idx]) {
+                        *d += r;
                     }
                 }
 
+                // long periods can be modeled with Fourier terms instead of STL
+                if let Some(&(_, terms)) = fourier.iter().find(|f| f.0 == seas_ids[idx]) {
+                    seasonality[idx] = fourier_fit(&deseas, seas_ids[idx], terms);
+                    subtract(&mut deseas, &seasonality[idx], &mut removed[idx]);
+                    continue;
+                }
+
+                let defaults;
+                let stl_params = if statsmodels {
+                    defaults = stl_params.statsmodels_defaults(seas_ids[idx]);
+                    &defaults
+                } else {
+                    stl_params
+                };
+
                 let fit = if let Some(sw) = &swin {
                     stl_params
                         .clone()
@@ -61,11 +104,13 @@ pub fn mstl(
                         .fit(&deseas, seas_ids[idx])?
                 };
 
-                (seasonality[idx], trend, _, _) = fit.into_parts();
-
-                for (d, s) in deseas.iter_mut().zip(&seasonality[idx]) {
-                    *d -= s;
+                windows[idx] = Some(fit.seasonal_length);
+                if keep_fits && j == iterate - 1 {
+                    fits[idx] = Some(fit.clone());
                 }
+
+                (seasonality[idx], trend, _, _) = fit.into_parts();
+                subtract(&mut deseas, &seasonality[idx], &mut removed[idx]);
             }
         }
     } else {
@@ -73,20 +118,22 @@ pub fn mstl(
         return Err(Error::Parameter("periods must not be empty".to_string()));
     }
 
+    if trend.is_empty() {
+        return Err(Error::Parameter(
+            "at least one period must use STL".to_string(),
+        ));
+    }
+
     let mut remainder = Vec::with_capacity(k);
     for i in 0..k {
         remainder.push(deseas[i] - trend[i]);
     }
 
-    Ok((trend, remainder, seasonality))
-}
-
-fn box_cox(y: &[f64], lambda: f64) -> Vec<f64> {
-    if lambda != 0.0 {
-        y.iter()
-            .map(|yi| (yi.powf(lambda) - 1.0) / lambda)
-            .collect()
+    let fits = if keep_fits {
+        fits.into_iter().collect::<Option<Vec<StlResult>>>()
     } else {
-        y.iter().map(|yi| yi.ln()).collect()
-    }
+        None
+    };
+
+    Ok((trend, remainder, seasonality, windows, fits))
 }
@@ -1,13 +1,27 @@
+use super::missing::fill_missing;
 use super::mstl_impl::mstl;
-use super::{Error, MstlResult, StlParams};
+use super::{Error, Loess, MstlResult, Smoother, StlParams, boxcox_lambda_guerrero};
 
 /// A set of MSTL parameters.
+///
+/// Like [`StlParams`], a configured set is `Send + Sync` and can be shared by
+/// concurrent fits without cloning.
 #[derive(Clone, Debug)]
 pub struct MstlParams {
     iterate: usize,
-    lambda: Option<f64>,
+    pub(crate) lambda: Option<f64>,
     swin: Option<Vec<usize>>,
     stl_params: StlParams,
+    pub(crate) keep_stl_results: bool,
+    statsmodels: bool,
+    keep_observed: bool,
+    pub(crate) auto_lambda: bool,
+    pub(crate) shift: Option<f64>,
+    pub(crate) auto_shift: bool,
+    fourier: Vec<(usize, usize)>,
+    final_trend: Option<(usize, i32)>,
+    names: Vec<(usize, String)>,
+    damping: f64,
 }
 
 impl MstlParams {
@@ -18,6 +32,16 @@ impl MstlParams {
             lambda: None,
             swin: None,
             stl_params: StlParams::new(),
+            keep_stl_results: false,
+            statsmodels: false,
+            keep_observed: false,
+            auto_lambda: false,
+            shift: None,
+            auto_shift: false,
+            fourier: Vec::new(),
+            final_trend: None,
+            names: Vec::new(),
+            damping: 1.0,
         }
     }
 
@@ -33,18 +57,111 @@ impl MstlParams {
         self
     }
 
+    /// Estimates lambda for Box-Cox transformation with Guerrero's method.
+    ///
+    /// Lambda is chosen between 0 and 1, using the shortest period.
+    pub fn auto_lambda(&mut self) -> &mut Self {
+        self.auto_lambda = true;
+        self
+    }
+
+    /// Sets an offset added to the series before the Box-Cox transformation.
+    ///
+    /// The offset is kept in the result and removed again when forecasts are
+    /// transformed back.
+    pub fn shift(&mut self, shift: f64) -> &mut Self {
+        self.shift = Some(shift);
+        self
+    }
+
+    /// Sets whether to shift a series with zero or negative values so its
+    /// minimum is 1 before the Box-Cox transformation.
+    ///
+    /// Without a shift, Box-Cox on negative values, or on zeros when lambda is
+    /// zero, is an error. An offset set with [`shift`](Self::shift) takes
+    /// precedence.
+    pub fn auto_shift(&mut self, auto_shift: bool) -> &mut Self {
+        self.auto_shift = auto_shift;
+        self
+    }
+
     /// Sets the lengths of the seasonal smoothers.
     pub fn seasonal_lengths(&mut self, lengths: &[usize]) -> &mut Self {
         self.swin = Some(lengths.to_vec());
         self
     }
 
+    /// Models the seasonal component of a period with `terms` Fourier terms.
+    ///
+    /// This replaces cycle-subseries smoothing for that period, which is much
+    /// faster and needs less data for very long periods, such as 8760 for
+    /// hourly data with yearly seasonality. The series does not need to cover
+    /// two cycles of that period, and STL results are not kept.
+    pub fn fourier_terms(&mut self, period: usize, terms: usize) -> &mut Self {
+        self.fourier.retain(|f| f.0 != period);
+        self.fourier.push((period, terms));
+        self
+    }
+
+    /// Re-estimates the trend once more after all seasonal components are removed.
+    ///
+    /// The deseasonalized series is smoothed with loess of the given length and
+    /// degree (0 or 1), which gives a smoother trend than the one from the last
+    /// STL fit. The remainder absorbs the difference.
+    pub fn final_trend(&mut self, length: usize, degree: i32) -> &mut Self {
+        self.final_trend = Some((length, degree));
+        self
+    }
+
+    /// Names the seasonal component of a period, like "weekly".
+    ///
+    /// The name is kept in the result and used in its labels.
+    pub fn period_name(&mut self, period: usize, name: &str) -> &mut Self {
+        self.names.retain(|n| n.0 != period);
+        self.names.push((period, name.to_string()));
+        self
+    }
+
+    /// Sets how far the subtracted part of each seasonal component moves
+    /// towards its new fit in each iteration but the last (defaults to 1).
+    ///
+    /// With close periods, such as 24 and 25, the period fitted first absorbs
+    /// much of the other one. Values below 1 subtract less of each component
+    /// early on, which reduces this leakage within a few iterations, while
+    /// many iterations converge to the same decomposition more slowly.
+    pub fn damping(&mut self, damping: f64) -> &mut Self {
+        self.damping = damping;
+        self
+    }
+
     /// Sets the STL parameters.
     pub fn stl_params(&mut self, stl_params: StlParams) -> &mut Self {
         self.stl_params = stl_params;
         self
     }
 
+    /// Sets whether to keep the STL result of each period.
+    pub fn keep_stl_results(&mut self, keep_stl_results: bool) -> &mut Self {
+        self.keep_stl_results = keep_stl_results;
+        self
+    }
+
+    /// Sets whether to keep a copy of the input series in the result.
+    pub fn keep_observed(&mut self, keep_observed: bool) -> &mut Self {
+        self.keep_observed = keep_observed;
+        self
+    }
+
+    /// Sets whether to match the defaults of statsmodels' MSTL.
+    ///
+    /// Unset STL parameters use statsmodels' defaults: seasonal and low-pass
+    /// degrees of 1, a low-pass length of the smallest odd number greater than
+    /// the period, and no skipping.
+    pub fn statsmodels_compat(&mut self, statsmodels: bool) -> &mut Self {
+        self.statsmodels = statsmodels;
+        self
+    }
+
     /// Decomposes a time series.
     pub fn fit(&self, series: &[f64], periods: &[usize]) -> Result<MstlResult, Error> {
         // return error to be consistent with stl
@@ -56,44 +173,171 @@ impl MstlParams {
         // return error to be consistent with stl
         // and ensure seasonal is always same length as periods
         for np in periods {
-            if series.len() < np * 2 {
+            if series.len() < np * 2 && !self.fourier.iter().any(|f| f.0 == *np) {
                 return Err(Error::Series(
                     "series has less than two periods".to_string(),
                 ));
             }
         }
 
-        if let Some(lambda) = self.lambda {
-            if !(0.0..=1.0).contains(&lambda) {
+        if let Some(lambda) = self.lambda
+            && !(0.0..=1.0).contains(&lambda)
+        {
+            return Err(Error::Parameter(
+                "lambda must be between 0 and 1".to_string(),
+            ));
+        }
+
+        if !(self.damping > 0.0 && self.damping <= 1.0) {
+            return Err(Error::Parameter(
+                "damping must be greater than 0 and at most 1".to_string(),
+            ));
+        }
+
+        if let Some(swin) = &self.swin
+            && swin.len() != periods.len()
+        {
+            return Err(Error::Parameter(
+                "seasonal_lengths must have the same length as periods".to_string(),
+            ));
+        }
+
+        for &(period, terms) in &self.fourier {
+            if terms < 1 || terms > period / 2 {
                 return Err(Error::Parameter(
-                    "lambda must be between 0 and 1".to_string(),
+                    "fourier terms must be between 1 and half the period".to_string(),
                 ));
             }
         }
 
-        if let Some(swin) = &self.swin {
-            if swin.len() != periods.len() {
+        if let Some((length, degree)) = self.final_trend {
+            if length < 3 || length.is_multiple_of(2) {
                 return Err(Error::Parameter(
-                    "seasonal_lengths must have the same length as periods".to_string(),
+                    "final trend length must be odd and at least 3".to_string(),
                 ));
             }
+            if degree != 0 && degree != 1 {
+                return Err(Error::Parameter(
+                    "final trend degree must be 0 or 1".to_string(),
+                ));
+            }
+        }
+
+        let observed = series;
+        let transform = self.lambda.is_some() || (self.auto_lambda && !periods.is_empty());
+        let min = series
+            .iter()
+            .copied()
+            .filter(|v| !v.is_nan())
+            .fold(f64::INFINITY, f64::min);
+        let shift = match self.shift {
+            _ if !transform => None,
+            Some(shift) => Some(shift),
+            None if self.auto_shift && min <= 0.0 => Some(1.0 - min),
+            None => None,
+        };
+        let shifted;
+        let series = match shift {
+            Some(shift) => {
+                shifted = series.iter().map(|v| v + shift).collect::<Vec<f64>>();
+                &shifted[..]
+            }
+            None => series,
+        };
+        let lambda = if self.auto_lambda && !periods.is_empty() {
+            Some(boxcox_lambda_guerrero(
+                series,
+                *periods.iter().min().unwrap(),
+            ))
+        } else {
+            self.lambda
+        };
+
+        // negative values, and zeros for the log, give NaN deep inside the fit
+        let min = min + shift.unwrap_or(0.0);
+        if let Some(lambda) = lambda
+            && (min < 0.0 || (lambda == 0.0 && min == 0.0))
+        {
+            return Err(Error::Series(format!(
+                "box-cox transformation with lambda {} is not defined for a minimum of {}; use a shift to offset the series",
+                lambda, min,
+            )));
         }
 
-        let (trend, remainder, seasonal) = mstl(
+        let (mut trend, mut remainder, seasonal, windows, stl_results) = mstl(
             series,
             periods,
             self.iterate,
-            self.lambda,
+            lambda,
             &self.swin,
             &self.stl_params,
+            self.keep_stl_results,
+            self.statsmodels,
+            &self.fourier,
+            self.damping,
         )?;
 
+        if let Some((length, degree)) = self.final_trend {
+            let deseasonalized = trend
+                .iter()
+                .zip(&remainder)
+                .map(|(t, r)| t + r)
+                .collect::<Vec<f64>>();
+            // missing values are interpolated and given no weight, so they
+            // don't spread through the window
+            let filled = fill_missing(&deseasonalized);
+            let (values, weights) = match &filled {
+                Some((filled, weights)) => (&filled[..], Some(&weights[..])),
+                None => (&deseasonalized[..], None),
+            };
+            Loess::new(length, degree, 1).smooth(values, weights, 0, &mut trend);
+            for ((r, d), t) in remainder.iter_mut().zip(&deseasonalized).zip(&trend) {
+                *r = d - t;
+            }
+        }
+
         Ok(MstlResult {
             seasonal,
             trend,
             remainder,
+            stl_results,
+            periods: periods.to_vec(),
+            windows,
+            names: periods
+                .iter()
+                .map(|p| self.names.iter().find(|n| n.0 == *p).map(|n| n.1.clone()))
+                .collect(),
+            lambda,
+            shift,
+            observed: self.keep_observed.then(|| observed.to_vec()),
         })
     }
+
+    /// Decomposes multiple time series with the same periods.
+    ///
+    /// With the `parallel` feature, the series are fitted in parallel.
+    pub fn fit_batch<S: AsRef<[f64]> + Sync>(
+        &self,
+        series: &[S],
+        periods: &[usize],
+    ) -> Result<Vec<MstlResult>, Error> {
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+            crate::parallel::install(|| {
+                series
+                    .par_iter()
+                    .map(|s| self.fit(s.as_ref(), periods))
+                    .collect()
+            })
+        }
+
+        #[cfg(not(feature = "parallel"))]
+        series
+            .iter()
+            .map(|s| self.fit(s.as_ref(), periods))
+            .collect()
+    }
 }
 
 impl Default for MstlParams {
@@ -1,4 +1,7 @@
-use super::stl_result::strength;
+use super::leakage::leakage;
+use super::stl_result::{anova_strength, last_cycle};
+use super::strength::{component_strength, statsmodels_strength, strength_with};
+use super::{Error, Leakage, MstlParams, StlResult, StrengthMethod, Variance, inv_boxcox};
 
 /// A MSTL result.
 #[derive(Clone, Debug)]
@@ -6,6 +9,13 @@ pub struct MstlResult {
     pub(crate) seasonal: Vec<Vec<f64>>,
     pub(crate) trend: Vec<f64>,
     pub(crate) remainder: Vec<f64>,
+    pub(crate) stl_results: Option<Vec<StlResult>>,
+    pub(crate) periods: Vec<usize>,
+    pub(crate) windows: Vec<Option<usize>>,
+    pub(crate) names: Vec<Option<String>>,
+    pub(crate) lambda: Option<f64>,
+    pub(crate) shift: Option<f64>,
+    pub(crate) observed: Option<Vec<f64>>,
 }
 
 impl MstlResult {
@@ -24,17 +34,228 @@ impl MstlResult {
         &self.remainder
     }
 
+    /// Returns the input series, if kept.
+    pub fn observed(&self) -> Option<&[f64]> {
+        self.observed.as_deref()
+    }
+
+    /// Returns the number of observations.
+    pub fn nobs(&self) -> usize {
+        self.trend.len()
+    }
+
+    /// Returns the periods, in the same order as the seasonal components.
+    pub fn periods(&self) -> &[usize] {
+        &self.periods
+    }
+
+    /// Returns the length of the seasonal smoother used for each period, in
+    /// the same order as the seasonal components, or `None` for periods
+    /// modeled with Fourier terms.
+    pub fn windows(&self) -> &[Option<usize>] {
+        &self.windows
+    }
+
+    /// Returns the seasonal component of a period, if it was decomposed.
+    pub fn seasonal_for(&self, period: usize) -> Option<&[f64]> {
+        self.periods
+            .iter()
+            .position(|&p| p == period)
+            .map(|i| &self.seasonal[i][..])
+    }
+
+    /// Returns the name of each period, if given, in the same order as the
+    /// seasonal components.
+    pub fn period_names(&self) -> &[Option<String>] {
+        &self.names
+    }
+
+    /// Returns the seasonal component with the given name, if any.
+    pub fn seasonal_named(&self, name: &str) -> Option<&[f64]> {
+        self.names
+            .iter()
+            .position(|n| n.as_deref() == Some(name))
+            .map(|i| &self.seasonal[i][..])
+    }
+
+    /// Returns lambda used for Box-Cox transformation, if any.
+    pub fn lambda(&self) -> Option<f64> {
+        self.lambda
+    }
+
+    /// Returns the offset added to the series before the Box-Cox
+    /// transformation, if any.
+    pub fn shift(&self) -> Option<f64> {
+        self.shift
+    }
+
+    /// Returns a label for each seasonal component, like `seasonal_7`, or
+    /// `seasonal_weekly` for a named period.
+    pub fn labels(&self) -> Vec<String> {
+        self.periods
+            .iter()
+            .zip(&self.names)
+            .map(|(p, name)| match name {
+                Some(name) => format!("seasonal_{}", name),
+                None => format!("seasonal_{}", p),
+            })
+            .collect()
+    }
+
+    /// Returns the STL result from the final fit of each period, if kept.
+    pub fn stl_results(&self) -> Option<&[StlResult]> {
+        self.stl_results.as_deref()
+    }
+
     /// Returns the seasonal strength.
     pub fn seasonal_strength(&self) -> Vec<f64> {
         self.seasonal()
             .iter()
-            .map(|s| strength(s, self.remainder()))
+            .map(|s| component_strength(s, self.remainder()))
             .collect()
     }
 
+    /// Returns the seasonal strength of each component with the given
+    /// variance estimator.
+    pub fn seasonal_strength_with(&self, variance: Variance) -> Vec<f64> {
+        self.seasonal()
+            .iter()
+            .map(|s| strength_with(s, self.remainder(), variance))
+            .collect()
+    }
+
+    /// Returns the seasonal strength of each component from an ANOVA of its cycle-subseries.
+    pub fn seasonal_strength_anova(&self) -> Vec<f64> {
+        self.seasonal()
+            .iter()
+            .zip(&self.periods)
+            .map(|(s, &p)| anova_strength(s, self.remainder(), p))
+            .collect()
+    }
+
+    /// Returns the seasonal strength of each component with the given formula.
+    pub fn seasonal_strength_by(&self, method: StrengthMethod) -> Vec<f64> {
+        match method {
+            StrengthMethod::VarianceRatio => self.seasonal_strength(),
+            StrengthMethod::Statsmodels => self
+                .seasonal()
+                .iter()
+                .map(|s| statsmodels_strength(s, self.remainder()))
+                .collect(),
+            StrengthMethod::FTest => self.seasonal_strength_anova(),
+        }
+    }
+
     /// Returns the trend strength.
     pub fn trend_strength(&self) -> f64 {
-        strength(self.trend(), self.remainder())
+        component_strength(self.trend(), self.remainder())
+    }
+
+    /// Returns the trend strength with the given variance estimator.
+    pub fn trend_strength_with(&self, variance: Variance) -> f64 {
+        strength_with(self.trend(), self.remainder(), variance)
+    }
+
+    /// Tests the remainder for correlation with the trend and each seasonal
+    /// component, at lag 0 and at each period.
+    pub fn check_leakage(&self) -> Leakage {
+        let mut components = vec![("trend".to_string(), &self.trend[..])];
+        components.extend(
+            self.labels()
+                .into_iter()
+                .zip(self.seasonal.iter().map(|s| &s[..])),
+        );
+        leakage(&self.remainder, &components, &self.periods)
+    }
+
+    /// Adds the most recent cycle of every seasonal component back onto a
+    /// forecast of the seasonally adjusted series that starts right after the
+    /// fitted series.
+    ///
+    /// The forecast is on the Box-Cox scale when a lambda was used, and the
+    /// transformation is reversed after adding the seasonal components.
+    pub fn reseasonalize(&self, adjusted_forecast: &[f64]) -> Vec<f64> {
+        let n = self.trend.len();
+        let mut forecast = adjusted_forecast.to_vec();
+        for (seasonal, &period) in self.seasonal.iter().zip(&self.periods) {
+            let cycle = last_cycle(seasonal, period);
+            for (h, v) in forecast.iter_mut().enumerate() {
+                *v += cycle[(n + h) % period];
+            }
+        }
+        self.untransform(&forecast)
+    }
+
+    // reverses the Box-Cox transformation and the shift, if any
+    fn untransform(&self, series: &[f64]) -> Vec<f64> {
+        match self.lambda {
+            Some(lambda) => inv_boxcox(series, lambda)
+                .iter()
+                .map(|v| v - self.shift.unwrap_or(0.0))
+                .collect(),
+            None => series.to_vec(),
+        }
+    }
+
+    /// Appends new observations, refitting only the end of the series.
+    ///
+    /// The last three cycles of the longest period are decomposed again with
+    /// the new observations, using the lambda and shift of this result.
+    /// Components are replaced from the second cycle of that window on, so the
+    /// start of the window, where the smoothers have edge effects, is left as
+    /// it was. This is much cheaper than refitting the whole series when data
+    /// arrives in small batches. STL results are dropped, as they would only
+    /// cover the window.
+    pub fn extend(&mut self, new_points: &[f64], params: &MstlParams) -> Result<(), Error> {
+        if new_points.is_empty() {
+            return Ok(());
+        }
+        let n = self.trend.len();
+        let longest = self.periods.iter().copied().max().unwrap_or(0);
+
+        // the series on the original scale, rebuilt if it wasn't kept
+        let observed = match &self.observed {
+            Some(observed) => observed.clone(),
+            None => {
+                let fitted = (0..n)
+                    .map(|i| {
+                        self.seasonal.iter().map(|s| s[i]).sum::<f64>()
+                            + self.trend[i]
+                            + self.remainder[i]
+                    })
+                    .collect::<Vec<f64>>();
+                self.untransform(&fitted)
+            }
+        };
+
+        let start = n.saturating_sub(3 * longest);
+        let mut window = observed[start..].to_vec();
+        window.extend_from_slice(new_points);
+
+        let mut params = params.clone();
+        params.lambda = self.lambda;
+        params.auto_lambda = false;
+        params.shift = self.shift;
+        params.auto_shift = false;
+        params.keep_stl_results = false;
+        let refit = params.fit(&window, &self.periods)?;
+
+        // keep the first cycle of the window unless the window is the whole series
+        let keep = if start == 0 { 0 } else { start + longest };
+        let offset = keep - start;
+        for (seasonal, new) in self.seasonal.iter_mut().zip(&refit.seasonal) {
+            seasonal.truncate(keep);
+            seasonal.extend_from_slice(&new[offset..]);
+        }
+        self.trend.truncate(keep);
+        self.trend.extend_from_slice(&refit.trend[offset..]);
+        self.remainder.truncate(keep);
+        self.remainder.extend_from_slice(&refit.remainder[offset..]);
+        if let Some(observed) = &mut self.observed {
+            observed.extend_from_slice(new_points);
+        }
+        self.stl_results = None;
+        Ok(())
     }
 
     /// Consumes the result, returning the seasonal components, trend component, and remainder.
@@ -0,0 +1,58 @@
+use std::sync::{Arc, RwLock};
+
+use rayon::ThreadPool;
+use rayon::ThreadPoolBuilder;
+
+use super::Error;
+
+static THREAD_POOL: RwLock<Option<Arc<ThreadPool>>> = RwLock::new(None);
+
+/// Sets the thread pool used for parallel fitting.
+///
+/// Without a configured pool, work runs on the pool of the caller, which is
+/// rayon's global pool unless the call happens inside [`ThreadPool::install`].
+pub fn set_thread_pool(pool: ThreadPool) {
+    *THREAD_POOL.write().unwrap() = Some(Arc::new(pool));
+}
+
+/// Sets the number of threads used for parallel fitting.
+pub fn set_num_threads(num_threads: usize) -> Result<(), Error> {
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()
+        .map_err(|e| Error::Parameter(e.to_string()))?;
+    set_thread_pool(pool);
+    Ok(())
+}
+
+/// Removes the configured thread pool.
+pub fn clear_thread_pool() {
+    *THREAD_POOL.write().unwrap() = None;
+}
+
+/// Runs `op` with parallel fitting on the given thread pool.
+pub fn with_thread_pool<R, F>(pool: &ThreadPool, op: F) -> R
+where
+    F: FnOnce() -> R + Send,
+    R: Send,
+{
+    pool.install(op)
+}
+
+// Swaps in a thread pool, returning the previous one so it can be put back
+pub(crate) fn replace(pool: Option<Arc<ThreadPool>>) -> Option<Arc<ThreadPool>> {
+    std::mem::replace(&mut *THREAD_POOL.write().unwrap(), pool)
+}
+
+pub(crate) fn install<R, F>(op: F) -> R
+where
+    F: FnOnce() -> R + Send,
+    R: Send,
+{
+    let pool = THREAD_POOL.read().unwrap().clone();
+    match pool {
+        // don't hop pools when already running on a worker thread
+        Some(pool) if rayon::current_thread_index().is_none() => pool.install(op),
+        _ => op(),
+    }
+}
@@ -0,0 +1,42 @@
+//! Named sets of STL parameters shared across a process.
+//!
+//! Register settings once, such as at startup, and look them up by name
+//! wherever a decomposition is configured.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use super::StlParams;
+
+static PROFILES: RwLock<Option<HashMap<String, StlParams>>> = RwLock::new(None);
+
+/// Registers parameters under a name, replacing any profile with that name.
+pub fn register(name: &str, params: StlParams) {
+    PROFILES
+        .write()
+        .unwrap()
+        .get_or_insert_with(HashMap::new)
+        .insert(name.to_string(), params);
+}
+
+/// Removes a profile, returning its parameters.
+pub fn unregister(name: &str) -> Option<StlParams> {
+    PROFILES.write().unwrap().as_mut()?.remove(name)
+}
+
+/// Returns a copy of the parameters registered under a name.
+pub fn get(name: &str) -> Option<StlParams> {
+    PROFILES.read().unwrap().as_ref()?.get(name).cloned()
+}
+
+/// Returns the names of the registered profiles, sorted.
+pub fn names() -> Vec<String> {
+    let mut names = PROFILES
+        .read()
+        .unwrap()
+        .as_ref()
+        .map(|p| p.keys().cloned().collect::<Vec<String>>())
+        .unwrap_or_default();
+    names.sort();
+    names
+}
@@ -0,0 +1,77 @@
+/// Estimated effects of regressors removed from a series before decomposition.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RegressionEffect {
+    pub(crate) names: Vec<String>,
+    pub(crate) coefficients: Vec<f64>,
+    pub(crate) effect: Vec<f64>,
+}
+
+impl RegressionEffect {
+    /// Returns the names of the regressors.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Returns the estimated coefficient of each regressor.
+    pub fn coefficients(&self) -> &[f64] {
+        &self.coefficients
+    }
+
+    /// Returns the combined effect at each observation.
+    pub fn effect(&self) -> &[f64] {
+        &self.effect
+    }
+}
+
+// Least-squares coefficients of y on the columns, skipping missing values
+pub(crate) fn ols(y: &[f64], columns: &[Vec<f64>]) -> Vec<f64> {
+    let m = columns.len();
+    let mut xtx = vec![vec![0.0; m]; m];
+    let mut xty = vec![0.0; m];
+    for (t, &v) in y.iter().enumerate() {
+        if v.is_nan() {
+            continue;
+        }
+        for a in 0..m {
+            xty[a] += columns[a][t] * v;
+            for b in 0..m {
+                xtx[a][b] += columns[a][t] * columns[b][t];
+            }
+        }
+    }
+    solve(xtx, xty)
+}
+
+// Gaussian elimination with partial pivoting; singular directions are set to zero
+fn solve(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Vec<f64> {
+    let m = b.len();
+    for col in 0..m {
+        let pivot = (col..m)
+            .max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))
+            .unwrap();
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        if a[col][col].abs() < 1e-12 {
+            continue;
+        }
+        let (top, bottom) = a.split_at_mut(col + 1);
+        let pivot_row = &top[col];
+        for (i, row) in bottom.iter_mut().enumerate() {
+            let f = row[col] / pivot_row[col];
+            for (v, p) in row[col..].iter_mut().zip(&pivot_row[col..]) {
+                *v -= f * p;
+            }
+            b[col + 1 + i] -= f * b[col];
+        }
+    }
+
+    let mut x = vec![0.0; m];
+    for row in (0..m).rev() {
+        if a[row][row].abs() < 1e-12 {
+            continue;
+        }
+        let s = (row + 1..m).map(|c| a[row][c] * x[c]).sum::<f64>();
+        x[row] = (b[row] - s) / a[row][row];
+    }
+    x
+}
@@ -0,0 +1,78 @@
+/// Autocorrelation of the remainder at the seasonal lags.
+///
+/// Seasonality left in the remainder, such as when the seasonal smoother is
+/// too stiff to follow a changing pattern, shows up as positive
+/// autocorrelation at the period and its harmonics.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResidualSeasonality {
+    pub(crate) lags: Vec<usize>,
+    pub(crate) acf: Vec<f64>,
+    pub(crate) threshold: f64,
+}
+
+impl ResidualSeasonality {
+    /// Returns the lags tested: the period and the periods of its harmonics
+    /// that are whole numbers of at least 2.
+    pub fn lags(&self) -> &[usize] {
+        &self.lags
+    }
+
+    /// Returns the autocorrelation of the remainder at each lag.
+    pub fn acf(&self) -> &[f64] {
+        &self.acf
+    }
+
+    /// Returns the autocorrelation above which seasonality is detected.
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    /// Returns whether the autocorrelation at any lag exceeds the threshold.
+    pub fn detected(&self) -> bool {
+        self.acf.iter().any(|&r| r > self.threshold)
+    }
+}
+
+// Autocorrelation at a lag, skipping pairs with a missing value
+fn acf(values: &[f64], mean: f64, var: f64, lag: usize) -> f64 {
+    let sum = values
+        .iter()
+        .zip(&values[lag..])
+        .filter(|(a, b)| !a.is_nan() && !b.is_nan())
+        .map(|(a, b)| (a - mean) * (b - mean))
+        .sum::<f64>();
+    sum / var
+}
+
+pub(crate) fn residual_seasonality(remainder: &[f64], period: usize) -> ResidualSeasonality {
+    let lags = (1..=period / 2)
+        .filter(|&k| period.is_multiple_of(k) && period / k >= 2)
+        .map(|k| period / k)
+        .filter(|&lag| lag < remainder.len())
+        .collect::<Vec<usize>>();
+
+    let observed = remainder.iter().filter(|v| !v.is_nan()).count();
+    let mean = remainder.iter().filter(|v| !v.is_nan()).sum::<f64>() / observed as f64;
+    let var = remainder
+        .iter()
+        .filter(|v| !v.is_nan())
+        .map(|v| (v - mean).powi(2))
+        .sum::<f64>();
+    let acf = lags
+        .iter()
+        .map(|&lag| {
+            if var > 0.0 {
+                acf(remainder, mean, var, lag)
+            } else {
+                0.0
+            }
+        })
+        .collect();
+
+    // a one-sided 1% test for each lag under white noise
+    ResidualSeasonality {
+        lags,
+        acf,
+        threshold: 2.33 / (observed as f64).sqrt(),
+    }
+}
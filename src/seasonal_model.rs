@@ -0,0 +1,143 @@
+use std::io::{BufRead, BufReader, Read, Write};
+
+use super::Error;
+
+const HEADER: &str = "stl-rs seasonal model v1";
+
+/// A compact description of a fitted decomposition.
+///
+/// Holds the most recent seasonal cycle, the tail of the trend, and the scale
+/// of the remainder, which is enough to score new observations without refitting.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SeasonalModel {
+    pub(crate) cycle: Vec<f64>,
+    pub(crate) next_phase: usize,
+    pub(crate) trend_tail: Vec<f64>,
+    pub(crate) remainder_scale: f64,
+}
+
+pub(crate) fn median(values: &[f64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap());
+    let n = sorted.len();
+    (sorted[(n - 1) / 2] + sorted[n / 2]) / 2.0
+}
+
+// median absolute deviation scaled to match the standard deviation
+// of normally distributed data
+pub(crate) fn mad(values: &[f64]) -> f64 {
+    let values = values
+        .iter()
+        .copied()
+        .filter(|v| !v.is_nan())
+        .collect::<Vec<f64>>();
+    let m = median(&values);
+    let dev = values.iter().map(|v| (v - m).abs()).collect::<Vec<f64>>();
+    1.4826 * median(&dev)
+}
+
+fn invalid(message: &str) -> Error {
+    Error::Format(format!("invalid seasonal model: {}", message))
+}
+
+fn parse_values(line: &str, key: &str) -> Result<Vec<f64>, Error> {
+    let rest = line
+        .strip_prefix(key)
+        .ok_or_else(|| invalid(&format!("expected {}", key)))?;
+    rest.split_whitespace()
+        .map(|v| {
+            v.parse::<f64>()
+                .map_err(|_| invalid(&format!("bad value in {}", key)))
+        })
+        .collect()
+}
+
+impl SeasonalModel {
+    /// Returns the period.
+    pub fn period(&self) -> usize {
+        self.cycle.len()
+    }
+
+    /// Returns the seasonal cycle, indexed by phase.
+    pub fn seasonal_cycle(&self) -> &[f64] {
+        &self.cycle
+    }
+
+    /// Returns the phase of the observation following the fitted series.
+    pub fn next_phase(&self) -> usize {
+        self.next_phase
+    }
+
+    /// Returns the last period of the trend component.
+    pub fn trend_tail(&self) -> &[f64] {
+        &self.trend_tail
+    }
+
+    /// Returns the robust scale (scaled MAD) of the remainder.
+    pub fn remainder_scale(&self) -> f64 {
+        self.remainder_scale
+    }
+
+    /// Removes the seasonal cycle from observations that directly follow the fitted series.
+    pub fn seasonally_adjust(&self, new_series: &[f64]) -> Vec<f64> {
+        new_series
+            .iter()
+            .enumerate()
+            .map(|(i, v)| v - self.cycle[(self.next_phase + i) % self.cycle.len()])
+            .collect()
+    }
+
+    /// Writes the model in a line-based text format.
+    pub fn save<W: Write>(&self, mut writer: W) -> Result<(), Error> {
+        let join = |values: &[f64]| {
+            values
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<String>>()
+                .join(" ")
+        };
+        write!(
+            writer,
+            "{}\nphase {}\nremainder_scale {}\ncycle {}\ntrend {}\n",
+            HEADER,
+            self.next_phase,
+            self.remainder_scale,
+            join(&self.cycle),
+            join(&self.trend_tail)
+        )
+        .map_err(|e| Error::Io(e.to_string()))
+    }
+
+    /// Reads a model written by [`SeasonalModel::save`].
+    pub fn load<R: Read>(reader: R) -> Result<Self, Error> {
+        let lines = BufReader::new(reader)
+            .lines()
+            .collect::<Result<Vec<String>, _>>()
+            .map_err(|e| Error::Io(e.to_string()))?;
+        if lines.len() < 5 || lines[0] != HEADER {
+            return Err(invalid("unrecognized format"));
+        }
+
+        let next_phase = lines[1]
+            .strip_prefix("phase ")
+            .and_then(|v| v.parse::<usize>().ok())
+            .ok_or_else(|| invalid("bad phase"))?;
+        let remainder_scale = parse_values(&lines[2], "remainder_scale")?;
+        let cycle = parse_values(&lines[3], "cycle")?;
+        let trend_tail = parse_values(&lines[4], "trend")?;
+
+        if remainder_scale.len() != 1 {
+            return Err(invalid("bad remainder_scale"));
+        }
+        if cycle.len() < 2 || next_phase >= cycle.len() {
+            return Err(invalid("cycle does not match phase"));
+        }
+
+        Ok(Self {
+            cycle,
+            next_phase,
+            trend_tail,
+            remainder_scale: remainder_scale[0],
+        })
+    }
+}
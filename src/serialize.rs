@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Error, FitReport, RegressionEffect, StlResult};
+
+// Every encoding starts with the magic bytes and a format version, so that
+// later versions can keep reading older encodings
+const MAGIC: &[u8; 4] = b"STLR";
+const VERSION: u16 = 1;
+
+// The layout of version 1, kept separate from StlResult so that changes to
+// the result don't change the format
+#[derive(Serialize, Deserialize)]
+struct ResultV1 {
+    period: u64,
+    seasonal_length: u64,
+    seasonal_degree: i32,
+    trend_length: u64,
+    trend_degree: i32,
+    partial_cycle: u64,
+    seasonal: Vec<f64>,
+    trend: Vec<f64>,
+    remainder: Vec<f64>,
+    weights: Vec<f64>,
+    regression: Option<(Vec<String>, Vec<f64>, Vec<f64>)>,
+}
+
+fn invalid(message: &str) -> Error {
+    Error::Format(format!("invalid result encoding: {}", message))
+}
+
+pub(crate) fn to_bytes(result: &StlResult) -> Vec<u8> {
+    let encoded = ResultV1 {
+        period: result.period as u64,
+        seasonal_length: result.seasonal_length as u64,
+        seasonal_degree: result.seasonal_degree,
+        trend_length: result.trend_length as u64,
+        trend_degree: result.trend_degree,
+        partial_cycle: result.partial_cycle as u64,
+        seasonal: result.seasonal.clone(),
+        trend: result.trend.clone(),
+        remainder: result.remainder.clone(),
+        weights: result.weights.clone(),
+        regression: result
+            .regression
+            .as_ref()
+            .map(|r| (r.names.clone(), r.coefficients.clone(), r.effect.clone())),
+    };
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend_from_slice(&VERSION.to_le_bytes());
+    postcard::to_extend(&encoded, bytes).expect("encoding into a Vec can't fail")
+}
+
+pub(crate) fn from_bytes(bytes: &[u8]) -> Result<StlResult, Error> {
+    if bytes.len() < 6 || &bytes[..4] != MAGIC {
+        return Err(invalid("unrecognized format"));
+    }
+    let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+    if version != VERSION {
+        return Err(invalid(&format!("unsupported version {}", version)));
+    }
+    let decoded: ResultV1 =
+        postcard::from_bytes(&bytes[6..]).map_err(|e| invalid(&e.to_string()))?;
+
+    let n = decoded.seasonal.len();
+    if decoded.trend.len() != n || decoded.remainder.len() != n || decoded.weights.len() != n {
+        return Err(invalid("components have different lengths"));
+    }
+    if decoded.period < 2 || decoded.period as usize > n {
+        return Err(invalid("period does not match the components"));
+    }
+    let regression = match decoded.regression {
+        Some((names, coefficients, effect)) => {
+            if coefficients.len() != names.len() || effect.len() != n {
+                return Err(invalid("regression does not match the components"));
+            }
+            Some(RegressionEffect {
+                names,
+                coefficients,
+                effect,
+            })
+        }
+        None => None,
+    };
+
+    let result = StlResult {
+        seasonal: decoded.seasonal,
+        trend: decoded.trend,
+        remainder: decoded.remainder,
+        weights: decoded.weights,
+        period: decoded.period as usize,
+        seasonal_length: decoded.seasonal_length as usize,
+        seasonal_degree: decoded.seasonal_degree,
+        trend_length: decoded.trend_length as usize,
+        trend_degree: decoded.trend_degree,
+        partial_cycle: decoded.partial_cycle as usize,
+        regression,
+        report: FitReport::default(),
+        residual_seasonality: None,
+        clipped: Vec::new(),
+        params: None,
+    };
+    result.check_settings().map_err(|e| invalid(e.message()))?;
+    Ok(result)
+}
@@ -0,0 +1,108 @@
+use std::collections::VecDeque;
+
+use super::{Error, StlParams, StlResult};
+
+/// STL over a fixed-size window of the most recent observations.
+///
+/// Memory use is bounded by the window length, regardless of how many
+/// observations are pushed.
+#[derive(Clone, Debug)]
+pub struct SlidingStl {
+    buffer: VecDeque<f64>,
+    window_len: usize,
+    period: usize,
+    params: StlParams,
+    count: usize,
+    // the trend of the last decomposition and the index of its first value
+    previous: Option<(Vec<f64>, usize)>,
+}
+
+impl SlidingStl {
+    /// Creates a new sliding window with default parameters.
+    pub fn new(window_len: usize, period: usize) -> Result<Self, Error> {
+        Self::with_params(window_len, period, StlParams::new())
+    }
+
+    /// Creates a new sliding window with the given parameters.
+    ///
+    /// The window must hold at least two periods.
+    pub fn with_params(window_len: usize, period: usize, params: StlParams) -> Result<Self, Error> {
+        if period < 2 {
+            return Err(Error::Parameter("period must be at least 2".to_string()));
+        }
+        if window_len < 2 * period {
+            return Err(Error::Parameter(
+                "window_len must be at least two periods".to_string(),
+            ));
+        }
+        Ok(Self {
+            buffer: VecDeque::with_capacity(window_len),
+            window_len,
+            period,
+            params,
+            count: 0,
+            previous: None,
+        })
+    }
+
+    /// Adds an observation, dropping the oldest one once the window is full.
+    pub fn push(&mut self, value: f64) {
+        if self.buffer.len() == self.window_len {
+            self.buffer.pop_front();
+        }
+        self.buffer.push_back(value);
+        self.count += 1;
+    }
+
+    /// Decomposes the current window.
+    ///
+    /// Each call refits the whole window, which takes time linear in the
+    /// window length. Unless the parameters set a warm start, the fit starts
+    /// from the trend of the previous call, shifted by the observations pushed
+    /// since, which takes fewer iterations (see
+    /// [`StlParams::warm_start`]).
+    pub fn current(&mut self) -> Result<StlResult, Error> {
+        let start = self.count - self.buffer.len();
+        let warm = self
+            .previous
+            .as_ref()
+            .and_then(|(trend, previous)| trend.get(start - previous..).filter(|t| !t.is_empty()));
+        let window = self.buffer.make_contiguous();
+        let result = match warm {
+            Some(trend) if self.params.warm_trend.is_none() => {
+                let mut params = self.params.clone();
+                params.warm_trend = Some(trend.into());
+                params.fit(window, self.period)
+            }
+            _ => self.params.fit(window, self.period),
+        }?;
+        self.previous = Some((result.trend().to_vec(), start));
+        Ok(result)
+    }
+
+    /// Returns the number of observations in the window.
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns the period.
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Returns whether the window is empty.
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    /// Returns whether the window is full.
+    pub fn is_full(&self) -> bool {
+        self.buffer.len() == self.window_len
+    }
+
+    /// Returns the phase of the first observation in the window, counting
+    /// from the first observation ever pushed.
+    pub fn start_phase(&self) -> usize {
+        (self.count - self.buffer.len()) % self.period
+    }
+}
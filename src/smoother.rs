@@ -0,0 +1,113 @@
+use std::fmt;
+
+use super::FitReport;
+use super::stl_impl::{ess, est};
+
+/// A smoother used in one stage of STL.
+///
+/// Custom smoothers replace loess in the seasonal, trend, or low-pass stage.
+/// Diagnostics and slopes on the result describe the default loess smoothers.
+pub trait Smoother: fmt::Debug + Send + Sync {
+    /// Smooths `y`, writing the fitted values to `fitted`.
+    ///
+    /// `fitted` has `y.len() + 2 * extend` elements, with the fit evaluated at
+    /// `extend` positions before the first and after the last observation as well.
+    /// The seasonal stage uses `extend == 1`, the other stages `extend == 0`.
+    /// `weights` are the robustness weights, if any.
+    fn smooth(&self, y: &[f64], weights: Option<&[f64]>, extend: usize, fitted: &mut [f64]);
+}
+
+/// The loess smoother used by default in every stage.
+#[derive(Clone, Debug)]
+pub struct Loess {
+    length: usize,
+    degree: i32,
+    jump: usize,
+}
+
+impl Loess {
+    /// Creates a loess smoother with the given window length, polynomial degree (0 or 1), and skipping value.
+    pub fn new(length: usize, degree: i32, jump: usize) -> Self {
+        Self {
+            length,
+            degree,
+            jump: jump.max(1),
+        }
+    }
+}
+
+impl Smoother for Loess {
+    fn smooth(&self, y: &[f64], weights: Option<&[f64]>, extend: usize, fitted: &mut [f64]) {
+        let n = y.len();
+        let len = self.length;
+        let userw = weights.is_some();
+        let rw = weights.unwrap_or(y);
+        let mut res = vec![0.0; n];
+
+        ess(
+            y,
+            n,
+            len,
+            self.degree,
+            self.jump,
+            userw,
+            rw,
+            &mut fitted[extend..extend + n],
+            &mut res,
+            &mut FitReport::default(),
+        );
+
+        for e in 1..=extend {
+            let xs = 1.0 - e as f64;
+            let ok = est(
+                y,
+                n,
+                len,
+                self.degree,
+                xs,
+                &mut fitted[extend - e],
+                1,
+                len.min(n),
+                &mut res,
+                userw,
+                rw,
+            );
+            if !ok {
+                fitted[extend - e] = fitted[extend - e + 1];
+            }
+
+            let xs = (n + e) as f64;
+            let nleft = 1.max(n as i32 - len as i32 + 1) as usize;
+            let ok = est(
+                y,
+                n,
+                len,
+                self.degree,
+                xs,
+                &mut fitted[extend + n + e - 1],
+                nleft,
+                n,
+                &mut res,
+                userw,
+                rw,
+            );
+            if !ok {
+                fitted[extend + n + e - 1] = fitted[extend + n + e - 2];
+            }
+        }
+    }
+}
+
+// Returns fixed values regardless of the input, for a component given in
+// advance; positions past the end repeat the last value
+#[derive(Debug)]
+pub(crate) struct Fixed<'a>(pub(crate) &'a [f64]);
+
+impl Smoother for Fixed<'_> {
+    fn smooth(&self, y: &[f64], _weights: Option<&[f64]>, extend: usize, fitted: &mut [f64]) {
+        let last = self.0.len() - 1;
+        for (i, v) in fitted[..y.len() + 2 * extend].iter_mut().enumerate() {
+            *v = self.0[i.saturating_sub(extend).min(last)];
+        }
+    }
+}
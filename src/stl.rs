@@ -9,6 +9,15 @@ impl Stl {
         StlParams::new().fit(series, period)
     }
 
+    /// Decomposes a time series with prior weights for each observation.
+    pub fn fit_with_weights(
+        series: &[f64],
+        period: usize,
+        weights: &[f64],
+    ) -> Result<StlResult, Error> {
+        StlParams::new().fit_with_weights(series, period, weights)
+    }
+
     /// Creates a new set of parameters.
     pub fn params() -> StlParams {
         StlParams::new()
@@ -16,12 +25,28 @@ impl Stl {
 }
 
 // Re-export the types so they can be imported from this module
-pub use super::stl_params::StlParams;
-pub use super::stl_result::StlResult;
+pub use super::anomaly::{Anomaly, Outliers};
+pub use super::anomaly_stream::{AnomalyScore, AnomalyStream};
+pub use super::forecast::ForecastMethod;
+pub use super::harmonic::Harmonic;
+pub use super::loess_diagnostics::LoessDiagnostics;
+pub use super::seasonal_model::SeasonalModel;
+pub use super::sliding::SlidingStl;
+pub use super::smoother::{Loess, Smoother};
+pub use super::stl_params::{CountModel, Monotone, NonNegative, PartialCycle, Shared, StlParams};
+pub use super::stl_result::{AggFn, Components, Row, StlResult};
+pub use super::turning_point::TurningPoint;
 
 #[cfg(test)]
 mod tests {
-    use crate::{Error, Stl};
+    use crate::synth::{Noise, SynthParams};
+    use crate::{
+        AggFn, AnomalyStream, CountModel, DecompositionModel, Error, ForecastMethod, Loess,
+        Monotone, NonNegative, PartialCycle, SeasonalFilter, SeasonalModel, Shared, SlidingStl,
+        Smoother, Stl, StlParams, StlResult, StrengthMethod, TimeSeries, TurningPoint, Variance,
+        X11, add_components, classical_decompose, fill_gaps, scale_component, seasonal_strengths,
+        strength_summary, subtract_components, sum_components, trend_strengths,
+    };
 
     fn assert_in_delta(exp: f64, act: f64) {
         assert!((exp - act).abs() < 0.001);
@@ -35,10 +60,10 @@ mod tests {
     }
 
     fn generate_series() -> Vec<f64> {
-        return vec![
+        vec![
             5.0, 9.0, 2.0, 9.0, 0.0, 6.0, 3.0, 8.0, 5.0, 8.0, 7.0, 8.0, 8.0, 0.0, 2.0, 5.0, 0.0,
             5.0, 6.0, 7.0, 3.0, 6.0, 1.0, 4.0, 4.0, 4.0, 3.0, 7.0, 5.0, 8.0,
-        ];
+        ]
     }
 
     #[test]
@@ -193,6 +218,38 @@ mod tests {
         assert_in_delta(1.0, result.seasonal_strength());
     }
 
+    #[test]
+    fn test_seasonal_strength_anova() {
+        let result = Stl::fit(&generate_series(), 7).unwrap();
+        assert_in_delta(0.0, result.seasonal_strength_anova());
+
+        let series = (0..30).map(|v| (v % 7) as f64).collect::<Vec<f64>>();
+        let result = Stl::fit(&series, 7).unwrap();
+        assert_in_delta(1.0, result.seasonal_strength_anova());
+    }
+
+    #[test]
+    fn test_seasonal_strength_pvalue() {
+        let result = Stl::fit(&generate_series(), 7).unwrap();
+        assert!(result.seasonal_strength_pvalue(19, 0).unwrap() > 0.05);
+
+        let series = (0..42)
+            .map(|v| ((v % 7) as f64) + 0.1 * ((v * 5 % 3) as f64))
+            .collect::<Vec<f64>>();
+        let result = Stl::fit(&series, 7).unwrap();
+        assert_in_delta(0.05, result.seasonal_strength_pvalue(19, 0).unwrap());
+
+        // surrogates are refit with the parameters of the fit
+        let result = Stl::params().robust(true).fit(&series, 7).unwrap();
+        assert_in_delta(0.05, result.seasonal_strength_pvalue(19, 1).unwrap());
+
+        let result = X11::fit(&series, 7).unwrap();
+        assert_eq!(
+            result.seasonal_strength_pvalue(19, 0).unwrap_err(),
+            Error::Parameter("the p-value needs a result fitted with StlParams".to_string())
+        );
+    }
+
     #[test]
     fn test_trend_strength() {
         let result = Stl::fit(&generate_series(), 7).unwrap();
@@ -205,4 +262,1614 @@ mod tests {
         let result = Stl::fit(&series, 7).unwrap();
         assert_in_delta(1.0, result.trend_strength());
     }
+
+    #[test]
+    fn test_trend_diagnostics() {
+        let result = Stl::fit(&generate_series(), 7).unwrap();
+        let diagnostics = result.trend_diagnostics();
+        assert_eq!(30, diagnostics.leverage().len());
+        assert!(
+            diagnostics
+                .leverage()
+                .iter()
+                .all(|&v| (0.0..=1.0).contains(&v))
+        );
+        assert!(diagnostics.equivalent_parameters() > 2.0);
+        assert!(diagnostics.equivalent_parameters() < 30.0);
+        assert_in_delta(
+            30.0 - diagnostics.equivalent_parameters(),
+            diagnostics.residual_df(),
+        );
+    }
+
+    #[test]
+    fn test_seasonal_diagnostics() {
+        let result = Stl::fit(&generate_series(), 7).unwrap();
+        let seasonal = result.seasonal_diagnostics();
+        let trend = result.trend_diagnostics();
+        assert_eq!(30, seasonal.leverage().len());
+        assert!(seasonal.equivalent_parameters() > trend.equivalent_parameters());
+    }
+
+    #[test]
+    fn test_trend_slope() {
+        let series = (0..30)
+            .map(|v| 2.0 * v as f64 + (v % 7) as f64)
+            .collect::<Vec<f64>>();
+        let result = Stl::fit(&series, 7).unwrap();
+        let slope = result.trend_slope();
+        assert_eq!(30, slope.len());
+        for v in slope {
+            assert!((v - 2.0).abs() < 0.1);
+        }
+    }
+
+    #[test]
+    fn test_trend_turning_points() {
+        let series = (0..42)
+            .map(|v| (if v < 21 { v } else { 42 - v }) as f64 + (v % 7) as f64)
+            .collect::<Vec<f64>>();
+        let result = Stl::fit(&series, 7).unwrap();
+        let points = result.trend_turning_points(3);
+        assert_eq!(1, points.len());
+        assert!(matches!(points[0], TurningPoint::Peak(_)));
+        assert!((18..=24).contains(&points[0].index()));
+    }
+
+    #[test]
+    fn test_trend_turning_points_persistence() {
+        let series = (0..30).map(|v| v as f64).collect::<Vec<f64>>();
+        let result = Stl::fit(&series, 7).unwrap();
+        assert!(result.trend_turning_points(3).is_empty());
+    }
+
+    #[test]
+    fn test_seasonally_adjust() {
+        let result = Stl::fit(&generate_series(), 7).unwrap();
+        let cycle = result.seasonal_cycle();
+        assert_eq!(7, cycle.len());
+        assert_in_delta(result.seasonal()[29], cycle[29 % 7]);
+        let adjusted = result.seasonally_adjust(&[1.0, 2.0, 3.0], 30);
+        assert_elements_in_delta(
+            &[
+                1.0 - result.seasonal()[23],
+                2.0 - result.seasonal()[24],
+                3.0 - result.seasonal()[25],
+            ],
+            &adjusted,
+        );
+    }
+
+    #[test]
+    fn test_seasonal_model() {
+        let result = Stl::fit(&generate_series(), 7).unwrap();
+        let model = result.seasonal_model();
+        assert_eq!(7, model.period());
+        assert_eq!(2, model.next_phase());
+        assert_elements_in_delta(&result.trend()[23..], model.trend_tail());
+        assert!(model.remainder_scale() > 0.0);
+        assert_elements_in_delta(
+            &result.seasonally_adjust(&[1.0, 2.0], 30),
+            &model.seasonally_adjust(&[1.0, 2.0]),
+        );
+
+        let mut buf = Vec::new();
+        model.save(&mut buf).unwrap();
+        let loaded = SeasonalModel::load(&buf[..]).unwrap();
+        assert_eq!(model, loaded);
+    }
+
+    #[test]
+    fn test_seasonal_model_load_invalid() {
+        let err = SeasonalModel::load("not a model".as_bytes()).unwrap_err();
+        assert_eq!(
+            err,
+            Error::Format("invalid seasonal model: unrecognized format".to_string())
+        );
+        assert_eq!(4, err.code());
+        assert!(!err.is_recoverable());
+    }
+
+    #[test]
+    fn test_fit_with_weights() {
+        let series = generate_series();
+        let unweighted = Stl::fit(&series, 7).unwrap();
+        let result = Stl::fit_with_weights(&series, 7, &[1.0; 30]).unwrap();
+        assert_elements_in_delta(unweighted.seasonal(), result.seasonal());
+        assert_elements_in_delta(unweighted.trend(), result.trend());
+
+        let mut weights = vec![1.0; 30];
+        weights[4] = 0.0;
+        let result = Stl::fit_with_weights(&series, 7, &weights).unwrap();
+        assert_eq!(0.0, result.weights()[4]);
+        assert!((result.trend()[4] - unweighted.trend()[4]).abs() > 0.001);
+    }
+
+    #[test]
+    fn test_fit_with_weights_bad_length() {
+        let result = Stl::fit_with_weights(&generate_series(), 7, &[1.0; 10]);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::Parameter("weights must have the same length as series".to_string())
+        );
+    }
+
+    #[test]
+    fn test_fit_batch() {
+        let series = vec![generate_series(), generate_series()];
+        let results = Stl::params().fit_batch(&series, 7).unwrap();
+        assert_eq!(2, results.len());
+        let expected = Stl::fit(&generate_series(), 7).unwrap();
+        for result in results {
+            assert_elements_in_delta(expected.seasonal(), result.seasonal());
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_with_thread_pool() {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap();
+        let series = vec![generate_series(); 4];
+        let results =
+            crate::with_thread_pool(&pool, || Stl::params().fit_batch(&series, 7)).unwrap();
+        assert_eq!(4, results.len());
+    }
+
+    #[test]
+    fn test_missing() {
+        let mut series = generate_series();
+        series[4] = f64::NAN;
+        series[0] = f64::NAN;
+        let result = Stl::fit(&series, 7).unwrap();
+        assert!(result.seasonal().iter().all(|v| v.is_finite()));
+        assert!(result.trend().iter().all(|v| v.is_finite()));
+        assert!(result.remainder()[4].is_nan());
+        assert_eq!(0.0, result.weights()[4]);
+        assert!(result.seasonal_strength().is_finite());
+        assert!(result.trend_slope().iter().all(|v| v.is_finite()));
+    }
+
+    #[test]
+    fn test_all_missing() {
+        let result = Stl::fit(&[f64::NAN; 30], 7);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::Series("series has no observed values".to_string())
+        );
+    }
+
+    #[test]
+    fn test_loess_smoothers() {
+        let series = generate_series();
+        let expected = Stl::params().trend_length(15).fit(&series, 7).unwrap();
+        let result = Stl::params()
+            .seasonal_smoother(Loess::new(7, 0, 1))
+            .trend_smoother(Loess::new(15, 1, 2))
+            .low_pass_smoother(Loess::new(7, 1, 1))
+            .fit(&series, 7)
+            .unwrap();
+        assert_elements_in_delta(expected.seasonal(), result.seasonal());
+        assert_elements_in_delta(expected.trend(), result.trend());
+    }
+
+    #[derive(Debug)]
+    struct Mean;
+
+    impl Smoother for Mean {
+        fn smooth(&self, y: &[f64], _: Option<&[f64]>, _: usize, fitted: &mut [f64]) {
+            let mean = y.iter().sum::<f64>() / y.len() as f64;
+            fitted.fill(mean);
+        }
+    }
+
+    #[test]
+    fn test_custom_smoother() {
+        let series = (0..30)
+            .map(|v| 3.0 + ((v % 7) as f64))
+            .collect::<Vec<f64>>();
+        let result = Stl::params().trend_smoother(Mean).fit(&series, 7).unwrap();
+        let trend = result.trend()[0];
+        assert!(result.trend().iter().all(|&v| v == trend));
+    }
+
+    #[test]
+    fn test_anomalies() {
+        let mut series = (0..42)
+            .map(|v| 10.0 + ((v % 7) as f64) + 0.1 * ((v * 5 % 3) as f64))
+            .collect::<Vec<f64>>();
+        series[20] += 10.0;
+        let result = Stl::params().robust(true).fit(&series, 7).unwrap();
+        let anomalies = result.anomalies(0.01);
+        assert_eq!(1, anomalies.len());
+        assert_eq!(20, anomalies[0].index());
+        assert!(anomalies[0].is_high());
+        assert_in_delta(series[20], anomalies[0].observed());
+    }
+
+    #[test]
+    fn test_outliers() {
+        let mut series = (0..42)
+            .map(|v| 10.0 + ((v % 7) as f64) + 0.1 * ((v * 5 % 3) as f64))
+            .collect::<Vec<f64>>();
+        series[20] += 10.0;
+        let result = Stl::params().robust(true).fit(&series, 7).unwrap();
+        let outliers = result.outliers(3.0);
+        assert_eq!(vec![20], outliers.indices());
+        assert!(outliers.flags()[20]);
+        assert!(outliers.scores()[20] > 3.0);
+        assert_eq!(0.0, outliers.scores()[0]);
+    }
+
+    #[test]
+    fn test_anomaly_stream() {
+        let series = (0..60)
+            .map(|v| 10.0 + ((v % 7) as f64) + 0.1 * ((v * 5 % 3) as f64))
+            .collect::<Vec<f64>>();
+        let mut stream = AnomalyStream::new(28, 7).unwrap();
+        let scores = series[..50]
+            .iter()
+            .filter_map(|&v| stream.push(v).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(50 - 14, scores.len());
+        assert_eq!(14, scores[0].index());
+        assert!(scores.iter().all(|s| s.z_score().abs() < 5.0));
+
+        let expected = 10.0 + (50 % 7) as f64 + 0.1 * ((50 * 5 % 3) as f64);
+        let score = stream.push(expected + 10.0).unwrap().unwrap();
+        assert_eq!(50, score.index());
+        assert!((expected - score.expected()).abs() < 0.5);
+        assert!(score.z_score() > 5.0);
+
+        assert_eq!(
+            AnomalyStream::new(10, 7).unwrap_err(),
+            Error::Series("series has less than two periods".to_string())
+        );
+        let mut params = Stl::params();
+        params.trend_degree(2);
+        assert_eq!(
+            AnomalyStream::with_params(28, 7, params).unwrap_err(),
+            Error::Parameter("trend_degree must be 0 or 1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_forecast() {
+        let series = (0..42)
+            .map(|v| 10.0 + 0.5 * v as f64 + ((v % 7) as f64))
+            .collect::<Vec<f64>>();
+        let result = Stl::fit(&series, 7).unwrap();
+        let cycle = result.seasonal_cycle();
+        let adjusted = series[41] - result.seasonal()[41];
+
+        let naive = result.forecast(8, ForecastMethod::Naive);
+        assert_eq!(8, naive.len());
+        assert_in_delta(adjusted + cycle[0], naive[0]);
+        assert_in_delta(adjusted + cycle[0], naive[7]);
+
+        let adjusted_forecast = vec![adjusted; 8];
+        assert_eq!(naive, result.reseasonalize(&adjusted_forecast));
+
+        let snaive = result.forecast(8, ForecastMethod::SeasonalNaive);
+        assert_in_delta(series[35], snaive[0]);
+        assert_in_delta(series[35], snaive[7]);
+
+        // the drift follows the linear trend of the series
+        let drift = result.forecast(8, ForecastMethod::Drift);
+        for (h, v) in drift.iter().enumerate() {
+            let v0 = 10.0 + 0.5 * (42 + h) as f64 + (((42 + h) % 7) as f64);
+            assert!((v0 - v).abs() < 0.5);
+        }
+
+        // Holt's method tracks the linear trend, while SES stays level
+        let holt = result.forecast(8, ForecastMethod::Holt);
+        for (h, v) in holt.iter().enumerate() {
+            let v0 = 10.0 + 0.5 * (42 + h) as f64 + (((42 + h) % 7) as f64);
+            assert!((v0 - v).abs() < 0.5);
+        }
+        let ses = result.forecast(8, ForecastMethod::Ses);
+        assert_in_delta(ses[0] - cycle[0], ses[7] - cycle[0]);
+        assert!((adjusted - (ses[0] - cycle[0])).abs() < 0.5);
+    }
+
+    #[cfg(feature = "arima")]
+    #[test]
+    fn test_forecast_arima() {
+        // an AR(1) around a linear trend, differenced once
+        let mut noise = 0.0;
+        let series = (0..84)
+            .map(|v| {
+                noise = 0.6 * noise + 0.2 * ((v * 7 % 5) as f64 - 2.0);
+                10.0 + 0.5 * v as f64 + ((v % 7) as f64) + noise
+            })
+            .collect::<Vec<f64>>();
+        let result = Stl::fit(&series, 7).unwrap();
+        for (p, q) in [(1, 0), (0, 1), (1, 1)] {
+            let forecast = result.forecast(7, ForecastMethod::Arima { p, d: 1, q });
+            assert_eq!(7, forecast.len());
+            for (h, v) in forecast.iter().enumerate() {
+                let v0 = 10.0 + 0.5 * (84 + h) as f64 + (((84 + h) % 7) as f64);
+                assert!((v0 - v).abs() < 2.0);
+            }
+        }
+
+        // without differencing, long forecasts revert to the mean
+        let series = (0..84)
+            .map(|v| 10.0 + ((v % 7) as f64) + 0.2 * ((v * 7 % 5) as f64 - 2.0))
+            .collect::<Vec<f64>>();
+        let result = Stl::fit(&series, 7).unwrap();
+        let mean = (0..84)
+            .map(|i| series[i] - result.seasonal()[i])
+            .sum::<f64>()
+            / 84.0;
+        let forecast = result.forecast(70, ForecastMethod::Arima { p: 1, d: 0, q: 0 });
+        let cycle = result.seasonal_cycle();
+        assert!((mean - (forecast[69] - cycle[(84 + 69) % 7])).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_fill_gaps() {
+        let mut series = generate_series();
+        series[10] = f64::NAN;
+        series[11] = f64::NAN;
+        let result = Stl::fit(&series, 7).unwrap();
+
+        let filled = fill_gaps(&series, &result, None);
+        assert_eq!(series[9], filled[9]);
+        assert_in_delta(result.seasonal()[10] + result.trend()[10], filled[10]);
+        assert_in_delta(result.seasonal()[11] + result.trend()[11], filled[11]);
+
+        // resampled noise is one of the observed remainders
+        let noisy = fill_gaps(&series, &result, Some(42));
+        let noise = noisy[10] - filled[10];
+        assert!(result.remainder().iter().any(|r| (r - noise).abs() < 1e-9));
+        assert_eq!(noisy, fill_gaps(&series, &result, Some(42)));
+    }
+
+    #[test]
+    fn test_fixed_trend() {
+        let series = (0..42)
+            .map(|v| 10.0 + 0.5 * v as f64 + ((v % 7) as f64 - 3.0))
+            .collect::<Vec<f64>>();
+        let trend = (0..42).map(|v| 10.0 + 0.5 * v as f64).collect::<Vec<f64>>();
+        let result = Stl::params()
+            .fixed_trend(&trend)
+            .robust(true)
+            .fit(&series, 7)
+            .unwrap();
+        assert_eq!(trend, result.trend());
+        for i in 0..42 {
+            assert_in_delta((i % 7) as f64 - 3.0, result.seasonal()[i]);
+            assert_in_delta(0.0, result.remainder()[i]);
+        }
+
+        assert_eq!(
+            Stl::params()
+                .fixed_trend(&trend[..40])
+                .fit(&series, 7)
+                .unwrap_err(),
+            Error::Parameter("fixed_trend must have one value per observation".to_string())
+        );
+    }
+
+    #[test]
+    fn test_fixed_seasonal() {
+        let cycle = (0..7).map(|p| p as f64 - 3.0).collect::<Vec<f64>>();
+        let series = (0..42)
+            .map(|v| 10.0 + 0.5 * v as f64 + cycle[v % 7])
+            .collect::<Vec<f64>>();
+        let result = Stl::params()
+            .fixed_seasonal(&cycle)
+            .fit(&series, 7)
+            .unwrap();
+        for i in 0..42 {
+            assert_eq!(cycle[i % 7], result.seasonal()[i]);
+            assert_in_delta(10.0 + 0.5 * i as f64, result.trend()[i]);
+        }
+
+        let full = (0..42).map(|v| cycle[v % 7]).collect::<Vec<f64>>();
+        let same = Stl::params().fixed_seasonal(&full).fit(&series, 7).unwrap();
+        assert_eq!(result.trend(), same.trend());
+
+        assert_eq!(
+            Stl::params()
+                .fixed_seasonal(&full[..10])
+                .fit(&series, 7)
+                .unwrap_err(),
+            Error::Parameter(
+                "fixed_seasonal must have one value per phase or per observation".to_string()
+            )
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_bytes() {
+        let series = generate_series();
+        let result = Stl::params()
+            .regressor(
+                "x",
+                &(0..series.len())
+                    .map(|i| (i % 3) as f64)
+                    .collect::<Vec<f64>>(),
+            )
+            .fit(&series, 7)
+            .unwrap();
+        let bytes = result.to_bytes();
+        assert_eq!(b"STLR", &bytes[..4]);
+        let decoded = crate::StlResult::from_bytes(&bytes).unwrap();
+        assert_eq!(result.seasonal(), decoded.seasonal());
+        assert_eq!(result.trend(), decoded.trend());
+        assert_eq!(result.remainder(), decoded.remainder());
+        assert_eq!(result.weights(), decoded.weights());
+        assert_eq!(result.period(), decoded.period());
+        assert_eq!(result.regression(), decoded.regression());
+
+        assert!(crate::StlResult::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(crate::StlResult::from_bytes(b"STLR\x02\x00").is_err());
+
+        let mut result = result;
+        result.seasonal_length = 8;
+        assert_eq!(
+            crate::StlResult::from_bytes(&result.to_bytes()).unwrap_err(),
+            Error::Format("invalid result encoding: seasonal_length must be odd".to_string())
+        );
+    }
+
+    #[test]
+    fn test_cache() {
+        let series = generate_series()
+            .iter()
+            .map(|v| v + 1000.0)
+            .collect::<Vec<f64>>();
+        crate::cache::configure(16, None);
+        let first = Stl::params().robust(true).fit(&series, 7).unwrap();
+        let second = Stl::params().robust(true).fit(&series, 7).unwrap();
+        let other = Stl::params().fit(&series, 7).unwrap();
+        crate::cache::configure(2, None);
+        for period in [3, 4, 5] {
+            Stl::fit(&series, period).unwrap();
+        }
+        assert!(crate::cache::len() <= 2);
+        crate::cache::configure(0, None);
+        assert!(crate::cache::is_empty());
+
+        // a cached result carries the timings of the original fit
+        assert_eq!(first.fit_report(), second.fit_report());
+        assert_eq!(first.seasonal(), second.seasonal());
+        assert_ne!(first.weights(), other.weights());
+    }
+
+    #[test]
+    fn test_strength_summary() {
+        let series = generate_series();
+        let results = vec![Stl::fit(&series, 7).unwrap(), Stl::fit(&series, 5).unwrap()];
+        let strengths = seasonal_strengths(&results);
+        assert_eq!(results[0].seasonal_strength(), strengths[0]);
+        assert_eq!(results[1].seasonal_strength(), strengths[1]);
+        assert_eq!(results[0].trend_strength(), trend_strengths(&results)[0]);
+
+        let summary = strength_summary(&[0.1, 0.5, 0.9, 1.0, f64::NAN], 4);
+        assert_eq!(4, summary.count());
+        assert_in_delta(0.625, summary.mean());
+        assert_elements_in_delta(&[0.1, 0.4, 0.7, 0.925, 1.0], &summary.quantiles());
+        assert_eq!(&[1, 0, 1, 2], summary.histogram());
+        assert_eq!(0, strength_summary(&[], 4).count());
+    }
+
+    #[test]
+    fn test_robust_strength() {
+        let mut series = (0..70)
+            .map(|i| ((i % 7) as f64) + 0.3 * ((i * 3 % 5) as f64))
+            .collect::<Vec<f64>>();
+        series[30] += 500.0;
+        let result = Stl::params().robust(true).fit(&series, 7).unwrap();
+        let sample = result.seasonal_strength();
+        assert_eq!(sample, result.seasonal_strength_with(Variance::Sample));
+        assert!(result.seasonal_strength_with(Variance::Mad) > sample);
+        assert!(result.seasonal_strength_with(Variance::Trimmed(0.05)) > sample);
+    }
+
+    #[test]
+    fn test_strength_method() {
+        let result = Stl::fit(&generate_series(), 7).unwrap();
+        assert_eq!(
+            result.seasonal_strength(),
+            result.seasonal_strength_by(StrengthMethod::VarianceRatio)
+        );
+        assert_eq!(
+            result.seasonal_strength_anova(),
+            result.seasonal_strength_by(StrengthMethod::FTest)
+        );
+        // population variances cancel to the same ratio without missing values
+        assert_in_delta(
+            result.seasonal_strength(),
+            result.seasonal_strength_by(StrengthMethod::Statsmodels),
+        );
+
+        let weak = Stl::fit(&[1.0, 9.0, 2.0, 8.0, 3.0, 7.0, 4.0, 6.0, 5.0, 5.0], 3).unwrap();
+        assert!(weak.seasonal_strength_by(StrengthMethod::Statsmodels) <= weak.seasonal_strength());
+    }
+
+    #[test]
+    fn test_component_algebra() {
+        let last_year = Stl::fit(&generate_series(), 7).unwrap();
+        let this_year = Stl::fit(&generate_series()[..28], 7).unwrap();
+        let observed = generate_series()[2..30].to_vec();
+        let adjusted = subtract_components(&observed, this_year.seasonal()).unwrap();
+        assert_in_delta(observed[3] - this_year.seasonal()[3], adjusted[3]);
+        assert_elements_in_delta(
+            &observed,
+            &add_components(&adjusted, this_year.seasonal()).unwrap(),
+        );
+        let result = subtract_components(&observed, last_year.seasonal());
+        assert_eq!(
+            Err(Error::Series(
+                "components must have the same length, got 28 and 30".to_string()
+            )),
+            result
+        );
+
+        let sum = sum_components(&[
+            last_year.trend(),
+            last_year.seasonal(),
+            last_year.remainder(),
+        ])
+        .unwrap();
+        assert_elements_in_delta(&generate_series(), &sum);
+        assert_eq!(vec![2.0, -4.0], scale_component(&[1.0, -2.0], 2.0));
+    }
+
+    #[test]
+    fn test_resample() {
+        let series = generate_series();
+        let result = Stl::fit(&series, 6).unwrap();
+        let weekly = result.resample(3, AggFn::Sum).unwrap();
+        assert_eq!(2, weekly.period());
+        assert_eq!(10, weekly.seasonal().len());
+        assert_in_delta(result.trend()[3..6].iter().sum::<f64>(), weekly.trend()[1]);
+        for (i, chunk) in series.chunks_exact(3).enumerate() {
+            let total = weekly.seasonal()[i] + weekly.trend()[i] + weekly.remainder()[i];
+            assert_in_delta(chunk.iter().sum::<f64>(), total);
+        }
+
+        let mean = result.resample(2, AggFn::Mean).unwrap();
+        assert_eq!(15, mean.seasonal().len());
+        assert_eq!(3, mean.period());
+        assert_in_delta(
+            series[..2].iter().sum::<f64>() / 2.0,
+            mean.seasonal()[0] + mean.trend()[0] + mean.remainder()[0],
+        );
+
+        // the trend window shrinks with the series
+        assert_eq!(13, result.trend_length);
+        assert_eq!(5, weekly.trend_length);
+        assert_eq!(10, weekly.trend_slope().len());
+        // the period must stay whole and seasonal
+        for factor in [0, 4, 6, 30] {
+            assert_eq!(
+                result.resample(factor, AggFn::Sum).unwrap_err(),
+                Error::Parameter(
+                    "factor must divide the period and leave a period of at least 2".to_string()
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_residual_seasonality() {
+        // the seasonal amplitude grows over time
+        let noise = SynthParams::new(120)
+            .seed(3)
+            .noise(Noise::Gaussian { sd: 0.5 })
+            .generate();
+        let series = noise
+            .noise()
+            .iter()
+            .enumerate()
+            .map(|(i, e)| {
+                let amplitude = 1.0 + i as f64 / 30.0;
+                amplitude * (2.0 * std::f64::consts::PI * i as f64 / 12.0).sin() + e
+            })
+            .collect::<Vec<f64>>();
+        let stiff = Stl::params()
+            .seasonal_length(10001)
+            .check_residual_seasonality(true)
+            .fit(&series, 12)
+            .unwrap();
+        let check = stiff.residual_seasonality().unwrap();
+        assert_eq!(&[12, 6, 4, 3, 2], check.lags());
+        assert!(check.detected());
+
+        let flexible = Stl::params()
+            .seasonal_length(7)
+            .seasonal_degree(1)
+            .check_residual_seasonality(true)
+            .fit(&series, 12)
+            .unwrap();
+        assert!(!flexible.residual_seasonality().unwrap().detected());
+        assert!(
+            Stl::fit(&series, 12)
+                .unwrap()
+                .residual_seasonality()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_leakage() {
+        let noise = SynthParams::new(120)
+            .seed(5)
+            .noise(Noise::Gaussian { sd: 0.5 })
+            .generate();
+        let trend = (0..120).map(|i| i as f64 / 10.0).collect::<Vec<f64>>();
+        let series = noise
+            .noise()
+            .iter()
+            .zip(&trend)
+            .enumerate()
+            .map(|(i, (e, t))| t + (2.0 * std::f64::consts::PI * i as f64 / 12.0).sin() + e)
+            .collect::<Vec<f64>>();
+
+        let check = Stl::fit(&series, 12).unwrap().check_leakage();
+        assert_eq!(&["trend", "seasonal"], check.labels());
+        assert_eq!(&[0, 12], check.lags());
+        assert!(!check.detected());
+
+        // half of the trend is left in the remainder
+        let half = trend.iter().map(|t| t / 2.0).collect::<Vec<f64>>();
+        let leaky = Stl::params()
+            .fixed_trend(&half)
+            .fit(&series, 12)
+            .unwrap()
+            .check_leakage();
+        assert_eq!(vec!["trend"], leaky.leaking());
+        assert!(leaky.correlations()[0][0] > 0.9);
+    }
+
+    #[test]
+    fn test_classical_decompose() {
+        let series = generate_series();
+        let result = classical_decompose(&series, 7, DecompositionModel::Additive).unwrap();
+        assert_eq!(7, result.period());
+        assert_eq!(result.seasonal()[0], result.seasonal()[7]);
+        assert_in_delta(0.0, result.seasonal()[..7].iter().sum::<f64>());
+        for (i, v) in series.iter().enumerate() {
+            let total = result.seasonal()[i] + result.trend()[i] + result.remainder()[i];
+            assert_in_delta(*v, total);
+        }
+
+        let series = series.iter().map(|v| v + 1.0).collect::<Vec<f64>>();
+        let result = classical_decompose(&series, 7, DecompositionModel::Multiplicative).unwrap();
+        assert_in_delta(7.0, result.seasonal()[..7].iter().sum::<f64>());
+        for (i, v) in series.iter().enumerate() {
+            let total = result.seasonal()[i] * result.trend()[i] * result.remainder()[i];
+            assert_in_delta(*v, total);
+        }
+
+        let mut with_zero = series.clone();
+        with_zero[3] = 0.0;
+        assert!(classical_decompose(&with_zero, 7, DecompositionModel::Multiplicative).is_err());
+        assert!(classical_decompose(&series[..13], 7, DecompositionModel::Additive).is_err());
+    }
+
+    #[test]
+    fn test_x11() {
+        let seasonal = |i: usize| 3.0 * (2.0 * std::f64::consts::PI * i as f64 / 12.0).sin();
+        let mut series = (0..120)
+            .map(|i| 10.0 + 0.1 * i as f64 + seasonal(i) + 0.2 * (i as f64 * 1.7).sin())
+            .collect::<Vec<f64>>();
+        series[50] += 20.0;
+
+        let result = X11::fit(&series, 12).unwrap();
+        assert_eq!(0.0, result.weights()[50]);
+        for (i, v) in series.iter().enumerate().take(96).skip(24) {
+            assert!((result.seasonal()[i] - seasonal(i)).abs() < 0.1);
+            assert!((result.trend()[i] - 10.0 - 0.1 * i as f64).abs() < 0.2);
+            let total = result.seasonal()[i] + result.trend()[i] + result.remainder()[i];
+            assert_in_delta(*v, total);
+        }
+
+        let result = X11::params()
+            .model(DecompositionModel::Multiplicative)
+            .seasonal_filter(SeasonalFilter::S3x9)
+            .fit(&series, 12)
+            .unwrap();
+        for (i, v) in series.iter().enumerate() {
+            let total = result.seasonal()[i] * result.trend()[i] * result.remainder()[i];
+            assert_in_delta(*v, total);
+        }
+
+        assert!(X11::fit(&series[..30], 12).is_err());
+        assert!(X11::params().henderson_length(12).fit(&series, 12).is_err());
+    }
+
+    #[test]
+    fn test_twicing() {
+        let trend = |i: usize| 20.0 * (-((i as f64 - 60.0) / 8.0).powi(2)).exp();
+        let series = (0..120)
+            .map(|i| trend(i) + (2.0 * std::f64::consts::PI * i as f64 / 12.0).sin())
+            .collect::<Vec<f64>>();
+        let error = |result: &StlResult| {
+            (0..120)
+                .map(|i| (result.trend()[i] - trend(i)).abs())
+                .fold(0.0, f64::max)
+        };
+
+        let once = Stl::params().trend_length(25).fit(&series, 12).unwrap();
+        let twice = Stl::params()
+            .trend_length(25)
+            .twicing(true)
+            .fit(&series, 12)
+            .unwrap();
+        assert!(error(&twice) < 0.6 * error(&once));
+    }
+
+    #[test]
+    fn test_winsorize() {
+        let mut series = generate_series();
+        series[10] = 100.0;
+        let plain = Stl::fit(&series, 7).unwrap();
+        let result = Stl::params().winsorize(0.05).fit(&series, 7).unwrap();
+        assert!(result.clipped().contains(&10));
+        assert!(result.trend()[10] < plain.trend()[10]);
+        for (i, v) in series.iter().enumerate() {
+            let total = result.seasonal()[i] + result.trend()[i] + result.remainder()[i];
+            assert_in_delta(*v, total);
+        }
+        assert!(result.remainder()[10] > 50.0);
+
+        let result = Stl::params().winsorize(0.0).fit(&series, 7).unwrap();
+        assert!(result.clipped().is_empty());
+        assert_elements_in_delta(plain.trend(), result.trend());
+
+        let result = Stl::params().winsorize(0.5).fit(&series, 7);
+        assert!(matches!(result, Err(Error::Parameter(_))));
+    }
+
+    #[test]
+    fn test_jump_fraction() {
+        // the default trend length for a period of 7 is 15
+        let fraction = Stl::params()
+            .seasonal_jump_fraction(0.3)
+            .trend_jump_fraction(0.2)
+            .low_pass_jump_fraction(0.01)
+            .fit(&generate_series(), 7)
+            .unwrap();
+        let points = Stl::params()
+            .seasonal_jump(2)
+            .trend_jump(3)
+            .low_pass_jump(1)
+            .fit(&generate_series(), 7)
+            .unwrap();
+        assert_eq!(points.seasonal(), fraction.seasonal());
+        assert_eq!(points.trend(), fraction.trend());
+        assert_eq!(
+            points.fit_report().loess_evaluations(),
+            fraction.fit_report().loess_evaluations()
+        );
+
+        let result = Stl::params()
+            .trend_jump_fraction(0.0)
+            .fit(&generate_series(), 7);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::Parameter(
+                "trend_jump fraction must be greater than 0 and at most 1".to_string()
+            )
+        );
+        let result = Stl::params()
+            .seasonal_jump_fraction(1.5)
+            .fit(&generate_series(), 7);
+        assert!(matches!(result, Err(Error::Parameter(_))));
+    }
+
+    #[test]
+    fn test_detrend() {
+        let series = generate_series()
+            .iter()
+            .enumerate()
+            .map(|(i, v)| v + 3.0 * i as f64)
+            .collect::<Vec<f64>>();
+        let result = Stl::params().detrend(true).fit(&series, 7).unwrap();
+        for (i, v) in series.iter().enumerate() {
+            let total = result.seasonal()[i] + result.trend()[i] + result.remainder()[i];
+            assert_in_delta(*v, total);
+        }
+
+        // adding a line only changes the line removed
+        let base = Stl::params()
+            .detrend(true)
+            .fit(&generate_series(), 7)
+            .unwrap();
+        assert_elements_in_delta(base.seasonal(), result.seasonal());
+        for i in 0..series.len() {
+            assert_in_delta(base.trend()[i] + 3.0 * i as f64, result.trend()[i]);
+        }
+    }
+
+    #[test]
+    fn test_shared_params() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<StlParams>();
+        assert_send_sync::<crate::MstlParams>();
+        assert_send_sync::<StlResult>();
+        assert_send_sync::<crate::MstlResult>();
+
+        let series = generate_series();
+        let mut params = Stl::params();
+        params.seasonal_length(7).robust(true);
+        let expected = params.fit(&series, 7).unwrap();
+        std::thread::scope(|scope| {
+            let handles = (0..4)
+                .map(|_| scope.spawn(|| params.fit(&series, 7).unwrap()))
+                .collect::<Vec<_>>();
+            for handle in handles {
+                let result = handle.join().unwrap();
+                assert_elements_in_delta(expected.trend(), result.trend());
+            }
+        });
+    }
+
+    #[test]
+    fn test_seasonal_penalty() {
+        let series = generate_series();
+        // the spread of each phase across cycles
+        let variation = |result: &StlResult| {
+            (0..7)
+                .map(|j| {
+                    let phase = result.seasonal().iter().skip(j).step_by(7);
+                    let max = phase.clone().fold(f64::MIN, |a, &b| a.max(b));
+                    let min = phase.fold(f64::MAX, |a, &b| a.min(b));
+                    max - min
+                })
+                .sum::<f64>()
+        };
+
+        let plain = Stl::fit(&series, 7).unwrap();
+        let result = Stl::params().seasonal_penalty(0.0).fit(&series, 7).unwrap();
+        assert_elements_in_delta(plain.seasonal(), result.seasonal());
+
+        let penalized = Stl::params().seasonal_penalty(4.0).fit(&series, 7).unwrap();
+        let periodic = Stl::params().seasonal_penalty(1e9).fit(&series, 7).unwrap();
+        assert!(variation(&penalized) < variation(&plain));
+        assert!(variation(&periodic) < 1e-6);
+
+        let result = Stl::params().seasonal_penalty(-1.0).fit(&series, 7);
+        assert!(matches!(result, Err(Error::Parameter(_))));
+    }
+
+    #[test]
+    fn test_zero_sum_seasonal() {
+        let series = generate_series();
+        let plain = Stl::fit(&series, 7).unwrap();
+        let result = Stl::params()
+            .zero_sum_seasonal(true)
+            .fit(&series, 7)
+            .unwrap();
+        for cycle in result.seasonal().chunks_exact(7) {
+            assert_in_delta(0.0, cycle.iter().sum::<f64>());
+        }
+        assert_elements_in_delta(plain.remainder(), result.remainder());
+        for i in 0..series.len() {
+            assert_in_delta(
+                plain.seasonal()[i] + plain.trend()[i],
+                result.seasonal()[i] + result.trend()[i],
+            );
+        }
+    }
+
+    #[test]
+    fn test_monotone_trend() {
+        let series = generate_series()
+            .iter()
+            .enumerate()
+            .map(|(i, v)| v + 0.2 * i as f64)
+            .collect::<Vec<f64>>();
+        let result = Stl::params()
+            .monotone_trend(Monotone::Increasing)
+            .fit(&series, 7)
+            .unwrap();
+        assert!(result.trend().windows(2).all(|w| w[0] <= w[1]));
+        for (i, v) in series.iter().enumerate() {
+            let total = result.seasonal()[i] + result.trend()[i] + result.remainder()[i];
+            assert_in_delta(*v, total);
+        }
+
+        let result = Stl::params()
+            .monotone_trend(Monotone::Decreasing)
+            .fit(&series, 7)
+            .unwrap();
+        assert!(result.trend().windows(2).all(|w| w[0] >= w[1]));
+    }
+
+    #[test]
+    fn test_non_negative() {
+        let series = [0.0, 0.0, 5.0, 0.0, 0.0, 0.0, 1.0]
+            .iter()
+            .cycle()
+            .take(42)
+            .enumerate()
+            .map(|(i, v)| if i < 14 { *v } else { 0.0 })
+            .collect::<Vec<f64>>();
+        let plain = Stl::fit(&series, 7).unwrap();
+        assert!(plain.trend().iter().any(|&t| t < 0.0));
+
+        let result = Stl::params()
+            .non_negative(NonNegative::Trend)
+            .fit(&series, 7)
+            .unwrap();
+        assert!(result.trend().iter().all(|&t| t >= 0.0));
+
+        let result = Stl::params()
+            .non_negative(NonNegative::Fitted)
+            .fit(&series, 7)
+            .unwrap();
+        for (i, v) in series.iter().enumerate() {
+            assert!(result.trend()[i] >= 0.0);
+            assert!(result.seasonal()[i] + result.trend()[i] >= -1e-12);
+            let total = result.seasonal()[i] + result.trend()[i] + result.remainder()[i];
+            assert_in_delta(*v, total);
+        }
+    }
+
+    #[test]
+    fn test_fit_counts() {
+        let factors = [0.2, 0.5, 1.0, 2.0, 4.0, 1.0, 0.3];
+        let series = (0..70)
+            .map(|i| (3.0 * factors[i % 7] + 0.3 * (i % 3) as f64).floor())
+            .collect::<Vec<f64>>();
+        assert!(series.contains(&0.0));
+
+        for model in [CountModel::Poisson, CountModel::NegativeBinomial(2.0)] {
+            let result = Stl::params().fit_counts(&series, 7, model).unwrap();
+            assert!(result.trend().iter().all(|t| t.is_finite()));
+            let cycle = &result.seasonal()[28..35];
+            let max = (0..7)
+                .max_by(|&a, &b| cycle[a].total_cmp(&cycle[b]))
+                .unwrap();
+            let min = (0..7)
+                .min_by(|&a, &b| cycle[a].total_cmp(&cycle[b]))
+                .unwrap();
+            assert_eq!(4, max);
+            assert_eq!(0, min);
+            // the fitted means are close to the counts on average
+            let fitted = (0..70)
+                .map(|i| (result.seasonal()[i] + result.trend()[i]).exp())
+                .sum::<f64>();
+            assert!((fitted / series.iter().sum::<f64>() - 1.0).abs() < 0.1);
+        }
+
+        let mut negative = series.clone();
+        negative[3] = -1.0;
+        let result = Stl::params().fit_counts(&negative, 7, CountModel::Poisson);
+        assert!(matches!(result, Err(Error::Series(_))));
+    }
+
+    #[test]
+    fn test_quantile() {
+        let series = generate_series();
+        let fitted = |result: &StlResult| {
+            (0..series.len())
+                .map(|i| result.seasonal()[i] + result.trend()[i])
+                .collect::<Vec<f64>>()
+        };
+        // the fraction of observations below the fitted values
+        let below = |fitted: &[f64]| {
+            series.iter().zip(fitted).filter(|(y, f)| y < f).count() as f64 / series.len() as f64
+        };
+
+        let median = fitted(&Stl::params().quantile(0.5).fit(&series, 7).unwrap());
+        let high = fitted(&Stl::params().quantile(0.9).fit(&series, 7).unwrap());
+        assert!((below(&median) - 0.5).abs() < 0.15);
+        assert!(below(&high) > 0.7);
+        assert!(high.iter().sum::<f64>() > median.iter().sum::<f64>());
+
+        let result = Stl::params().quantile(1.0).fit(&series, 7);
+        assert!(matches!(result, Err(Error::Parameter(_))));
+    }
+
+    #[test]
+    fn test_remainder_scale() {
+        // noise that is ten times larger in the second half of each cycle
+        let series = (0..240)
+            .map(|i| {
+                let scale = if i % 12 < 6 { 0.1 } else { 1.0 };
+                let noise = ((i * 37 % 11) as f64 - 5.0) / 5.0;
+                (i % 12) as f64 + noise * scale
+            })
+            .collect::<Vec<f64>>();
+        let result = Stl::params().seasonal_length(7).fit(&series, 12).unwrap();
+        let scale = result.remainder_scale();
+        assert_eq!(scale.len(), series.len());
+        assert!(scale.iter().all(|s| *s >= 0.0));
+
+        let mean = |phases: std::ops::Range<usize>| {
+            let values = (0..240)
+                .filter(|i| phases.contains(&(i % 12)))
+                .map(|i| scale[i])
+                .collect::<Vec<f64>>();
+            values.iter().sum::<f64>() / values.len() as f64
+        };
+        assert!(mean(6..12) > 3.0 * mean(0..6));
+    }
+
+    #[test]
+    fn test_fit_joint() {
+        // three sensors reading the same trend at different levels, with
+        // their own seasonal patterns and noise
+        let trend = (0..120)
+            .map(|i| (i as f64 / 20.0).sin() * 5.0)
+            .collect::<Vec<f64>>();
+        let series = (0..3)
+            .map(|k| {
+                (0..120)
+                    .map(|i| {
+                        let seasonal = ((i % 6) as f64 - 2.5) * (k + 1) as f64;
+                        let noise = (((i * 37 + k * 11) % 13) as f64 - 6.0) / 6.0;
+                        10.0 * k as f64 + trend[i] + seasonal + noise
+                    })
+                    .collect::<Vec<f64>>()
+            })
+            .collect::<Vec<Vec<f64>>>();
+
+        let results = Stl::params().fit_joint(&series, 6, Shared::Trend).unwrap();
+        assert_eq!(results.len(), 3);
+        for (k, result) in results.iter().enumerate() {
+            // the trends differ only by the level of each series
+            for (i, y) in series[k].iter().enumerate() {
+                let shift = result.trend()[i] - results[0].trend()[i];
+                assert!((shift - 10.0 * k as f64).abs() < 0.5);
+                let sum = result.seasonal()[i] + result.trend()[i] + result.remainder()[i];
+                assert!((sum - y).abs() < 1e-9);
+            }
+        }
+
+        let results = Stl::params()
+            .fit_joint(&series[..1], 6, Shared::Seasonal)
+            .unwrap();
+        let single = Stl::params().fit(&series[0], 6).unwrap();
+        for (a, b) in single.seasonal().iter().zip(results[0].seasonal()) {
+            assert!((a - b).abs() < 0.5);
+        }
+
+        let result = Stl::params().fit_joint(&[&series[0][..60], &series[1][..]], 6, Shared::Trend);
+        assert!(matches!(result, Err(Error::Series(_))));
+    }
+
+    #[test]
+    fn test_fit_pooled() {
+        // short series of stores with the same weekly pattern, different
+        // levels and trends, and noise
+        let pattern = [-3.0, -1.0, 0.0, 1.0, 2.0, 4.0, -3.0];
+        let series = (0..20)
+            .map(|k| {
+                (0..21)
+                    .map(|i| {
+                        let noise = (((i * 37 + k * 11) % 13) as f64 - 6.0) / 3.0;
+                        k as f64 + 0.1 * (k as f64 - 10.0) * i as f64 + pattern[i % 7] + noise
+                    })
+                    .collect::<Vec<f64>>()
+            })
+            .collect::<Vec<Vec<f64>>>();
+
+        let pooled = Stl::params().pooled_seasonal(&series, 7).unwrap();
+        assert_eq!(pooled.len(), 7);
+        assert!(pooled.iter().sum::<f64>().abs() < 1e-9);
+        let error = |cycle: &[f64]| {
+            cycle
+                .iter()
+                .zip(&pattern)
+                .map(|(a, b)| (a - b).abs())
+                .fold(0.0, f64::max)
+        };
+        let single = Stl::params().fit(&series[0], 7).unwrap();
+        assert!(error(&pooled) < error(&single.seasonal()[..7]));
+
+        let results = Stl::params().fit_pooled(&series, 7).unwrap();
+        assert_eq!(results.len(), 20);
+        for result in &results {
+            assert_elements_in_delta(&pooled, &result.seasonal()[..7]);
+        }
+
+        let empty: &[Vec<f64>] = &[];
+        assert!(Stl::params().fit_pooled(empty, 7).is_err());
+    }
+
+    #[test]
+    fn test_time_series() {
+        let series = TimeSeries::new(generate_series());
+        assert_eq!(series.len(), 30);
+        let result = series.stl(7).unwrap();
+        assert_elements_in_delta(
+            Stl::fit(&generate_series(), 7).unwrap().seasonal(),
+            result.seasonal(),
+        );
+        assert_eq!(series.mstl(&[6, 10]).unwrap().periods(), &[6, 10]);
+
+        // hourly data with daily and weekly cycles and a trend
+        let hourly = (0..24 * 7 * 4)
+            .map(|i| {
+                let t = i as f64;
+                let daily = (2.0 * std::f64::consts::PI * t / 24.0).sin();
+                let weekly = if (i / 24) % 7 >= 5 { 3.0 } else { 0.0 };
+                0.01 * t + daily + weekly
+            })
+            .collect::<Vec<f64>>();
+        assert_eq!(TimeSeries::new(hourly).detect_periods(), vec![24, 168]);
+        assert!(TimeSeries::new(vec![1.0; 30]).detect_periods().is_empty());
+
+        // a missing value, a gap in the timestamps, and an unsorted duplicate
+        let mut series = TimeSeries::with_timestamps(
+            vec![1.0, f64::NAN, 3.0, 6.0, 5.0, 7.0],
+            vec![0, 60, 120, 240, 180, 240],
+        )
+        .unwrap();
+        assert_eq!(series.sampling_interval(), Some(60));
+        let cleaned = series.clean();
+        assert_eq!(cleaned.timestamps(), Some(&[0, 60, 120, 180, 240][..]));
+        assert_elements_in_delta(&[1.0, 2.0, 3.0, 5.0, 6.5], cleaned.values());
+        let cleaned = series.interval(120).clean();
+        assert_eq!(cleaned.timestamps(), Some(&[0, 120, 240][..]));
+
+        let result = TimeSeries::with_timestamps(vec![1.0, 2.0], vec![0]);
+        assert!(matches!(result, Err(Error::Series(_))));
+    }
+
+    #[test]
+    fn test_fit_report() {
+        let result = Stl::params()
+            .robust(true)
+            .fit(&generate_series(), 7)
+            .unwrap();
+        let report = result.fit_report();
+        assert_eq!(15, report.robustness_iterations());
+        assert_eq!(32, report.inner_iterations());
+        assert!(report.loess_evaluations() > 0);
+
+        let result = Stl::fit(&generate_series(), 7).unwrap();
+        let report = result.fit_report();
+        assert_eq!(0, report.robustness_iterations());
+        assert_eq!(5, report.inner_iterations());
+
+        // skipping points trades loess evaluations for interpolation
+        let dense = Stl::params()
+            .trend_jump(1)
+            .fit(&generate_series(), 7)
+            .unwrap();
+        let sparse = Stl::params()
+            .trend_jump(2)
+            .fit(&generate_series(), 7)
+            .unwrap();
+        assert!(sparse.fit_report().loess_evaluations() < dense.fit_report().loess_evaluations());
+        assert!(
+            sparse.fit_report().interpolated_points() > dense.fit_report().interpolated_points()
+        );
+
+        // a fit nested in a smoother keeps a report of its own
+        #[derive(Debug)]
+        struct Nested;
+
+        impl Smoother for Nested {
+            fn smooth(&self, y: &[f64], _: Option<&[f64]>, _: usize, fitted: &mut [f64]) {
+                fitted.copy_from_slice(Stl::fit(y, 2).unwrap().trend());
+            }
+        }
+
+        let result = Stl::params()
+            .robust(true)
+            .trend_smoother(Nested)
+            .fit(&generate_series(), 7)
+            .unwrap();
+        let report = result.fit_report();
+        assert_eq!(15, report.robustness_iterations());
+        assert_eq!(32, report.inner_iterations());
+    }
+
+    #[test]
+    fn test_warm_start() {
+        let series = (0..84)
+            .map(|v| 10.0 + 0.2 * v as f64 + ((v % 7) as f64) + 0.1 * ((v * 5 % 3) as f64))
+            .collect::<Vec<f64>>();
+        let previous = Stl::fit(&series[..77], 7).unwrap();
+        let cold = Stl::fit(&series, 7).unwrap();
+        let warm = Stl::params().warm_start(&previous).fit(&series, 7).unwrap();
+        assert_eq!(1, warm.fit_report().inner_iterations());
+        assert!(warm.fit_report().loess_evaluations() < cold.fit_report().loess_evaluations());
+        for i in 0..84 {
+            assert!((cold.trend()[i] - warm.trend()[i]).abs() < 0.2);
+            assert!((cold.seasonal()[i] - warm.seasonal()[i]).abs() < 0.2);
+        }
+    }
+
+    #[test]
+    fn test_seasonal_harmonics() {
+        let series = (0..48)
+            .map(|v| {
+                let w = 2.0 * std::f64::consts::PI * v as f64 / 12.0;
+                10.0 + 3.0 * (w - 0.5).cos() + (2.0 * w).cos()
+            })
+            .collect::<Vec<f64>>();
+        let result = Stl::fit(&series, 12).unwrap();
+        let harmonics = result.seasonal_harmonics(10);
+        assert_eq!(6, harmonics.len());
+        assert_eq!(1, harmonics[0].order());
+        assert!((harmonics[0].amplitude() - 3.0).abs() < 0.1);
+        assert!((harmonics[0].phase() - 0.5).abs() < 0.05);
+        assert!((harmonics[1].amplitude() - 1.0).abs() < 0.1);
+        assert!(harmonics[2..].iter().all(|h| h.amplitude() < 0.1));
+    }
+
+    #[test]
+    fn test_partial_cycle() {
+        let series = &generate_series()[..25];
+        let result = Stl::fit(series, 7).unwrap();
+        assert_eq!(4, result.partial_cycle_len());
+        assert_eq!(25, result.seasonal().len());
+
+        let result = Stl::params()
+            .partial_cycle(PartialCycle::Truncate)
+            .fit(series, 7)
+            .unwrap();
+        assert_eq!(4, result.partial_cycle_len());
+        assert_eq!(21, result.seasonal().len());
+
+        let result = Stl::params()
+            .partial_cycle(PartialCycle::PadWithTrend)
+            .fit(series, 7)
+            .unwrap();
+        assert_eq!(25, result.seasonal().len());
+        for (i, v) in series.iter().enumerate() {
+            assert_in_delta(
+                *v,
+                result.seasonal()[i] + result.trend()[i] + result.remainder()[i],
+            );
+        }
+
+        // inputs with a value per observation only cover the series
+        let trend = (0..25).map(|i| 3.0 + 0.1 * i as f64).collect::<Vec<f64>>();
+        let result = Stl::params()
+            .partial_cycle(PartialCycle::PadWithTrend)
+            .fixed_trend(&trend)
+            .fit(series, 7)
+            .unwrap();
+        assert_elements_in_delta(&trend, result.trend());
+        assert_eq!(25, result.seasonal().len());
+
+        let regressor = (0..25).map(|i| (i % 2) as f64).collect::<Vec<f64>>();
+        let result = Stl::params()
+            .partial_cycle(PartialCycle::PadWithTrend)
+            .regressor("odd", &regressor)
+            .fit(series, 7)
+            .unwrap();
+        assert_eq!(25, result.seasonal().len());
+        assert_eq!(25, result.regression().unwrap().effect().len());
+        for (i, v) in series.iter().enumerate() {
+            assert_in_delta(
+                *v,
+                result.seasonal()[i]
+                    + result.trend()[i]
+                    + result.remainder()[i]
+                    + result.regression().unwrap().effect()[i],
+            );
+        }
+
+        let result = Stl::params()
+            .partial_cycle(PartialCycle::Strict)
+            .fit(series, 7);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::Series("series length must be a multiple of the period".to_string())
+        );
+        let result = Stl::params()
+            .partial_cycle(PartialCycle::Strict)
+            .fit(&series[..21], 7)
+            .unwrap();
+        assert_eq!(0, result.partial_cycle_len());
+    }
+
+    #[test]
+    fn test_sliding() {
+        let series = generate_series();
+        let mut sliding = SlidingStl::new(21, 7).unwrap();
+        for v in &series[..13] {
+            sliding.push(*v);
+        }
+        assert!(!sliding.is_full());
+        assert_eq!(
+            sliding.current().unwrap_err(),
+            Error::Series("series has less than two periods".to_string())
+        );
+
+        for v in &series[13..] {
+            sliding.push(*v);
+        }
+        assert_eq!(21, sliding.len());
+        assert_eq!(2, sliding.start_phase());
+        let expected = Stl::fit(&series[9..], 7).unwrap();
+        let result = sliding.current().unwrap();
+        assert_elements_in_delta(expected.seasonal(), result.seasonal());
+        assert_elements_in_delta(expected.trend(), result.trend());
+
+        // later fits start from the previous trend
+        sliding.push(series[2]);
+        let result = sliding.current().unwrap();
+        assert_eq!(1, result.fit_report().inner_iterations());
+        let mut expected = series[10..].to_vec();
+        expected.push(series[2]);
+        let expected = Stl::fit(&expected, 7).unwrap();
+        for (e, r) in expected.trend().iter().zip(result.trend()) {
+            assert!((e - r).abs() < 0.2);
+        }
+
+        assert_eq!(
+            SlidingStl::new(13, 7).unwrap_err(),
+            Error::Parameter("window_len must be at least two periods".to_string())
+        );
+        assert_eq!(
+            SlidingStl::new(10, 1).unwrap_err(),
+            Error::Parameter("period must be at least 2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_warnings() {
+        assert!(Stl::params().warnings(7).is_empty());
+
+        let warnings = Stl::params()
+            .seasonal_length(8)
+            .trend_length(5)
+            .seasonal_jump(9)
+            .warnings(7);
+        assert_eq!(
+            vec![
+                "seasonal_length is even and will be increased to 9",
+                "trend_length (5) is smaller than the period (7), so the trend may absorb seasonality",
+                "seasonal_jump (9) is larger than seasonal_length (8)",
+            ],
+            warnings
+        );
+    }
+
+    #[test]
+    fn test_error_codes() {
+        let err = Stl::fit(&generate_series(), 16).unwrap_err();
+        assert_eq!(2, err.code());
+        assert!(err.is_recoverable());
+        assert_eq!("series has less than two periods", err.message());
+
+        let err = Stl::params()
+            .trend_degree(2)
+            .fit(&generate_series(), 7)
+            .unwrap_err();
+        assert_eq!(1, err.code());
+        assert!(!err.is_recoverable());
+    }
+
+    #[test]
+    fn test_check_settings() {
+        let series = generate_series();
+        let results = [
+            Stl::fit(&series, 7).unwrap(),
+            X11::fit(&series, 7).unwrap(),
+            classical_decompose(&series, 6, DecompositionModel::Additive).unwrap(),
+        ];
+        for result in &results {
+            assert_eq!(Ok(()), result.check_settings());
+        }
+
+        let mut result = Stl::fit(&series, 7).unwrap();
+        result.trend_length = 4;
+        assert_eq!(
+            result.check_settings().unwrap_err(),
+            Error::Parameter("trend_length must be odd".to_string())
+        );
+        result.trend_length = 5;
+        result.seasonal_degree = 2;
+        assert_eq!(
+            result.check_settings().unwrap_err(),
+            Error::Parameter("seasonal_degree must be 0 or 1".to_string())
+        );
+        result.seasonal_degree = 0;
+        result.partial_cycle = 7;
+        assert_eq!(
+            result.check_settings().unwrap_err(),
+            Error::Parameter("partial_cycle must be less than the period".to_string())
+        );
+    }
+
+    #[test]
+    fn test_profiles() {
+        let mut params = Stl::params();
+        params.robust(true).seasonal_length(9);
+        crate::profiles::register("test-robust", params);
+        assert!(crate::profiles::names().contains(&"test-robust".to_string()));
+
+        let expected = Stl::params()
+            .robust(true)
+            .seasonal_length(9)
+            .fit(&generate_series(), 7)
+            .unwrap();
+        let result = StlParams::from_profile("test-robust")
+            .unwrap()
+            .fit(&generate_series(), 7)
+            .unwrap();
+        assert_eq!(expected.seasonal(), result.seasonal());
+
+        assert!(crate::profiles::unregister("test-robust").is_some());
+        assert_eq!(
+            StlParams::from_profile("test-robust").unwrap_err(),
+            Error::Parameter("no profile named test-robust".to_string())
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(Stl::params().validate(30, 7).is_empty());
+
+        let errors = Stl::params()
+            .low_pass_length(8)
+            .trend_degree(2)
+            .validate(10, 7);
+        assert_eq!(
+            vec![
+                Error::Series("series has less than two periods".to_string()),
+                Error::Parameter("trend_degree must be 0 or 1".to_string()),
+                Error::Parameter("low_pass_degree must be 0 or 1".to_string()),
+                Error::Parameter("low_pass_length must be odd".to_string()),
+            ],
+            errors
+        );
+
+        assert_eq!(
+            vec![Error::Parameter(
+                "winsorize fraction must be at least 0 and less than 0.5".to_string()
+            )],
+            Stl::params().winsorize(0.5).validate(30, 7)
+        );
+    }
+
+    #[test]
+    fn test_conversions() {
+        let result = Stl::fit(&generate_series(), 7).unwrap();
+        let components = result.components();
+        assert_eq!(result.trend(), components.trend);
+
+        let rows = result.rows().collect::<Vec<_>>();
+        assert_eq!(30, rows.len());
+        assert_in_delta(result.seasonal()[3], rows[3].seasonal);
+        assert_in_delta(result.weights()[3], rows[3].weight);
+
+        let expected = result.remainder().to_vec();
+        let (seasonal, trend, remainder): (Vec<f64>, Vec<f64>, Vec<f64>) = result.into();
+        assert_eq!(30, seasonal.len());
+        assert_eq!(30, trend.len());
+        assert_eq!(expected, remainder);
+    }
+
+    #[test]
+    fn test_trading_day() {
+        let beta = [0.5, 0.3, 0.2, 0.1, 0.4, -0.8];
+        // a known effect on top of seasonality, trend, and noise
+        let seasonal = (0..120).map(|t| ((t % 12) as f64 - 5.5).abs());
+        let trend = (0..120).map(|t| 10.0 + 0.05 * t as f64);
+        let noise = (0..120).map(|t| 0.05 * ((t * 7 % 11) as f64 - 5.0) / 5.0);
+        let base = seasonal
+            .zip(trend)
+            .zip(noise)
+            .map(|((s, t), e)| s + t + e)
+            .collect::<Vec<f64>>();
+        let mut days = Vec::new();
+        for t in 0..120 {
+            // weekday counts of each month from 2010-01, Monday first
+            let (year, month) = (2010 + t / 12, t % 12 + 1);
+            let len = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31][month - 1]
+                + usize::from(month == 2 && year % 4 == 0);
+            let offset = (0..t)
+                .map(|u| {
+                    let (y, m) = (2010 + u / 12, u % 12 + 1);
+                    [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31][m - 1]
+                        + usize::from(m == 2 && y % 4 == 0)
+                })
+                .sum::<usize>();
+            // 2010-01-01 was a Friday
+            let mut counts = [0.0; 7];
+            for d in 0..len {
+                counts[(offset + d + 4) % 7] += 1.0;
+            }
+            days.push(counts);
+        }
+        let series = base
+            .iter()
+            .zip(&days)
+            .map(|(b, c)| b + (0..6).map(|d| beta[d] * (c[d] - c[6])).sum::<f64>())
+            .collect::<Vec<f64>>();
+
+        let result = Stl::params().trading_day(2010, 1).fit(&series, 12).unwrap();
+        let regression = result.regression().unwrap();
+        assert_eq!("mon", regression.names()[0]);
+        for (exp, act) in beta.iter().zip(regression.coefficients()) {
+            assert!((exp - act).abs() < 0.1);
+        }
+        for (i, v) in series.iter().enumerate() {
+            assert_in_delta(
+                *v,
+                result.seasonal()[i]
+                    + result.trend()[i]
+                    + result.remainder()[i]
+                    + regression.effect()[i],
+            );
+        }
+
+        assert_eq!(
+            Stl::params()
+                .trading_day(2010, 1)
+                .fit(&series, 7)
+                .unwrap_err(),
+            Error::Parameter("trading_day requires a period of 12 or 4".to_string())
+        );
+    }
+
+    #[test]
+    fn test_holiday() {
+        let easter = [
+            (2010, 4, 4),
+            (2011, 4, 24),
+            (2012, 4, 8),
+            (2013, 3, 31),
+            (2014, 4, 20),
+            (2015, 4, 5),
+            (2016, 3, 27),
+            (2017, 4, 16),
+            (2018, 4, 1),
+            (2019, 4, 21),
+        ];
+        // Easter moves between March and April, adding 2.0 to its month
+        let series = (0..120)
+            .map(|t| {
+                let (year, month) = (2010 + t / 12, t % 12 + 1);
+                let holiday = easter
+                    .iter()
+                    .any(|&(y, m, _)| y == year && m == month as u32);
+                ((t % 12) as f64 - 5.5).abs()
+                    + 10.0
+                    + 0.05 * t as f64
+                    + if holiday { 2.0 } else { 0.0 }
+            })
+            .collect::<Vec<f64>>();
+
+        let result = Stl::params()
+            .holiday("easter", 2010, 1, &easter)
+            .fit(&series, 12)
+            .unwrap();
+        let regression = result.regression().unwrap();
+        assert_eq!(vec!["easter"], regression.names());
+        assert!((2.0 - regression.coefficients()[0]).abs() < 0.1);
+        for (i, v) in series.iter().enumerate() {
+            assert_in_delta(
+                *v,
+                result.seasonal()[i]
+                    + result.trend()[i]
+                    + result.remainder()[i]
+                    + regression.effect()[i],
+            );
+        }
+
+        assert_eq!(
+            Stl::params()
+                .regressor("event", &[1.0, 0.0])
+                .fit(&series, 12)
+                .unwrap_err(),
+            Error::Parameter("regressor event must have one value per observation".to_string())
+        );
+        assert_eq!(
+            Stl::params()
+                .holiday("bad", 2010, 1, &[(2011, 2, 30)])
+                .fit(&series, 12)
+                .unwrap_err(),
+            Error::Parameter("holiday bad has an invalid date".to_string())
+        );
+    }
 }
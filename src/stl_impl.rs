@@ -6,6 +6,36 @@
 
 #![allow(clippy::too_many_arguments)]
 
+use std::time::Instant;
+
+use super::fit_report::FitReport;
+use super::{Monotone, NonNegative, Smoother};
+
+// Custom smoothers replacing loess in each stage, if any
+#[derive(Clone, Copy, Default)]
+pub struct Smoothers<'a> {
+    pub seasonal: Option<&'a dyn Smoother>,
+    pub trend: Option<&'a dyn Smoother>,
+    pub low_pass: Option<&'a dyn Smoother>,
+}
+
+// Options that change what the passes fit, beyond the smoothers themselves
+#[derive(Clone, Copy, Default)]
+pub struct FitOptions<'a> {
+    /// A seasonal component fixed in advance, which skips the seasonal stage.
+    pub fixed_seasonal: Option<&'a [f64]>,
+    /// Whether to smooth the trend residuals and add them back.
+    pub twicing: bool,
+    /// The penalty shrinking the seasonal component towards a periodic pattern.
+    pub seasonal_penalty: f64,
+    /// The direction the trend is constrained to, if any.
+    pub monotone: Option<Monotone>,
+    /// The components constrained to be non-negative, if any.
+    pub non_negative: Option<NonNegative>,
+    /// The quantile fitted in place of the mean, if any.
+    pub quantile: Option<f64>,
+}
+
 pub fn stl(
     y: &[f64],
     n: usize,
@@ -21,10 +51,15 @@ pub fn stl(
     nljump: usize,
     ni: usize,
     no: usize,
+    pw: Option<&[f64]>,
+    smoothers: Smoothers,
+    options: FitOptions,
     rw: &mut [f64],
     season: &mut [f64],
     trend: &mut [f64],
-) {
+) -> FitReport {
+    let mut report = FitReport::default();
+
     let mut work1 = vec![0.0; n + 2 * np];
     let mut work2 = vec![0.0; n + 2 * np];
     let mut work3 = vec![0.0; n + 2 * np];
@@ -34,10 +69,17 @@ pub fn stl(
     let mut userw = false;
     let mut k = 0;
 
+    // prior weights are applied from the first pass
+    if let Some(pw) = pw {
+        rw.copy_from_slice(pw);
+        userw = true;
+    }
+
     loop {
         onestp(
-            y, n, np, ns, nt, nl, isdeg, itdeg, ildeg, nsjump, ntjump, nljump, ni, userw, rw,
-            season, trend, &mut work1, &mut work2, &mut work3, &mut work4, &mut work5,
+            y, n, np, ns, nt, nl, isdeg, itdeg, ildeg, nsjump, ntjump, nljump, ni, userw,
+            smoothers, options, rw, season, trend, &mut work1, &mut work2, &mut work3,
+            &mut work4, &mut work5, &mut report,
         );
         k += 1;
         if k > no {
@@ -46,18 +88,28 @@ pub fn stl(
         for i in 0..n {
             work1[i] = trend[i] + season[i];
         }
-        rwts(y, n, &work1, rw);
+        match options.quantile {
+            Some(q) => qwts(y, n, q, &work1, rw),
+            None => rwts(y, n, &work1, rw),
+        }
+        report.robustness_iterations += 1;
+        if let Some(pw) = pw {
+            for i in 0..n {
+                rw[i] *= pw[i];
+            }
+        }
         userw = true;
     }
 
-    if no == 0 {
+    if no == 0 && pw.is_none() {
         for v in rw.iter_mut() {
             *v = 1.0;
         }
     }
+    report
 }
 
-fn ess(
+pub fn ess(
     y: &[f64],
     n: usize,
     len: usize,
@@ -67,6 +119,7 @@ fn ess(
     rw: &[f64],
     ys: &mut [f64],
     res: &mut [f64],
+    report: &mut FitReport,
 ) {
     if n < 2 {
         ys[0] = y[0];
@@ -75,6 +128,7 @@ fn ess(
 
     let mut nleft = 0;
     let mut nright = 0;
+    let mut interpolated = 0;
 
     let newnj = njump.min(n - 1);
     if len >= n {
@@ -102,7 +156,7 @@ fn ess(
         }
     } else if newnj == 1 {
         // newnj equal to one, len less than n
-        let nsh = (len + 1) / 2;
+        let nsh = len.div_ceil(2);
         nleft = 1;
         nright = len;
         for i in 1..=n {
@@ -130,7 +184,7 @@ fn ess(
         }
     } else {
         // newnj greater than one, len less than n
-        let nsh = (len + 1) / 2;
+        let nsh = len.div_ceil(2);
         let mut i = 1;
         while i <= n {
             // fitted value at i
@@ -171,6 +225,7 @@ fn ess(
             for j in i + 1..=i + newnj - 1 {
                 ys[j - 1] = ys[i - 1] + delta * ((j - i) as f64);
             }
+            interpolated += newnj - 1;
             i += newnj;
         }
         let k = ((n - 1) / newnj) * newnj + 1;
@@ -196,12 +251,16 @@ fn ess(
                 for j in k + 1..=n - 1 {
                     ys[j - 1] = ys[k - 1] + delta * ((j - k) as f64);
                 }
+                interpolated += n - 1 - k;
             }
         }
     }
+    // every point is either fitted or interpolated
+    report.loess_evaluations += n - interpolated;
+    report.interpolated_points += interpolated;
 }
 
-fn est(
+pub fn est(
     y: &[f64],
     n: usize,
     len: usize,
@@ -297,12 +356,10 @@ fn ma(x: &[f64], n: usize, len: usize, ave: &mut [f64]) {
     ave[0] = v / flen;
 
     if newn > 1 {
-        let mut k = len;
         for (m, aj) in ave.iter_mut().take(newn).skip(1).enumerate() {
             // window down the array
-            v = v - x[m] + x[k];
+            v = v - x[m] + x[len + m];
             *aj = v / flen;
-            k += 1;
         }
     }
 }
@@ -322,6 +379,8 @@ fn onestp(
     nljump: usize,
     ni: usize,
     userw: bool,
+    smoothers: Smoothers,
+    options: FitOptions,
     rw: &mut [f64],
     season: &mut [f64],
     trend: &mut [f64],
@@ -330,24 +389,183 @@ fn onestp(
     work3: &mut [f64],
     work4: &mut [f64],
     work5: &mut [f64],
+    report: &mut FitReport,
 ) {
     for _ in 0..ni {
+        report.inner_iterations += 1;
+        if let Some(fixed) = options.fixed_seasonal {
+            season[..n].copy_from_slice(&fixed[..n]);
+            for i in 0..n {
+                work1[i] = y[i] - season[i];
+            }
+            smooth_trend(
+                work1, n, nt, itdeg, ntjump, userw, smoothers, options, rw, trend, work3, report,
+            );
+            continue;
+        }
+
         for i in 0..n {
             work1[i] = y[i] - trend[i];
         }
 
+        let start = Instant::now();
+
         ss(
-            work1, n, np, ns, isdeg, nsjump, userw, rw, work2, work3, work4, work5, season,
+            work1,
+            n,
+            np,
+            ns,
+            isdeg,
+            nsjump,
+            userw,
+            rw,
+            smoothers.seasonal,
+            work2,
+            work3,
+            work4,
+            work5,
+            season,
+            report,
         );
+        report.seasonal_time += start.elapsed();
+
+        let start = Instant::now();
         fts(work2, n + 2 * np, np, work3, work1);
-        ess(work3, n, nl, ildeg, nljump, false, work4, work1, work5);
+        if let Some(smoother) = smoothers.low_pass {
+            smoother.smooth(&work3[..n], None, 0, &mut work1[..n]);
+        } else {
+            ess(
+                work3, n, nl, ildeg, nljump, false, work4, work1, work5, report,
+            );
+        }
+        report.low_pass_time += start.elapsed();
         for i in 0..n {
             season[i] = work2[np + i] - work1[i];
         }
+        if options.seasonal_penalty > 0.0 {
+            shrink_seasonal(&mut season[..n], np, options.seasonal_penalty);
+        }
         for i in 0..n {
             work1[i] = y[i] - season[i];
         }
-        ess(work1, n, nt, itdeg, ntjump, userw, rw, trend, work3);
+
+        smooth_trend(
+            work1, n, nt, itdeg, ntjump, userw, smoothers, options, rw, trend, work3, report,
+        );
+        if options.non_negative == Some(NonNegative::Fitted) {
+            for i in 0..n {
+                season[i] = season[i].max(-trend[i]);
+            }
+        }
+    }
+}
+
+// Smooths the deseasonalized series in work1 into the trend; with twicing,
+// the residuals are smoothed the same way and added back, which reduces the
+// bias of a single pass where the trend curves sharply
+fn smooth_trend(
+    work1: &[f64],
+    n: usize,
+    nt: usize,
+    itdeg: i32,
+    ntjump: usize,
+    userw: bool,
+    smoothers: Smoothers,
+    options: FitOptions,
+    rw: &[f64],
+    trend: &mut [f64],
+    work3: &mut [f64],
+    report: &mut FitReport,
+) {
+    let start = Instant::now();
+    let mut pass = |y: &[f64], fit: &mut [f64]| {
+        if let Some(smoother) = smoothers.trend {
+            smoother.smooth(&y[..n], userw.then_some(&rw[..n]), 0, &mut fit[..n]);
+        } else {
+            ess(y, n, nt, itdeg, ntjump, userw, rw, fit, work3, report);
+        }
+    };
+    pass(work1, trend);
+    if options.twicing {
+        let residuals = (0..n).map(|i| work1[i] - trend[i]).collect::<Vec<f64>>();
+        let mut fit = vec![0.0; n];
+        pass(&residuals, &mut fit);
+        for i in 0..n {
+            trend[i] += fit[i];
+        }
+    }
+    if let Some(monotone) = options.monotone {
+        isotonic(&mut trend[..n], monotone);
+    }
+    if options.non_negative.is_some() {
+        for t in &mut trend[..n] {
+            *t = t.max(0.0);
+        }
+    }
+    report.trend_time += start.elapsed();
+}
+
+// Projects onto monotone sequences with pool adjacent violators, replacing
+// each run that breaks the order with its mean
+fn isotonic(values: &mut [f64], monotone: Monotone) {
+    let sign = match monotone {
+        Monotone::Increasing => 1.0,
+        Monotone::Decreasing => -1.0,
+    };
+    // blocks of (sum, count), merged while the means are out of order
+    let mut blocks: Vec<(f64, usize)> = Vec::with_capacity(values.len());
+    for &v in values.iter() {
+        blocks.push((sign * v, 1));
+        while let [.., (s1, c1), (s2, c2)] = blocks[..]
+            && s1 / c1 as f64 > s2 / c2 as f64
+        {
+            blocks.pop();
+            *blocks.last_mut().unwrap() = (s1 + s2, c1 + c2);
+        }
+    }
+    let mut i = 0;
+    for (sum, count) in blocks {
+        for v in &mut values[i..i + count] {
+            *v = sign * sum / count as f64;
+        }
+        i += count;
+    }
+}
+
+// Weights of iteratively reweighted least squares for the check loss of
+// quantile regression, scaled to a maximum of one
+fn qwts(y: &[f64], n: usize, q: f64, fit: &[f64], rw: &mut [f64]) {
+    for i in 0..n {
+        rw[i] = (y[i] - fit[i]).abs();
+    }
+    rw[..n].sort_unstable_by(|a, b| a.partial_cmp(b).unwrap());
+    // residuals near zero would get unbounded weights
+    let eps = (0.01 * (rw[(n - 1) / 2] + rw[n / 2]) / 2.0).max(f64::MIN_POSITIVE);
+
+    for i in 0..n {
+        let r = y[i] - fit[i];
+        let side = if r > 0.0 { q } else { 1.0 - q };
+        rw[i] = side / r.abs().max(eps);
+    }
+    let max = rw[..n].iter().copied().fold(0.0, f64::max);
+    for w in &mut rw[..n] {
+        *w /= max;
+    }
+}
+
+// Shrinks each cycle-subseries towards its mean, so a penalty of zero leaves
+// the seasonal component as is and a large penalty makes it periodic
+fn shrink_seasonal(season: &mut [f64], np: usize, penalty: f64) {
+    for j in 0..np {
+        let (sum, count) = season
+            .iter()
+            .skip(j)
+            .step_by(np)
+            .fold((0.0, 0), |(s, c), v| (s + v, c + 1));
+        let mean = sum / count as f64;
+        for v in season.iter_mut().skip(j).step_by(np) {
+            *v = (*v + penalty * mean) / (1.0 + penalty);
+        }
     }
 }
 
@@ -386,11 +604,13 @@ fn ss(
     nsjump: usize,
     userw: bool,
     rw: &[f64],
+    smoother: Option<&dyn Smoother>,
     season: &mut [f64],
     work1: &mut [f64],
     work2: &mut [f64],
     work3: &mut [f64],
     work4: &mut [f64],
+    report: &mut FitReport,
 ) {
     for j in 1..=np {
         let k = (n - j) / np + 1;
@@ -403,6 +623,18 @@ fn ss(
                 work3[i - 1] = rw[(i - 1) * np + j - 1];
             }
         }
+        if let Some(smoother) = smoother {
+            smoother.smooth(
+                &work1[..k],
+                userw.then_some(&work3[..k]),
+                1,
+                &mut work2[..k + 2],
+            );
+            for m in 1..=k + 2 {
+                season[(m - 1) * np + j - 1] = work2[m - 1];
+            }
+            continue;
+        }
         ess(
             work1,
             k,
@@ -413,6 +645,7 @@ fn ss(
             work3,
             &mut work2[1..],
             work4,
+            report,
         );
         let mut xs = 0.0;
         let nright = ns.min(k);
@@ -450,8 +683,105 @@ fn ss(
         if !ok {
             work2[k + 1] = work2[k];
         }
+        report.loess_evaluations += 2;
         for m in 1..=k + 2 {
             season[(m - 1) * np + j - 1] = work2[m - 1];
         }
     }
 }
+
+// Calls f(i, nleft, nright) for every point, using the same neighborhoods
+// as ess with njump == 1.
+fn nbhd(n: usize, len: usize, mut f: impl FnMut(usize, usize, usize)) {
+    let nsh = len.div_ceil(2);
+    let mut nleft = 1;
+    let mut nright = len.min(n);
+    for i in 1..=n {
+        if len < n && i > nsh && nright != n {
+            nleft += 1;
+            nright += 1;
+        }
+        f(i, nleft, nright);
+    }
+}
+
+// Diagonal of the loess operator matrix, i.e. the weight each observation
+// receives in its own fitted value.
+pub fn lev(n: usize, len: usize, ideg: i32, userw: bool, rw: &[f64], lev: &mut [f64]) {
+    let y = vec![0.0; n];
+    let mut w = vec![0.0; n];
+    let mut ys = 0.0;
+
+    if n < 2 {
+        lev[0] = 1.0;
+        return;
+    }
+
+    nbhd(n, len, |i, nleft, nright| {
+        let ok = est(
+            &y, n, len, ideg, i as f64, &mut ys, nleft, nright, &mut w, userw, rw,
+        );
+        lev[i - 1] = if ok { w[i - 1] } else { 1.0 };
+    });
+}
+
+// Slope of the locally-weighted linear fit at each point.
+pub fn slp(y: &[f64], n: usize, len: usize, userw: bool, rw: &[f64], slope: &mut [f64]) {
+    let range = (n as f64) - 1.0;
+    let mut w = vec![0.0; n];
+    let mut ys = 0.0;
+
+    if n < 2 {
+        slope[0] = 0.0;
+        return;
+    }
+
+    nbhd(n, len, |i, nleft, nright| {
+        // degree zero leaves the normalized neighborhood weights in w
+        let ok = est(
+            y, n, len, 0, i as f64, &mut ys, nleft, nright, &mut w, userw, rw,
+        );
+        slope[i - 1] = 0.0;
+        if ok {
+            let mut a = 0.0;
+            for j in nleft..=nright {
+                a += w[j - 1] * (j as f64);
+            }
+            let mut b = 0.0;
+            let mut c = 0.0;
+            for j in nleft..=nright {
+                if w[j - 1] > 0.0 {
+                    b += w[j - 1] * ((j as f64) - a) * y[j - 1];
+                    c += w[j - 1] * ((j as f64) - a).powi(2);
+                }
+            }
+            if c.sqrt() > 0.001 * range {
+                slope[i - 1] = b / c;
+            }
+        }
+    });
+}
+
+// Leverage of the cycle-subseries smoother, one subseries at a time.
+pub fn sslev(
+    n: usize,
+    np: usize,
+    ns: usize,
+    isdeg: i32,
+    userw: bool,
+    rw: &[f64],
+    lev_out: &mut [f64],
+) {
+    let mut work1 = vec![0.0; n / np + 1];
+    let mut work2 = vec![0.0; n / np + 1];
+    for j in 1..=np {
+        let k = (n - j) / np + 1;
+        for i in 1..=k {
+            work1[i - 1] = rw[(i - 1) * np + j - 1];
+        }
+        lev(k, ns, isdeg, userw, &work1, &mut work2);
+        for i in 1..=k {
+            lev_out[(i - 1) * np + j - 1] = work2[i - 1];
+        }
+    }
+}
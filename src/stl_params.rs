@@ -1,21 +1,171 @@
-use super::stl_impl::stl;
-use super::{Error, StlResult};
+use std::sync::Arc;
+
+use super::anomaly::quantile;
+use super::cache;
+use super::calendar::{days_in_month, holiday_regressor, trading_day_regressors};
+use super::missing::fill_missing;
+use super::profiles;
+use super::regression::{RegressionEffect, ols};
+use super::smoother::Fixed;
+use super::stl_impl::{FitOptions, Smoothers, stl};
+use super::{Error, Smoother, StlResult};
+
+/// How to handle a trailing cycle that is shorter than the period.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum PartialCycle {
+    /// Decompose the series as-is.
+    #[default]
+    Allow,
+    /// Return an error unless the length is a multiple of the period.
+    Strict,
+    /// Drop the trailing partial cycle before decomposing.
+    Truncate,
+    /// Complete the final cycle with the extrapolated trend and the most
+    /// recent seasonal cycle, then drop the padding from the result.
+    PadWithTrend,
+}
+
+// Clips values beyond the quantiles of each tail, returning the clipped
+// series and the indices of the clipped values
+fn winsorize(series: &[f64], fraction: f64) -> (Vec<f64>, Vec<usize>) {
+    let mut sorted = series
+        .iter()
+        .copied()
+        .filter(|v| !v.is_nan())
+        .collect::<Vec<f64>>();
+    if sorted.is_empty() {
+        return (series.to_vec(), Vec::new());
+    }
+    sorted.sort_unstable_by(|a, b| a.total_cmp(b));
+    let (lo, hi) = (
+        quantile(&sorted, fraction),
+        quantile(&sorted, 1.0 - fraction),
+    );
+    let clipped = series.iter().map(|v| v.clamp(lo, hi)).collect::<Vec<f64>>();
+    let indices = (0..series.len())
+        .filter(|&i| series[i] < lo || series[i] > hi)
+        .collect();
+    (clipped, indices)
+}
+
+// Moves the mean of each full cycle of the seasonal component into the trend
+fn center_cycles(result: &mut StlResult, period: usize) {
+    let mut mean = 0.0;
+    let seasonal = result.seasonal.chunks_mut(period);
+    let trend = result.trend.chunks_mut(period);
+    for (s, t) in seasonal.zip(trend) {
+        if s.len() == period {
+            mean = s.iter().sum::<f64>() / period as f64;
+        }
+        for (s, t) in s.iter_mut().zip(t) {
+            *s -= mean;
+            *t += mean;
+        }
+    }
+}
+
+/// A direction the trend is constrained to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Monotone {
+    /// The trend never decreases.
+    Increasing,
+    /// The trend never increases.
+    Decreasing,
+}
+
+/// Which components are constrained to be non-negative.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NonNegative {
+    /// The trend.
+    Trend,
+    /// The trend and the fitted values, the sum of the trend and the seasonal
+    /// component.
+    Fitted,
+}
+
+/// A distribution for count data, decomposed on the log scale.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CountModel {
+    /// Poisson counts, with variance equal to the mean.
+    Poisson,
+    /// Negative binomial counts with the given size (dispersion) parameter,
+    /// with variance `mean + mean^2 / size`.
+    NegativeBinomial(f64),
+}
+
+/// The component shared by series decomposed jointly.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Shared {
+    /// A common trend, shifted by a constant level for each series.
+    Trend,
+    /// A common seasonal component.
+    Seasonal,
+}
+
+// Dates of a holiday for monthly or quarterly data starting in a given month
+#[derive(Clone, Debug)]
+struct Holiday {
+    name: String,
+    start: (i32, u32),
+    dates: Vec<(i32, u32, u32)>,
+}
+
+// A skipping value as a number of points or a fraction of the window length
+#[derive(Clone, Copy, Debug)]
+enum Jump {
+    Points(usize),
+    Fraction(f64),
+}
+
+impl Jump {
+    // Resolves the jump for a window, defaulting to a tenth of its length
+    fn resolve(jump: Option<Jump>, length: usize) -> usize {
+        match jump {
+            Some(Jump::Points(points)) => points,
+            Some(Jump::Fraction(fraction)) => ((fraction * length as f64).round() as usize).max(1),
+            None => ((length as f64) / 10.0).ceil().max(1.0) as usize,
+        }
+    }
+}
 
 /// A set of STL parameters.
+///
+/// Parameters are configured through `&mut self` and fitted through `&self`,
+/// and are `Send + Sync`, so one configured set can be shared, for example in
+/// an `Arc`, by any number of concurrent fits without cloning.
 #[derive(Clone, Debug)]
 pub struct StlParams {
     pub(crate) ns: Option<usize>,
     nt: Option<usize>,
     nl: Option<usize>,
-    isdeg: i32,
+    isdeg: Option<i32>,
     itdeg: i32,
     ildeg: Option<i32>,
-    nsjump: Option<usize>,
-    ntjump: Option<usize>,
-    nljump: Option<usize>,
+    nsjump: Option<Jump>,
+    ntjump: Option<Jump>,
+    nljump: Option<Jump>,
     ni: Option<usize>,
     no: Option<usize>,
     robust: bool,
+    partial_cycle: PartialCycle,
+    trading_day: Option<(i32, u32)>,
+    holidays: Vec<Holiday>,
+    regressors: Vec<(String, Vec<f64>)>,
+    fixed_trend: Option<Arc<[f64]>>,
+    fixed_seasonal: Option<Arc<[f64]>>,
+    pub(crate) warm_trend: Option<Arc<[f64]>>,
+    check_residual: bool,
+    twicing: bool,
+    winsorize: Option<f64>,
+    detrend: bool,
+    seasonal_penalty: f64,
+    zero_sum_seasonal: bool,
+    monotone: Option<Monotone>,
+    non_negative: Option<NonNegative>,
+    quantile: Option<f64>,
+    seasonal_smoother: Option<Arc<dyn Smoother>>,
+    trend_smoother: Option<Arc<dyn Smoother>>,
+    low_pass_smoother: Option<Arc<dyn Smoother>>,
 }
 
 impl StlParams {
@@ -25,7 +175,7 @@ impl StlParams {
             ns: None,
             nt: None,
             nl: None,
-            isdeg: 0,
+            isdeg: None,
             itdeg: 1,
             ildeg: None,
             nsjump: None,
@@ -34,9 +184,35 @@ impl StlParams {
             ni: None,
             no: None,
             robust: false,
+            partial_cycle: PartialCycle::Allow,
+            trading_day: None,
+            holidays: Vec::new(),
+            regressors: Vec::new(),
+            fixed_trend: None,
+            fixed_seasonal: None,
+            warm_trend: None,
+            check_residual: false,
+            twicing: false,
+            winsorize: None,
+            detrend: false,
+            seasonal_penalty: 0.0,
+            zero_sum_seasonal: false,
+            monotone: None,
+            non_negative: None,
+            quantile: None,
+            seasonal_smoother: None,
+            trend_smoother: None,
+            low_pass_smoother: None,
         }
     }
 
+    /// Creates a copy of the parameters registered under a name.
+    ///
+    /// See [`profiles::register`](crate::profiles::register).
+    pub fn from_profile(name: &str) -> Result<Self, Error> {
+        profiles::get(name).ok_or_else(|| Error::Parameter(format!("no profile named {}", name)))
+    }
+
     /// Sets the length of the seasonal smoother.
     pub fn seasonal_length(&mut self, length: usize) -> &mut Self {
         self.ns = Some(length);
@@ -57,7 +233,7 @@ impl StlParams {
 
     /// Sets the degree of locally-fitted polynomial in seasonal smoothing.
     pub fn seasonal_degree(&mut self, degree: i32) -> &mut Self {
-        self.isdeg = degree;
+        self.isdeg = Some(degree);
         self
     }
 
@@ -75,19 +251,40 @@ impl StlParams {
 
     /// Sets the skipping value for seasonal smoothing.
     pub fn seasonal_jump(&mut self, jump: usize) -> &mut Self {
-        self.nsjump = Some(jump);
+        self.nsjump = Some(Jump::Points(jump));
+        self
+    }
+
+    /// Sets the skipping value for seasonal smoothing as a fraction of the
+    /// seasonal length, resolved for each fit and rounded to at least 1.
+    pub fn seasonal_jump_fraction(&mut self, fraction: f64) -> &mut Self {
+        self.nsjump = Some(Jump::Fraction(fraction));
         self
     }
 
     /// Sets the skipping value for trend smoothing.
     pub fn trend_jump(&mut self, jump: usize) -> &mut Self {
-        self.ntjump = Some(jump);
+        self.ntjump = Some(Jump::Points(jump));
+        self
+    }
+
+    /// Sets the skipping value for trend smoothing as a fraction of the
+    /// trend length, resolved for each fit and rounded to at least 1.
+    pub fn trend_jump_fraction(&mut self, fraction: f64) -> &mut Self {
+        self.ntjump = Some(Jump::Fraction(fraction));
         self
     }
 
     /// Sets the skipping value for low-pass smoothing.
     pub fn low_pass_jump(&mut self, jump: usize) -> &mut Self {
-        self.nljump = Some(jump);
+        self.nljump = Some(Jump::Points(jump));
+        self
+    }
+
+    /// Sets the skipping value for low-pass smoothing as a fraction of the
+    /// low-pass length, resolved for each fit and rounded to at least 1.
+    pub fn low_pass_jump_fraction(&mut self, fraction: f64) -> &mut Self {
+        self.nljump = Some(Jump::Fraction(fraction));
         self
     }
 
@@ -109,109 +306,1067 @@ impl StlParams {
         self
     }
 
-    /// Decomposes a time series.
-    pub fn fit(&self, series: &[f64], period: usize) -> Result<StlResult, Error> {
-        let y = series;
-        let np = period;
-        let n = y.len();
+    /// Decomposes a quantile of the series, such as 0.5 for the median or
+    /// 0.9 for the 90th percentile, instead of the mean.
+    ///
+    /// The robustness iterations use the weights of quantile regression in
+    /// place of the bisquare weights, so the seasonal and trend smoothers
+    /// become quantile loess. Unless set explicitly, the loop counts are the
+    /// same as for robust fitting.
+    pub fn quantile(&mut self, quantile: f64) -> &mut Self {
+        self.quantile = Some(quantile);
+        self
+    }
 
-        if n < np * 2 {
-            return Err(Error::Series(
+    /// Sets how to handle a trailing cycle that is shorter than the period.
+    pub fn partial_cycle(&mut self, partial_cycle: PartialCycle) -> &mut Self {
+        self.partial_cycle = partial_cycle;
+        self
+    }
+
+    /// Estimates and removes trading-day effects before decomposing.
+    ///
+    /// For monthly (period 12) or quarterly (period 4) data starting in the
+    /// given year and month. The regressors are the number of each weekday
+    /// from Monday to Saturday minus the number of Sundays in each period, and
+    /// the estimated effect is kept on the result.
+    pub fn trading_day(&mut self, start_year: i32, start_month: u32) -> &mut Self {
+        self.trading_day = Some((start_year, start_month));
+        self
+    }
+
+    /// Estimates and removes the effect of a holiday before decomposing.
+    ///
+    /// For monthly (period 12) or quarterly (period 4) data starting in the
+    /// given year and month. The regressor is the number of the given dates
+    /// (year, month, day) falling in each period, centered on its mean at each
+    /// phase of the cycle, so the average effect stays with the seasonal
+    /// component and moving holidays like Easter don't leak into it.
+    pub fn holiday(
+        &mut self,
+        name: &str,
+        start_year: i32,
+        start_month: u32,
+        dates: &[(i32, u32, u32)],
+    ) -> &mut Self {
+        self.holidays.push(Holiday {
+            name: name.to_string(),
+            start: (start_year, start_month),
+            dates: dates.to_vec(),
+        });
+        self
+    }
+
+    /// Estimates and removes the effect of a regressor before decomposing.
+    ///
+    /// The values must cover every observation, such as an indicator that is
+    /// one on the days of an event and zero elsewhere.
+    pub fn regressor(&mut self, name: &str, values: &[f64]) -> &mut Self {
+        self.regressors.push((name.to_string(), values.to_vec()));
+        self
+    }
+
+    /// Uses a known trend instead of estimating one.
+    ///
+    /// Only the seasonal component is estimated, from the series minus this
+    /// trend, and the remainder is what is left. The trend must have a value
+    /// for each observation.
+    pub fn fixed_trend(&mut self, trend: &[f64]) -> &mut Self {
+        self.fixed_trend = Some(trend.into());
+        self
+    }
+
+    /// Uses a known seasonal component instead of estimating one.
+    ///
+    /// Only the trend is estimated, from the series minus this component, and
+    /// the remainder is what is left. The component is either one cycle,
+    /// starting at the phase of the first observation, or a value for each
+    /// observation.
+    pub fn fixed_seasonal(&mut self, seasonal: &[f64]) -> &mut Self {
+        self.fixed_seasonal = Some(seasonal.into());
+        self
+    }
+
+    /// Starts from the trend of a previous fit instead of zero.
+    ///
+    /// The previous fit is aligned with the start of the series, and its last
+    /// trend value is carried forward over any new observations, as when
+    /// refitting a series after appending data. Unless set explicitly, a
+    /// single inner loop is used, since the iterations mostly serve to
+    /// converge from a zero trend.
+    pub fn warm_start(&mut self, previous: &StlResult) -> &mut Self {
+        self.warm_trend = Some(previous.trend().into());
+        self
+    }
+
+    /// Sets whether to test the remainder for leftover seasonality after
+    /// fitting.
+    ///
+    /// The check is kept in the result, and a warning is logged when
+    /// seasonality is detected, which suggests a shorter seasonal smoother.
+    pub fn check_residual_seasonality(&mut self, check: bool) -> &mut Self {
+        self.check_residual = check;
+        self
+    }
+
+    /// Sets whether to smooth the trend twice (Tukey's twicing).
+    ///
+    /// The residuals of each trend smoothing pass are smoothed again and
+    /// added back, which reduces the bias of loess on sharply curving trends
+    /// at the cost of a rougher trend.
+    pub fn twicing(&mut self, twicing: bool) -> &mut Self {
+        self.twicing = twicing;
+        self
+    }
+
+    /// Clips the given fraction of observations in each tail to the nearest
+    /// quantile before fitting.
+    ///
+    /// This tames the trend around extreme values at a fraction of the cost
+    /// of robust fitting. The clipped amounts are added back to the remainder,
+    /// and the clipped observations are listed in the result.
+    pub fn winsorize(&mut self, fraction: f64) -> &mut Self {
+        self.winsorize = Some(fraction);
+        self
+    }
+
+    /// Sets whether to remove a least squares line before fitting and add it
+    /// back to the trend afterwards.
+    ///
+    /// This steadies loess on strongly trending series with short histories,
+    /// where the seasonal smoother otherwise picks up part of the trend.
+    pub fn detrend(&mut self, detrend: bool) -> &mut Self {
+        self.detrend = detrend;
+        self
+    }
+
+    /// Sets a penalty on cycle-to-cycle variation of the seasonal component.
+    ///
+    /// In every pass, each cycle-subseries is shrunk towards its mean by a
+    /// factor of `1 / (1 + penalty)`. Zero (the default) leaves the loess fit
+    /// as is, and larger values move towards a strictly periodic seasonal
+    /// component.
+    pub fn seasonal_penalty(&mut self, penalty: f64) -> &mut Self {
+        self.seasonal_penalty = penalty;
+        self
+    }
+
+    /// Sets whether to shift the seasonal component so each full cycle,
+    /// counted from the start of the series, sums to zero.
+    ///
+    /// The mean of each cycle moves into the trend, and a trailing partial
+    /// cycle is shifted by the mean of the last full cycle.
+    pub fn zero_sum_seasonal(&mut self, zero_sum: bool) -> &mut Self {
+        self.zero_sum_seasonal = zero_sum;
+        self
+    }
+
+    /// Constrains the trend to one direction.
+    ///
+    /// After every trend smoothing pass, the trend is replaced by the closest
+    /// monotone sequence in least squares (isotonic regression), so
+    /// wiggles known to be artifacts, as in cumulative adoption, end up in
+    /// the remainder.
+    pub fn monotone_trend(&mut self, monotone: Monotone) -> &mut Self {
+        self.monotone = Some(monotone);
+        self
+    }
+
+    /// Constrains the trend, and optionally the fitted values, to be
+    /// non-negative, as for low-volume counts.
+    ///
+    /// Negative values are raised to zero after every pass, and the
+    /// difference ends up in the remainder. A fixed seasonal component is
+    /// never changed.
+    pub fn non_negative(&mut self, non_negative: NonNegative) -> &mut Self {
+        self.non_negative = Some(non_negative);
+        self
+    }
+
+    /// Sets a custom smoother for the cycle-subseries.
+    pub fn seasonal_smoother<S: Smoother + 'static>(&mut self, smoother: S) -> &mut Self {
+        self.seasonal_smoother = Some(Arc::new(smoother));
+        self
+    }
+
+    /// Sets a custom smoother for the trend.
+    pub fn trend_smoother<S: Smoother + 'static>(&mut self, smoother: S) -> &mut Self {
+        self.trend_smoother = Some(Arc::new(smoother));
+        self
+    }
+
+    /// Sets a custom smoother for the low-pass filter.
+    pub fn low_pass_smoother<S: Smoother + 'static>(&mut self, smoother: S) -> &mut Self {
+        self.low_pass_smoother = Some(Arc::new(smoother));
+        self
+    }
+
+    // Fills unset parameters with the defaults of statsmodels' STL
+    pub(crate) fn statsmodels_defaults(&self, period: usize) -> StlParams {
+        let mut params = self.clone();
+        params.isdeg.get_or_insert(1);
+        params.ildeg.get_or_insert(1);
+        params.nl.get_or_insert(if period.is_multiple_of(2) {
+            period + 1
+        } else {
+            period + 2
+        });
+        params.nsjump.get_or_insert(Jump::Points(1));
+        params.ntjump.get_or_insert(Jump::Points(1));
+        params.nljump.get_or_insert(Jump::Points(1));
+        params
+    }
+
+    /// Checks the parameters against a series length and period without fitting.
+    ///
+    /// Returns every violation, in the order that fitting would report them.
+    pub fn validate(&self, series_len: usize, period: usize) -> Vec<Error> {
+        let mut errors = Vec::new();
+        if series_len < period * 2 {
+            errors.push(Error::Series(
                 "series has less than two periods".to_string(),
             ));
         }
 
-        let ns = self.ns.unwrap_or(7);
-
-        let isdeg = self.isdeg;
-        let itdeg = self.itdeg;
-
-        let mut rw = vec![0.0; n];
-        let mut season = vec![0.0; n];
-        let mut trend = vec![0.0; n];
+        if let Some((_, start_month)) = self.trading_day {
+            if period != 12 && period != 4 {
+                errors.push(Error::Parameter(
+                    "trading_day requires a period of 12 or 4".to_string(),
+                ));
+            }
+            if !(1..=12).contains(&start_month) {
+                errors.push(Error::Parameter(
+                    "start_month must be between 1 and 12".to_string(),
+                ));
+            }
+        }
+        for holiday in &self.holidays {
+            if period != 12 && period != 4 {
+                errors.push(Error::Parameter(
+                    "holiday requires a period of 12 or 4".to_string(),
+                ));
+            }
+            if !(1..=12).contains(&holiday.start.1) {
+                errors.push(Error::Parameter(
+                    "start_month must be between 1 and 12".to_string(),
+                ));
+            }
+            if holiday.dates.iter().any(|&(year, month, day)| {
+                !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month)
+            }) {
+                errors.push(Error::Parameter(format!(
+                    "holiday {} has an invalid date",
+                    holiday.name
+                )));
+            }
+        }
+        for (name, values) in &self.regressors {
+            if values.len() < series_len {
+                errors.push(Error::Parameter(format!(
+                    "regressor {} must have one value per observation",
+                    name
+                )));
+            }
+        }
 
-        let ildeg = self.ildeg.unwrap_or(itdeg);
+        if let Some(trend) = &self.fixed_trend
+            && trend.len() < series_len
+        {
+            errors.push(Error::Parameter(
+                "fixed_trend must have one value per observation".to_string(),
+            ));
+        }
 
-        let mut newns = ns.max(3);
-        if newns % 2 == 0 {
-            newns += 1;
+        if let Some(seasonal) = &self.fixed_seasonal
+            && seasonal.len() != period
+            && seasonal.len() < series_len
+        {
+            errors.push(Error::Parameter(
+                "fixed_seasonal must have one value per phase or per observation".to_string(),
+            ));
         }
 
-        let newnp = np.max(2);
+        if let Some(quantile) = self.quantile
+            && !(quantile > 0.0 && quantile < 1.0)
+        {
+            errors.push(Error::Parameter(
+                "quantile must be between 0 and 1".to_string(),
+            ));
+        }
 
-        let mut nt = if let Some(trend_len) = self.nt {
-            trend_len
-        } else {
-            ((1.5 * newnp as f64) / (1.0 - 1.5 / newns as f64)).ceil() as usize
-        };
-        nt = nt.max(3);
-        if nt % 2 == 0 {
-            nt += 1;
+        for (name, jump) in [
+            ("seasonal", self.nsjump),
+            ("trend", self.ntjump),
+            ("low_pass", self.nljump),
+        ] {
+            if let Some(Jump::Fraction(fraction)) = jump
+                && !(fraction > 0.0 && fraction <= 1.0)
+            {
+                errors.push(Error::Parameter(format!(
+                    "{}_jump fraction must be greater than 0 and at most 1",
+                    name
+                )));
+            }
         }
 
-        let mut nl = self.nl.unwrap_or(newnp);
-        if nl % 2 == 0 && self.nl.is_none() {
-            nl += 1;
+        if let Some(fraction) = self.winsorize
+            && !(0.0..0.5).contains(&fraction)
+        {
+            errors.push(Error::Parameter(
+                "winsorize fraction must be at least 0 and less than 0.5".to_string(),
+            ));
         }
 
-        let ni = self.ni.unwrap_or(if self.robust { 2 } else { 5 });
-        let no = self.no.unwrap_or(if self.robust { 15 } else { 0 });
+        if !(self.seasonal_penalty >= 0.0 && self.seasonal_penalty.is_finite()) {
+            errors.push(Error::Parameter(
+                "seasonal_penalty must be non-negative".to_string(),
+            ));
+        }
 
-        let nsjump = self
-            .nsjump
-            .unwrap_or(((newns as f64) / 10.0).ceil().max(1.0) as usize);
-        let ntjump = self
-            .ntjump
-            .unwrap_or(((nt as f64) / 10.0).ceil().max(1.0) as usize);
-        let nljump = self
-            .nljump
-            .unwrap_or(((nl as f64) / 10.0).ceil().max(1.0) as usize);
+        let (newnp, newns, nt, nl) = self.lengths(period);
+        let isdeg = self.isdeg.unwrap_or(0);
+        let itdeg = self.itdeg;
+        let ildeg = self.ildeg.unwrap_or(itdeg);
 
         if newns < 3 {
-            return Err(Error::Parameter(
+            errors.push(Error::Parameter(
                 "seasonal_length must be at least 3".to_string(),
             ));
         }
         if nt < 3 {
-            return Err(Error::Parameter(
+            errors.push(Error::Parameter(
                 "trend_length must be at least 3".to_string(),
             ));
         }
         if nl < 3 {
-            return Err(Error::Parameter(
+            errors.push(Error::Parameter(
                 "low_pass_length must be at least 3".to_string(),
             ));
         }
         if newnp < 2 {
-            return Err(Error::Parameter("period must be at least 2".to_string()));
+            errors.push(Error::Parameter("period must be at least 2".to_string()));
         }
 
         if isdeg != 0 && isdeg != 1 {
-            return Err(Error::Parameter(
+            errors.push(Error::Parameter(
                 "seasonal_degree must be 0 or 1".to_string(),
             ));
         }
         if itdeg != 0 && itdeg != 1 {
-            return Err(Error::Parameter("trend_degree must be 0 or 1".to_string()));
+            errors.push(Error::Parameter("trend_degree must be 0 or 1".to_string()));
         }
         if ildeg != 0 && ildeg != 1 {
-            return Err(Error::Parameter(
+            errors.push(Error::Parameter(
                 "low_pass_degree must be 0 or 1".to_string(),
             ));
         }
 
         if newns % 2 != 1 {
-            return Err(Error::Parameter("seasonal_length must be odd".to_string()));
+            errors.push(Error::Parameter("seasonal_length must be odd".to_string()));
         }
         if nt % 2 != 1 {
-            return Err(Error::Parameter("trend_length must be odd".to_string()));
+            errors.push(Error::Parameter("trend_length must be odd".to_string()));
         }
         if nl % 2 != 1 {
-            return Err(Error::Parameter("low_pass_length must be odd".to_string()));
+            errors.push(Error::Parameter("low_pass_length must be odd".to_string()));
+        }
+
+        errors
+    }
+
+    // The period and the smoother lengths after defaults and rounding
+    fn lengths(&self, np: usize) -> (usize, usize, usize, usize) {
+        let ns = self.ns.unwrap_or(7);
+        let mut newns = ns.max(3);
+        if newns.is_multiple_of(2) {
+            newns += 1;
+        }
+
+        let newnp = np.max(2);
+
+        let mut nt = if let Some(trend_len) = self.nt {
+            trend_len
+        } else {
+            ((1.5 * newnp as f64) / (1.0 - 1.5 / newns as f64)).ceil() as usize
+        };
+        nt = nt.max(3);
+        if nt % 2 == 0 {
+            nt += 1;
+        }
+
+        let mut nl = self.nl.unwrap_or(newnp);
+        if nl.is_multiple_of(2) && self.nl.is_none() {
+            nl += 1;
+        }
+
+        (newnp, newns, nt, nl)
+    }
+
+    /// Returns warnings for settings that are adjusted or likely to give odd output.
+    ///
+    /// These are also logged when fitting.
+    pub fn warnings(&self, period: usize) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if let Some(ns) = self.ns
+            && ns >= 3
+            && ns.is_multiple_of(2)
+        {
+            warnings.push(format!(
+                "seasonal_length is even and will be increased to {}",
+                ns + 1
+            ));
+        }
+        if let Some(nt) = self.nt {
+            if nt >= 3 && nt.is_multiple_of(2) {
+                warnings.push(format!(
+                    "trend_length is even and will be increased to {}",
+                    nt + 1
+                ));
+            }
+            if nt < period {
+                warnings.push(format!(
+                    "trend_length ({}) is smaller than the period ({}), so the trend may absorb seasonality",
+                    nt, period
+                ));
+            }
+        }
+        let jumps = [
+            ("seasonal", self.nsjump, self.ns),
+            ("trend", self.ntjump, self.nt),
+            ("low_pass", self.nljump, self.nl),
+        ];
+        for (name, jump, length) in jumps {
+            if let (Some(Jump::Points(jump)), Some(length)) = (jump, length)
+                && jump > length
+            {
+                warnings.push(format!(
+                    "{}_jump ({}) is larger than {}_length ({})",
+                    name, jump, name, length
+                ));
+            }
+        }
+        warnings
+    }
+
+    /// Decomposes a time series.
+    pub fn fit(&self, series: &[f64], period: usize) -> Result<StlResult, Error> {
+        let key = self.cache_key(series, period);
+        if let Some(result) = key.as_ref().and_then(cache::get) {
+            return Ok(result);
+        }
+        let result = self.fit_impl(series, period, None)?;
+        if let Some(key) = key {
+            cache::insert(key, &result);
+        }
+        Ok(result)
+    }
+
+    // The key of the fit in the result cache, if enabled and the parameters
+    // are fully described by their values
+    fn cache_key(&self, series: &[f64], period: usize) -> Option<cache::Key> {
+        if !cache::enabled()
+            || self.seasonal_smoother.is_some()
+            || self.trend_smoother.is_some()
+            || self.low_pass_smoother.is_some()
+        {
+            return None;
+        }
+        Some(cache::key(series, &format!("{:?}", self), period))
+    }
+
+    /// Decomposes a time series with prior weights for each observation.
+    ///
+    /// The weights are combined with the robustness weights in every pass.
+    pub fn fit_with_weights(
+        &self,
+        series: &[f64],
+        period: usize,
+        weights: &[f64],
+    ) -> Result<StlResult, Error> {
+        self.fit_impl(series, period, Some(weights))
+    }
+
+    /// Decomposes a series of counts on the log scale.
+    ///
+    /// The fit alternates between a weighted STL fit of the linearized log
+    /// counts and updating the weights from the fitted means (iteratively
+    /// reweighted least squares), so zeros need no offset and sparse series
+    /// get sensible seasonal factors. The seasonal component and trend are on
+    /// the log scale: `exp(trend)` is the baseline mean and `exp(seasonal)`
+    /// the seasonal factor. The remainder is the final linearized log count
+    /// minus both.
+    pub fn fit_counts(
+        &self,
+        series: &[f64],
+        period: usize,
+        model: CountModel,
+    ) -> Result<StlResult, Error> {
+        if series.iter().any(|&v| v < 0.0 || v.is_infinite()) {
+            return Err(Error::Series(
+                "counts must be non-negative and finite".to_string(),
+            ));
+        }
+        let size = match model {
+            CountModel::Poisson => f64::INFINITY,
+            CountModel::NegativeBinomial(size) => {
+                if size.is_nan() || size <= 0.0 {
+                    return Err(Error::Parameter(
+                        "negative binomial size must be positive".to_string(),
+                    ));
+                }
+                size
+            }
+        };
+
+        // start from the log counts, with zeros moved off the boundary
+        let mut eta = series.iter().map(|v| (v + 0.5).ln()).collect::<Vec<f64>>();
+        let mut result = None;
+        for _ in 0..25 {
+            let mu = eta.iter().map(|e| e.exp()).collect::<Vec<f64>>();
+            let working = series
+                .iter()
+                .zip(&eta)
+                .zip(&mu)
+                .map(|((y, e), m)| e + (y - m) / m)
+                .collect::<Vec<f64>>();
+            // the inverse variance of the working response
+            let weights = mu
+                .iter()
+                .zip(series)
+                .map(|(m, y)| {
+                    if y.is_nan() {
+                        0.0
+                    } else {
+                        m / (1.0 + m / size)
+                    }
+                })
+                .collect::<Vec<f64>>();
+            let max = weights.iter().copied().fold(0.0, f64::max);
+            let weights = weights
+                .iter()
+                .map(|w| if max > 0.0 { w / max } else { 1.0 })
+                .collect::<Vec<f64>>();
+
+            let fit = self.fit_with_weights(&working, period, &weights)?;
+            let next = fit
+                .seasonal
+                .iter()
+                .zip(&fit.trend)
+                .map(|(s, t)| s + t)
+                .collect::<Vec<f64>>();
+            let change = next
+                .iter()
+                .zip(&eta)
+                .map(|(a, b)| (a - b).abs())
+                .fold(0.0, f64::max);
+            eta = next;
+            result = Some(fit);
+            if change < 1e-6 {
+                break;
+            }
+        }
+        Ok(result.unwrap())
+    }
+
+    /// Decomposes multiple time series with the same period.
+    ///
+    /// With the `parallel` feature, the series are fitted in parallel.
+    pub fn fit_batch<S: AsRef<[f64]> + Sync>(
+        &self,
+        series: &[S],
+        period: usize,
+    ) -> Result<Vec<StlResult>, Error> {
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+            crate::parallel::install(|| {
+                series
+                    .par_iter()
+                    .map(|s| self.fit(s.as_ref(), period))
+                    .collect()
+            })
+        }
+
+        #[cfg(not(feature = "parallel"))]
+        series
+            .iter()
+            .map(|s| self.fit(s.as_ref(), period))
+            .collect()
+    }
+
+    /// Decomposes several series of the same length together, with one
+    /// component shared by all of them, such as sensors measuring the same
+    /// underlying process.
+    ///
+    /// The fit alternates between estimating the other component of each
+    /// series with the shared one fixed, and averaging the shared component
+    /// of each series with the other one fixed, until the shared component
+    /// settles. A shared trend is the average trend, with each series keeping
+    /// its own level.
+    pub fn fit_joint<S: AsRef<[f64]>>(
+        &self,
+        series: &[S],
+        period: usize,
+        shared: Shared,
+    ) -> Result<Vec<StlResult>, Error> {
+        let n = match series.first() {
+            Some(first) => first.as_ref().len(),
+            None => return Err(Error::Series("no series to decompose".to_string())),
+        };
+        if series.iter().any(|s| s.as_ref().len() != n) {
+            return Err(Error::Series(
+                "series must all have the same length".to_string(),
+            ));
+        }
+
+        let mut results = series
+            .iter()
+            .map(|s| self.fit(s.as_ref(), period))
+            .collect::<Result<Vec<StlResult>, Error>>()?;
+        let mut common = vec![0.0; n];
+        for _ in 0..10 {
+            // the shared component of each series with the other one fixed
+            let own = results
+                .iter()
+                .zip(series)
+                .map(|(result, s)| {
+                    let mut params = self.clone();
+                    match shared {
+                        Shared::Trend => params.fixed_seasonal(&result.seasonal),
+                        Shared::Seasonal => params.fixed_trend(&result.trend),
+                    };
+                    params.fit(s.as_ref(), period)
+                })
+                .collect::<Result<Vec<StlResult>, Error>>()?;
+            let components = own
+                .iter()
+                .map(|r| match shared {
+                    Shared::Trend => &r.trend,
+                    Shared::Seasonal => &r.seasonal,
+                })
+                .collect::<Vec<&Vec<f64>>>();
+
+            let next = (0..n)
+                .map(|i| components.iter().map(|c| c[i]).sum::<f64>() / components.len() as f64)
+                .collect::<Vec<f64>>();
+            let change = next
+                .iter()
+                .zip(&common)
+                .map(|(a, b)| (a - b).abs())
+                .fold(0.0, f64::max);
+            common = next;
+
+            // the other component of each series with the shared one fixed
+            results = components
+                .iter()
+                .zip(series)
+                .map(|(component, s)| {
+                    let mut params = self.clone();
+                    match shared {
+                        Shared::Trend => {
+                            let level = component
+                                .iter()
+                                .zip(&common)
+                                .map(|(c, t)| c - t)
+                                .sum::<f64>()
+                                / n as f64;
+                            let trend = common.iter().map(|t| t + level).collect::<Vec<f64>>();
+                            params.fixed_trend(&trend)
+                        }
+                        Shared::Seasonal => params.fixed_seasonal(&common),
+                    };
+                    params.fit(s.as_ref(), period)
+                })
+                .collect::<Result<Vec<StlResult>, Error>>()?;
+
+            let scale = common.iter().map(|v| v.abs()).fold(0.0, f64::max);
+            if change <= 1e-6 * scale.max(1.0) {
+                break;
+            }
+        }
+        Ok(results)
+    }
+
+    /// Estimates one seasonal cycle pooled across many series of the same
+    /// kind, such as sales at each store.
+    ///
+    /// Each series is decomposed on its own, and the average of its seasonal
+    /// component at each phase is averaged across series, then centered on
+    /// zero. The series must be aligned so that their first observations are
+    /// at the same phase, and the cycle starts at that phase.
+    pub fn pooled_seasonal<S: AsRef<[f64]> + Sync>(
+        &self,
+        series: &[S],
+        period: usize,
+    ) -> Result<Vec<f64>, Error> {
+        if series.is_empty() {
+            return Err(Error::Series("no series to decompose".to_string()));
+        }
+        let results = self.fit_batch(series, period)?;
+
+        let mut pattern = vec![0.0; period];
+        for result in &results {
+            let mut sums = vec![0.0; period];
+            let mut counts = vec![0; period];
+            for (i, v) in result.seasonal.iter().enumerate() {
+                sums[i % period] += v;
+                counts[i % period] += 1;
+            }
+            for (p, (sum, count)) in pattern.iter_mut().zip(sums.iter().zip(&counts)) {
+                *p += sum / *count as f64 / results.len() as f64;
+            }
+        }
+        let mean = pattern.iter().sum::<f64>() / period as f64;
+        for p in &mut pattern {
+            *p -= mean;
+        }
+        Ok(pattern)
+    }
+
+    /// Decomposes many short series of the same kind with a seasonal cycle
+    /// pooled across all of them, so only the trend and remainder are
+    /// estimated for each series.
+    ///
+    /// The cycle is estimated with
+    /// [`pooled_seasonal`](Self::pooled_seasonal), and the series must be
+    /// aligned in the same way. With the `parallel` feature, the series are
+    /// fitted in parallel.
+    pub fn fit_pooled<S: AsRef<[f64]> + Sync>(
+        &self,
+        series: &[S],
+        period: usize,
+    ) -> Result<Vec<StlResult>, Error> {
+        let pattern = self.pooled_seasonal(series, period)?;
+        self.clone()
+            .fixed_seasonal(&pattern)
+            .fit_batch(series, period)
+    }
+
+    fn fit_impl(
+        &self,
+        series: &[f64],
+        period: usize,
+        weights: Option<&[f64]>,
+    ) -> Result<StlResult, Error> {
+        if self.detrend {
+            let n = series.len();
+            let columns = [vec![1.0; n], (0..n).map(|i| i as f64).collect()];
+            let coefficients = ols(series, &columns);
+            let (a, b) = (coefficients[0], coefficients[1]);
+            let line = |i: usize| a + b * i as f64;
+            let remove = |values: &[f64]| {
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(i, v)| v - line(i))
+                    .collect::<Vec<f64>>()
+            };
+
+            // fixed and warm trends are given with the line
+            let mut params = self.clone();
+            params.detrend = false;
+            params.fixed_trend = self.fixed_trend.as_deref().map(|t| remove(t).into());
+            params.warm_trend = self.warm_trend.as_deref().map(|t| remove(t).into());
+            let mut result = params.fit_impl(&remove(series), period, weights)?;
+            for (i, t) in result.trend.iter_mut().enumerate() {
+                *t += line(i);
+            }
+            result.params = Some(self.clone());
+            return Ok(result);
+        }
+
+        let mut result = match self.winsorize {
+            Some(fraction) => {
+                // clipping needs a valid fraction, so the settings are checked first
+                if let Some(err) = self.validate(series.len(), period).into_iter().next() {
+                    return Err(err);
+                }
+                let (clipped, indices) = winsorize(series, fraction);
+                let mut result = self.fit_regression(&clipped, period, weights)?;
+                for (r, (y, c)) in result.remainder.iter_mut().zip(series.iter().zip(&clipped)) {
+                    *r += y - c;
+                }
+                result.clipped = indices
+                    .into_iter()
+                    .filter(|&i| i < result.remainder.len())
+                    .collect();
+                result
+            }
+            None => self.fit_regression(series, period, weights)?,
+        };
+        if self.zero_sum_seasonal {
+            center_cycles(&mut result, period);
+        }
+        if self.check_residual {
+            let check = result.check_residual_seasonality();
+            if check.detected() {
+                log::warn!(
+                    "remainder has seasonality left at lags {:?}, consider a shorter seasonal smoother",
+                    check.lags()
+                );
+            }
+            result.residual_seasonality = Some(check);
+        }
+        result.params = Some(self.clone());
+        Ok(result)
+    }
+
+    fn fit_regression(
+        &self,
+        series: &[f64],
+        period: usize,
+        weights: Option<&[f64]>,
+    ) -> Result<StlResult, Error> {
+        let n = series.len();
+        let mut regressors = Vec::new();
+        let regress =
+            self.trading_day.is_some() || !self.holidays.is_empty() || !self.regressors.is_empty();
+        if regress && let Some(err) = self.validate(n, period).into_iter().next() {
+            return Err(err);
+        }
+        if let Some((start_year, start_month)) = self.trading_day {
+            regressors.extend(trading_day_regressors(start_year, start_month, period, n));
+        }
+        for holiday in &self.holidays {
+            let (start_year, start_month) = holiday.start;
+            regressors.push((
+                holiday.name.clone(),
+                holiday_regressor(&holiday.dates, start_year, start_month, period, n),
+            ));
+        }
+        regressors.extend(self.regressors.iter().cloned());
+        if regressors.is_empty() {
+            return self.fit_partial(series, period, weights);
+        }
+
+        // alternate between decomposing the adjusted series and regressing
+        // the unexplained part on the regressors
+        let (names, columns): (Vec<String>, Vec<Vec<f64>>) = regressors.into_iter().unzip();
+        let mut effect = vec![0.0; n];
+        let mut coefficients = vec![0.0; columns.len()];
+        for _ in 0..3 {
+            let adjusted = series
+                .iter()
+                .zip(&effect)
+                .map(|(y, e)| y - e)
+                .collect::<Vec<f64>>();
+            let result = self.fit_partial(&adjusted, period, weights)?;
+            let len = result.remainder.len();
+            let target = result
+                .remainder
+                .iter()
+                .zip(&effect)
+                .map(|(r, e)| r + e)
+                .collect::<Vec<f64>>();
+            let truncated = columns
+                .iter()
+                .map(|c| c[..len].to_vec())
+                .collect::<Vec<Vec<f64>>>();
+            coefficients = ols(&target, &truncated);
+            effect = (0..n)
+                .map(|t| {
+                    columns
+                        .iter()
+                        .zip(&coefficients)
+                        .map(|(c, b)| c[t] * b)
+                        .sum()
+                })
+                .collect();
+        }
+
+        let adjusted = series
+            .iter()
+            .zip(&effect)
+            .map(|(y, e)| y - e)
+            .collect::<Vec<f64>>();
+        let mut result = self.fit_partial(&adjusted, period, weights)?;
+        effect.truncate(result.remainder.len());
+        result.regression = Some(RegressionEffect {
+            names,
+            coefficients,
+            effect,
+        });
+        Ok(result)
+    }
+
+    fn fit_partial(
+        &self,
+        series: &[f64],
+        period: usize,
+        weights: Option<&[f64]>,
+    ) -> Result<StlResult, Error> {
+        let n = series.len();
+        let partial = if period > 0 { n % period } else { 0 };
+
+        let mut result = match self.partial_cycle {
+            _ if partial == 0 => self.fit_cycles(series, period, weights)?,
+            PartialCycle::Allow => self.fit_cycles(series, period, weights)?,
+            PartialCycle::Strict => {
+                return Err(Error::Series(
+                    "series length must be a multiple of the period".to_string(),
+                ));
+            }
+            PartialCycle::Truncate => self.fit_cycles(
+                &series[..n - partial],
+                period,
+                weights.map(|w| &w[..n - partial]),
+            )?,
+            PartialCycle::PadWithTrend => {
+                let first = self.fit_cycles(series, period, weights)?;
+                let cycle = first.seasonal_cycle();
+                let slope = first.trend[n - 1] - first.trend[n - 2];
+                let mut padded = series.to_vec();
+                padded.extend((0..period - partial).map(|k| {
+                    first.trend[n - 1] + slope * (k + 1) as f64 + cycle[(n + k) % period]
+                }));
+                let len = padded.len();
+                let padded_weights = weights.map(|w| {
+                    let mut w = w.to_vec();
+                    w.resize(len, 1.0);
+                    w
+                });
+
+                // inputs with a value per observation are given for the series
+                // only, so they are extended over the padding too
+                let mut params = self.clone();
+                params.fixed_trend = self.fixed_trend.as_deref().map(|t| {
+                    let slope = t[n - 1] - t[n - 2];
+                    (0..len.max(t.len()))
+                        .map(|i| {
+                            t.get(i)
+                                .copied()
+                                .unwrap_or_else(|| t[n - 1] + slope * (i + 1 - n) as f64)
+                        })
+                        .collect()
+                });
+                params.fixed_seasonal = self
+                    .fixed_seasonal
+                    .as_deref()
+                    .filter(|s| s.len() != period)
+                    .map(|s| {
+                        (0..len.max(s.len()))
+                            .map(|i| {
+                                s.get(i)
+                                    .copied()
+                                    .unwrap_or_else(|| s[n - period + (i - n) % period])
+                            })
+                            .collect()
+                    })
+                    .or_else(|| self.fixed_seasonal.clone());
+                for (_, values) in &mut params.regressors {
+                    values.resize(values.len().max(len), 0.0);
+                }
+                let mut result = params.fit_cycles(&padded, period, padded_weights.as_deref())?;
+                result.seasonal.truncate(n);
+                result.trend.truncate(n);
+                result.remainder.truncate(n);
+                result.weights.truncate(n);
+                result
+            }
+        };
+        result.partial_cycle = partial;
+        Ok(result)
+    }
+
+    fn fit_cycles(
+        &self,
+        series: &[f64],
+        period: usize,
+        weights: Option<&[f64]>,
+    ) -> Result<StlResult, Error> {
+        let y = series;
+        let np = period;
+        let n = y.len();
+
+        for warning in self.warnings(period) {
+            log::warn!("{}", warning);
+        }
+
+        if n < np * 2 {
+            return Err(Error::Series(
+                "series has less than two periods".to_string(),
+            ));
+        }
+
+        if let Some(weights) = weights {
+            if weights.len() != n {
+                return Err(Error::Parameter(
+                    "weights must have the same length as series".to_string(),
+                ));
+            }
+            if weights.iter().any(|w| !(w.is_finite() && *w >= 0.0)) {
+                return Err(Error::Parameter("weights must be non-negative".to_string()));
+            }
+        }
+
+        let filled = fill_missing(y).map(|(filled, mut mw)| {
+            if let Some(weights) = weights {
+                for (m, w) in mw.iter_mut().zip(weights) {
+                    *m *= w;
+                }
+            }
+            (filled, mw)
+        });
+        let (x, weights) = match &filled {
+            Some((filled, mw)) => (&filled[..], Some(&mw[..])),
+            None => (y, weights),
+        };
+        if filled.is_some() && x.iter().all(|v| v.is_nan()) {
+            return Err(Error::Series("series has no observed values".to_string()));
+        }
+
+        let isdeg = self.isdeg.unwrap_or(0);
+        let itdeg = self.itdeg;
+
+        let mut rw = vec![0.0; n];
+        let mut season = vec![0.0; n];
+        let mut trend = vec![0.0; n];
+        if let Some(warm) = self.warm_trend.as_deref().filter(|w| !w.is_empty()) {
+            for (i, t) in trend.iter_mut().enumerate() {
+                *t = warm[i.min(warm.len() - 1)];
+            }
+        }
+
+        let ildeg = self.ildeg.unwrap_or(itdeg);
+
+        let (newnp, newns, nt, nl) = self.lengths(np);
+
+        let ni = self.ni.unwrap_or(match &self.warm_trend {
+            Some(_) => 1,
+            None if self.robust || self.quantile.is_some() => 2,
+            None => 5,
+        });
+        let no = self
+            .no
+            .unwrap_or(if self.robust || self.quantile.is_some() {
+                15
+            } else {
+                0
+            });
+
+        let nsjump = Jump::resolve(self.nsjump, newns);
+        let ntjump = Jump::resolve(self.ntjump, nt);
+        let nljump = Jump::resolve(self.nljump, nl);
+
+        if let Some(err) = self.validate(n, np).into_iter().next() {
+            return Err(err);
         }
 
-        stl(
-            y,
+        let fixed = self.fixed_trend.as_deref().map(Fixed);
+        // a single cycle, or the padding past the end, repeats the last cycle given
+        let fixed_seasonal = self.fixed_seasonal.as_deref().map(|s| {
+            (0..n)
+                .map(|i| {
+                    if i < s.len() {
+                        s[i]
+                    } else {
+                        s[s.len() - np + (i - s.len()) % np]
+                    }
+                })
+                .collect::<Vec<f64>>()
+        });
+        let report = stl(
+            x,
             n,
             newnp,
             newns,
@@ -225,6 +1380,23 @@ impl StlParams {
             nljump,
             ni,
             no,
+            weights,
+            Smoothers {
+                seasonal: self.seasonal_smoother.as_deref(),
+                trend: match &fixed {
+                    Some(fixed) => Some(fixed),
+                    None => self.trend_smoother.as_deref(),
+                },
+                low_pass: self.low_pass_smoother.as_deref(),
+            },
+            FitOptions {
+                fixed_seasonal: fixed_seasonal.as_deref(),
+                twicing: self.twicing,
+                seasonal_penalty: self.seasonal_penalty,
+                monotone: self.monotone,
+                non_negative: self.non_negative,
+                quantile: self.quantile,
+            },
             &mut rw,
             &mut season,
             &mut trend,
@@ -240,6 +1412,17 @@ impl StlParams {
             trend,
             remainder,
             weights: rw,
+            period: newnp,
+            seasonal_length: newns,
+            seasonal_degree: isdeg,
+            trend_length: nt,
+            trend_degree: itdeg,
+            partial_cycle: 0,
+            regression: None,
+            report,
+            residual_seasonality: None,
+            clipped: Vec::new(),
+            params: None,
         })
     }
 }
@@ -1,3 +1,19 @@
+use super::anomaly::{anomalies, outliers};
+use super::forecast::{adjusted_series, forecast_adjusted};
+use super::harmonic::harmonics;
+use super::leakage::leakage;
+use super::residual_seasonality::residual_seasonality;
+use super::seasonal_model::mad;
+use super::stl_impl::{lev, slp, sslev};
+use super::strength::{component_strength, statsmodels_strength, strength_with};
+use super::synth::Rng;
+use super::turning_point::turning_points;
+use super::{
+    Anomaly, Error, FitReport, ForecastMethod, Harmonic, Leakage, LoessDiagnostics, NonNegative,
+    Outliers, RegressionEffect, ResidualSeasonality, SeasonalModel, StlParams, StrengthMethod,
+    TurningPoint, Variance,
+};
+
 /// A STL result.
 #[derive(Clone, Debug)]
 pub struct StlResult {
@@ -5,20 +21,185 @@ pub struct StlResult {
     pub(crate) trend: Vec<f64>,
     pub(crate) remainder: Vec<f64>,
     pub(crate) weights: Vec<f64>,
+    pub(crate) period: usize,
+    pub(crate) seasonal_length: usize,
+    pub(crate) seasonal_degree: i32,
+    pub(crate) trend_length: usize,
+    pub(crate) trend_degree: i32,
+    pub(crate) partial_cycle: usize,
+    pub(crate) regression: Option<RegressionEffect>,
+    pub(crate) report: FitReport,
+    pub(crate) residual_seasonality: Option<ResidualSeasonality>,
+    pub(crate) clipped: Vec<usize>,
+    pub(crate) params: Option<StlParams>,
+}
+
+// Phase-randomized surrogates of a series: the amplitude at each frequency of
+// the discrete Fourier transform is kept and the phase drawn at random, except
+// at the seasonal frequencies, where the amplitude is drawn as for noise with
+// the power of the frequencies nearby
+struct Spectrum {
+    mean: f64,
+    // the amplitudes at frequencies 1 to n / 2, or the power nearby for the
+    // seasonal ones
+    amplitudes: Vec<f64>,
+    seasonal: Vec<bool>,
+    cos: Vec<f64>,
+    sin: Vec<f64>,
 }
 
-fn var(series: &[f64]) -> f64 {
-    let mean = series.iter().sum::<f64>() / series.len() as f64;
-    series.iter().map(|v| (v - mean).powf(2.0)).sum::<f64>() / (series.len() as f64 - 1.0)
+impl Spectrum {
+    fn new(series: &[f64], period: usize) -> Self {
+        let n = series.len();
+        let observed = series.iter().filter(|v| !v.is_nan()).collect::<Vec<&f64>>();
+        let mean = observed.iter().copied().sum::<f64>() / observed.len().max(1) as f64;
+        let values = series
+            .iter()
+            .map(|&v| if v.is_nan() { 0.0 } else { v - mean })
+            .collect::<Vec<f64>>();
+
+        let angle = |j: usize| 2.0 * std::f64::consts::PI * j as f64 / n as f64;
+        let cos = (0..n).map(|j| angle(j).cos()).collect::<Vec<f64>>();
+        let sin = (0..n).map(|j| angle(j).sin()).collect::<Vec<f64>>();
+        let amplitudes = (1..=n / 2)
+            .map(|k| {
+                let (mut a, mut b) = (0.0, 0.0);
+                for (t, v) in values.iter().enumerate() {
+                    a += v * cos[k * t % n];
+                    b += v * sin[k * t % n];
+                }
+                a.hypot(b)
+            })
+            .collect::<Vec<f64>>();
+
+        // within one frequency of a multiple of n / period
+        let cycles = n as f64 / period as f64;
+        let seasonal = |k: usize| {
+            let multiple = (k as f64 / cycles).round();
+            multiple >= 1.0 && (k as f64 - multiple * cycles).abs() < 1.0
+        };
+        let seasonal = (1..=n / 2).map(seasonal).collect::<Vec<bool>>();
+        let amplitudes = (1..=n / 2)
+            .map(|k| {
+                if !seasonal[k - 1] {
+                    return amplitudes[k - 1];
+                }
+                let nearby = (k.saturating_sub(3).max(1)..=(k + 3).min(n / 2))
+                    .filter(|&m| !seasonal[m - 1])
+                    .map(|m| amplitudes[m - 1].powi(2))
+                    .collect::<Vec<f64>>();
+                nearby.iter().sum::<f64>() / nearby.len().max(1) as f64
+            })
+            .collect();
+
+        Self {
+            mean,
+            amplitudes,
+            seasonal,
+            cos,
+            sin,
+        }
+    }
+
+    fn surrogate(&self, rng: &mut Rng) -> Vec<f64> {
+        let n = self.cos.len();
+        let mut values = vec![self.mean; n];
+        for (k, (&amplitude, &seasonal)) in (1..).zip(self.amplitudes.iter().zip(&self.seasonal)) {
+            // the squared amplitude of noise is exponential
+            let amplitude = if seasonal {
+                (-amplitude * rng.uniform().ln()).sqrt()
+            } else {
+                amplitude
+            };
+            let phase = 2.0 * std::f64::consts::PI * rng.uniform();
+            // the Nyquist frequency has no sine part, so only its sign is drawn
+            let (a, b) = if 2 * k == n {
+                (amplitude * phase.cos().signum() / n as f64, 0.0)
+            } else {
+                let scale = 2.0 * amplitude / n as f64;
+                (scale * phase.cos(), scale * phase.sin())
+            };
+            for (t, v) in values.iter_mut().enumerate() {
+                *v += a * self.cos[k * t % n] - b * self.sin[k * t % n];
+            }
+        }
+        values
+    }
 }
 
-pub(crate) fn strength(component: &[f64], remainder: &[f64]) -> f64 {
-    let sr = component
+// One-way ANOVA of the detrended series grouped by phase, reported
+// as omega squared so that short series are not biased towards strength
+pub(crate) fn anova_strength(component: &[f64], remainder: &[f64], period: usize) -> f64 {
+    let mut sums = vec![0.0; period];
+    let mut counts = vec![0usize; period];
+    let values = component
         .iter()
         .zip(remainder)
-        .map(|(a, b)| a + b)
-        .collect::<Vec<f64>>();
-    (1.0 - var(remainder) / var(&sr)).max(0.0)
+        .enumerate()
+        .map(|(i, (a, b))| (i % period, a + b))
+        .filter(|(_, v)| !v.is_nan())
+        .collect::<Vec<(usize, f64)>>();
+    for &(phase, v) in &values {
+        sums[phase] += v;
+        counts[phase] += 1;
+    }
+
+    let groups = counts.iter().filter(|&&c| c > 0).count();
+    let n = values.len();
+    if groups < 2 || n <= groups {
+        return 0.0;
+    }
+
+    let mean = values.iter().map(|(_, v)| v).sum::<f64>() / n as f64;
+    let (mut ssb, mut ssw) = (0.0, 0.0);
+    for &(phase, v) in &values {
+        let group_mean = sums[phase] / counts[phase] as f64;
+        ssb += (group_mean - mean).powi(2);
+        ssw += (v - group_mean).powi(2);
+    }
+    let msw = ssw / (n - groups) as f64;
+    let sst = ssb + ssw;
+    if sst + msw == 0.0 {
+        return 0.0;
+    }
+    ((ssb - (groups - 1) as f64 * msw) / (sst + msw)).max(0.0)
+}
+
+// The last value of the seasonal component at each phase
+pub(crate) fn last_cycle(seasonal: &[f64], period: usize) -> Vec<f64> {
+    let n = seasonal.len();
+    let mut cycle = vec![0.0; period];
+    for (i, v) in seasonal.iter().enumerate().skip(n - period) {
+        cycle[i % period] = *v;
+    }
+    cycle
+}
+
+/// How to aggregate a bucket of observations.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AggFn {
+    /// The sum, for flows like sales.
+    Sum,
+    /// The mean, for levels like temperatures.
+    Mean,
+}
+
+/// Borrowed columns of a STL result.
+#[derive(Clone, Copy, Debug)]
+pub struct Components<'a> {
+    pub seasonal: &'a [f64],
+    pub trend: &'a [f64],
+    pub remainder: &'a [f64],
+    pub weights: &'a [f64],
+}
+
+/// The components of a STL result at one observation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Row {
+    pub seasonal: f64,
+    pub trend: f64,
+    pub remainder: f64,
+    pub weight: f64,
 }
 
 impl StlResult {
@@ -44,12 +225,433 @@ impl StlResult {
 
     /// Returns the seasonal strength.
     pub fn seasonal_strength(&self) -> f64 {
-        strength(self.seasonal(), self.remainder())
+        component_strength(self.seasonal(), self.remainder())
+    }
+
+    /// Returns the seasonal strength with the given variance estimator.
+    pub fn seasonal_strength_with(&self, variance: Variance) -> f64 {
+        strength_with(self.seasonal(), self.remainder(), variance)
+    }
+
+    /// Returns the seasonal strength from an ANOVA of the cycle-subseries.
+    ///
+    /// This is omega squared, which is less prone than [`Self::seasonal_strength`]
+    /// to overstate strength for short series.
+    pub fn seasonal_strength_anova(&self) -> f64 {
+        anova_strength(self.seasonal(), self.remainder(), self.period)
+    }
+
+    /// Returns the seasonal strength with the given formula.
+    pub fn seasonal_strength_by(&self, method: StrengthMethod) -> f64 {
+        match method {
+            StrengthMethod::VarianceRatio => self.seasonal_strength(),
+            StrengthMethod::Statsmodels => statsmodels_strength(self.seasonal(), self.remainder()),
+            StrengthMethod::FTest => self.seasonal_strength_anova(),
+        }
+    }
+
+    /// Returns a surrogate p-value for the seasonal strength.
+    ///
+    /// Surrogates of the detrended series are drawn by phase randomization
+    /// from `seed`, with the seasonal frequencies flattened to the level of
+    /// their neighbours, so that they keep the autocorrelation but not the
+    /// seasonal pattern. The trend is added back and each surrogate is refit
+    /// with the parameters of the fit. The p-value is the share of surrogates
+    /// at least as strong as the fit.
+    pub fn seasonal_strength_pvalue(&self, n_surrogates: usize, seed: u64) -> Result<f64, Error> {
+        let Some(params) = &self.params else {
+            return Err(Error::Parameter(
+                "the p-value needs a result fitted with StlParams".to_string(),
+            ));
+        };
+        let observed = self.seasonal_strength();
+        let detrended = self
+            .seasonal
+            .iter()
+            .zip(&self.remainder)
+            .map(|(s, r)| s + r)
+            .collect::<Vec<f64>>();
+
+        let spectrum = Spectrum::new(&detrended, self.period);
+        let mut rng = Rng(seed);
+        let mut count = 0;
+        for _ in 0..n_surrogates {
+            // missing values stay missing in every surrogate
+            let surrogate = spectrum
+                .surrogate(&mut rng)
+                .iter()
+                .zip(&self.trend)
+                .zip(&detrended)
+                .map(|((s, t), d)| if d.is_nan() { f64::NAN } else { s + t })
+                .collect::<Vec<f64>>();
+            if params.fit(&surrogate, self.period)?.seasonal_strength() >= observed {
+                count += 1;
+            }
+        }
+        Ok((count + 1) as f64 / (n_surrogates + 1) as f64)
     }
 
     /// Returns the trend strength.
     pub fn trend_strength(&self) -> f64 {
-        strength(self.trend(), self.remainder())
+        component_strength(self.trend(), self.remainder())
+    }
+
+    /// Returns the trend strength with the given variance estimator.
+    pub fn trend_strength_with(&self, variance: Variance) -> f64 {
+        strength_with(self.trend(), self.remainder(), variance)
+    }
+
+    /// Returns the period used for the decomposition.
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Returns the regression effects removed before decomposing, if any.
+    ///
+    /// The seasonal component, trend component, remainder, and this effect add
+    /// up to the series.
+    pub fn regression(&self) -> Option<&RegressionEffect> {
+        self.regression.as_ref()
+    }
+
+    /// Returns counts and timings of the work done by the final fit.
+    pub fn fit_report(&self) -> &FitReport {
+        &self.report
+    }
+
+    /// Encodes the result in a compact, versioned binary format.
+    ///
+    /// The fit report is not included.
+    #[cfg(feature = "serde")]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        super::serialize::to_bytes(self)
+    }
+
+    /// Decodes a result written by [`StlResult::to_bytes`].
+    #[cfg(feature = "serde")]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, super::Error> {
+        super::serialize::from_bytes(bytes)
+    }
+
+    // Checks the smoother settings of a result read back from outside, which
+    // must be ones a fit can produce
+    pub(crate) fn check_settings(&self) -> Result<(), Error> {
+        for (name, length) in [
+            ("seasonal_length", self.seasonal_length),
+            ("trend_length", self.trend_length),
+        ] {
+            if length < 3 {
+                return Err(Error::Parameter(format!("{} must be at least 3", name)));
+            }
+            if length.is_multiple_of(2) {
+                return Err(Error::Parameter(format!("{} must be odd", name)));
+            }
+        }
+        if self.seasonal_degree != 0 && self.seasonal_degree != 1 {
+            return Err(Error::Parameter(
+                "seasonal_degree must be 0 or 1".to_string(),
+            ));
+        }
+        // X11 trends come from Henderson filters, which reproduce cubics
+        if ![0, 1, 3].contains(&self.trend_degree) {
+            return Err(Error::Parameter(
+                "trend_degree must be 0, 1 or 3".to_string(),
+            ));
+        }
+        if self.partial_cycle >= self.period {
+            return Err(Error::Parameter(
+                "partial_cycle must be less than the period".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Tests the remainder for seasonality left at the period and its harmonics.
+    pub fn check_residual_seasonality(&self) -> ResidualSeasonality {
+        residual_seasonality(&self.remainder, self.period)
+    }
+
+    /// Tests the remainder for correlation with the trend and seasonal
+    /// component, at lag 0 and at the period.
+    pub fn check_leakage(&self) -> Leakage {
+        leakage(
+            &self.remainder,
+            &[
+                ("trend".to_string(), &self.trend),
+                ("seasonal".to_string(), &self.seasonal),
+            ],
+            &[self.period],
+        )
+    }
+
+    /// Returns the residual seasonality check made during the fit, if enabled
+    /// with [`StlParams::check_residual_seasonality`].
+    pub fn residual_seasonality(&self) -> Option<&ResidualSeasonality> {
+        self.residual_seasonality.as_ref()
+    }
+
+    /// Returns the indices of the observations clipped before fitting, if
+    /// enabled with [`StlParams::winsorize`].
+    pub fn clipped(&self) -> &[usize] {
+        &self.clipped
+    }
+
+    /// Returns the number of observations in the incomplete final cycle of the series.
+    pub fn partial_cycle_len(&self) -> usize {
+        self.partial_cycle
+    }
+
+    /// Returns the observations whose remainder is unusually large.
+    ///
+    /// The remainder is standardized with its median and scaled MAD, and
+    /// observations are flagged when a two-sided test at level `alpha` rejects
+    /// under a normal distribution.
+    pub fn anomalies(&self, alpha: f64) -> Vec<Anomaly> {
+        anomalies(&self.seasonal, &self.trend, &self.remainder, alpha)
+    }
+
+    /// Flags observations whose remainder lies outside Tukey's fences.
+    ///
+    /// An observation is an outlier when its remainder is more than `k_iqr`
+    /// interquartile ranges below the lower quartile or above the upper
+    /// quartile, with 1.5 the usual choice and 3.0 for extreme outliers. This
+    /// is a quicker screen than [`anomalies`](Self::anomalies).
+    pub fn outliers(&self, k_iqr: f64) -> Outliers {
+        outliers(&self.remainder, k_iqr)
+    }
+
+    /// Returns the scale of the remainder at each observation, which varies
+    /// with the season when the noise does.
+    ///
+    /// The absolute remainder is decomposed with a robust STL fit at the same
+    /// period, and its fitted values are scaled to estimate the standard
+    /// deviation of normally distributed noise. Dividing the remainder by the
+    /// scale gives z-scores for thresholds that vary by time of day or day of
+    /// week.
+    pub fn remainder_scale(&self) -> Vec<f64> {
+        let absolute = self.remainder.iter().map(|r| r.abs()).collect::<Vec<f64>>();
+        match StlParams::new()
+            .robust(true)
+            .non_negative(NonNegative::Fitted)
+            .fit(&absolute, self.period)
+        {
+            // the mean absolute deviation of a normal distribution is sqrt(2 / pi) sigma
+            Ok(fit) => fit
+                .seasonal
+                .iter()
+                .zip(&fit.trend)
+                .map(|(s, t)| (s + t).max(0.0) * (std::f64::consts::PI / 2.0).sqrt())
+                .collect(),
+            Err(_) => vec![f64::NAN; absolute.len()],
+        }
+    }
+
+    /// Returns the most recent seasonal cycle, indexed by phase.
+    ///
+    /// The value at phase `p` is the seasonal component at the last index `i`
+    /// of the series with `i % period == p`.
+    pub fn seasonal_cycle(&self) -> Vec<f64> {
+        last_cycle(&self.seasonal, self.period)
+    }
+
+    /// Returns the first `k` Fourier harmonics of the most recent seasonal cycle.
+    ///
+    /// Orders above half the period are not identifiable and are omitted.
+    pub fn seasonal_harmonics(&self, k: usize) -> Vec<Harmonic> {
+        harmonics(&self.seasonal_cycle(), k)
+    }
+
+    /// Removes the most recent seasonal cycle from new observations.
+    ///
+    /// `start_phase` is the phase of the first new observation, counting from
+    /// the start of the fitted series. For data that directly follows the
+    /// fitted series, this is the length of that series.
+    pub fn seasonally_adjust(&self, new_series: &[f64], start_phase: usize) -> Vec<f64> {
+        let cycle = self.seasonal_cycle();
+        new_series
+            .iter()
+            .enumerate()
+            .map(|(i, v)| v - cycle[(start_phase + i) % self.period])
+            .collect()
+    }
+
+    /// Forecasts the series `horizon` steps past its end (STLF).
+    ///
+    /// The seasonally adjusted series (trend plus remainder) is forecast with
+    /// `method`, and the most recent seasonal cycle is added back.
+    pub fn forecast(&self, horizon: usize, method: ForecastMethod) -> Vec<f64> {
+        let adjusted = adjusted_series(&self.trend, &self.remainder);
+        self.reseasonalize(&forecast_adjusted(&adjusted, self.period, horizon, method))
+    }
+
+    /// Adds the most recent seasonal cycle back onto a forecast of the
+    /// seasonally adjusted series that starts right after the fitted series.
+    pub fn reseasonalize(&self, adjusted_forecast: &[f64]) -> Vec<f64> {
+        let cycle = self.seasonal_cycle();
+        let n = self.seasonal.len();
+        adjusted_forecast
+            .iter()
+            .enumerate()
+            .map(|(h, v)| v + cycle[(n + h) % self.period])
+            .collect()
+    }
+
+    /// Aggregates every `factor` consecutive observations of each component,
+    /// such as daily to weekly with a factor of 7.
+    ///
+    /// The components are aggregated the same way, so they still add up to the
+    /// aggregated series. The period and trend length are divided by the
+    /// factor, and a trailing incomplete bucket is dropped. The factor must
+    /// divide the period and leave a period of at least 2.
+    pub fn resample(&self, factor: usize, agg: AggFn) -> Result<StlResult, Error> {
+        if factor == 0 || !self.period.is_multiple_of(factor) || self.period / factor < 2 {
+            return Err(Error::Parameter(
+                "factor must divide the period and leave a period of at least 2".to_string(),
+            ));
+        }
+        let aggregate = |values: &[f64]| {
+            values
+                .chunks_exact(factor)
+                .map(|chunk| {
+                    let sum = chunk.iter().sum::<f64>();
+                    match agg {
+                        AggFn::Sum => sum,
+                        AggFn::Mean => sum / factor as f64,
+                    }
+                })
+                .collect::<Vec<f64>>()
+        };
+        let weights = self
+            .weights
+            .chunks_exact(factor)
+            .map(|chunk| chunk.iter().sum::<f64>() / factor as f64)
+            .collect::<Vec<f64>>();
+        let period = self.period / factor;
+        let len = weights.len();
+        // the seasonal length counts cycles, which resampling keeps
+        let mut trend_length = self.trend_length.div_ceil(factor).max(3);
+        if trend_length.is_multiple_of(2) {
+            trend_length += 1;
+        }
+        Ok(StlResult {
+            seasonal: aggregate(&self.seasonal),
+            trend: aggregate(&self.trend),
+            remainder: aggregate(&self.remainder),
+            partial_cycle: weights.len() % period,
+            weights,
+            period,
+            seasonal_length: self.seasonal_length,
+            seasonal_degree: self.seasonal_degree,
+            trend_length,
+            trend_degree: self.trend_degree,
+            regression: self.regression.as_ref().map(|r| RegressionEffect {
+                names: r.names.clone(),
+                coefficients: r.coefficients.clone(),
+                effect: aggregate(&r.effect),
+            }),
+            report: self.report.clone(),
+            residual_seasonality: None,
+            clipped: {
+                let mut clipped = self
+                    .clipped
+                    .iter()
+                    .map(|i| i / factor)
+                    .filter(|&i| i < len)
+                    .collect::<Vec<usize>>();
+                clipped.dedup();
+                clipped
+            },
+            // the fitted parameters don't apply at the coarser frequency
+            params: None,
+        })
+    }
+
+    /// Extracts a compact seasonal model that can be saved and restored.
+    pub fn seasonal_model(&self) -> SeasonalModel {
+        let n = self.trend.len();
+        SeasonalModel {
+            cycle: self.seasonal_cycle(),
+            next_phase: n % self.period,
+            trend_tail: self.trend[n - self.period..].to_vec(),
+            remainder_scale: mad(&self.remainder),
+        }
+    }
+
+    /// Returns the local slope of the trend at each point.
+    ///
+    /// The slope is the linear coefficient of the loess fit at that point,
+    /// using the trend smoother's neighborhood and robustness weights. For a
+    /// trend degree of 0, this is the slope of a local linear fit with the same weights.
+    pub fn trend_slope(&self) -> Vec<f64> {
+        let n = self.trend.len();
+        let y = self
+            .trend
+            .iter()
+            .zip(&self.remainder)
+            .map(|(t, r)| t + r)
+            .collect::<Vec<f64>>();
+        let mut slope = vec![0.0; n];
+        slp(&y, n, self.trend_length, true, &self.weights, &mut slope);
+        slope
+    }
+
+    /// Returns the points where the trend changes direction.
+    ///
+    /// A change is only reported once the new direction has persisted for
+    /// `min_duration` consecutive steps.
+    pub fn trend_turning_points(&self, min_duration: usize) -> Vec<TurningPoint> {
+        turning_points(&self.trend, min_duration)
+    }
+
+    /// Returns diagnostics for the final trend smoother.
+    pub fn trend_diagnostics(&self) -> LoessDiagnostics {
+        let n = self.trend.len();
+        let mut leverage = vec![0.0; n];
+        lev(
+            n,
+            self.trend_length,
+            self.trend_degree,
+            true,
+            &self.weights,
+            &mut leverage,
+        );
+        LoessDiagnostics { leverage }
+    }
+
+    /// Returns diagnostics for the final cycle-subseries smoother.
+    pub fn seasonal_diagnostics(&self) -> LoessDiagnostics {
+        let n = self.seasonal.len();
+        let mut leverage = vec![0.0; n];
+        sslev(
+            n,
+            self.period,
+            self.seasonal_length,
+            self.seasonal_degree,
+            true,
+            &self.weights,
+            &mut leverage,
+        );
+        LoessDiagnostics { leverage }
+    }
+
+    /// Returns the components as borrowed columns.
+    pub fn components(&self) -> Components<'_> {
+        Components {
+            seasonal: &self.seasonal,
+            trend: &self.trend,
+            remainder: &self.remainder,
+            weights: &self.weights,
+        }
+    }
+
+    /// Returns an iterator over the components at each observation.
+    pub fn rows(&self) -> impl Iterator<Item = Row> + '_ {
+        (0..self.seasonal.len()).map(|i| Row {
+            seasonal: self.seasonal[i],
+            trend: self.trend[i],
+            remainder: self.remainder[i],
+            weight: self.weights[i],
+        })
     }
 
     /// Consumes the result, returning the seasonal component, trend component, remainder, and weights.
@@ -57,3 +659,10 @@ impl StlResult {
         (self.seasonal, self.trend, self.remainder, self.weights)
     }
 }
+
+impl From<StlResult> for (Vec<f64>, Vec<f64>, Vec<f64>) {
+    /// Returns the seasonal component, trend component, and remainder.
+    fn from(result: StlResult) -> Self {
+        (result.seasonal, result.trend, result.remainder)
+    }
+}
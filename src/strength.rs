@@ -0,0 +1,189 @@
+use std::borrow::Borrow;
+
+use super::StlResult;
+use super::anomaly::quantile;
+use super::seasonal_model::mad;
+
+/// An estimator of variance for the strength formulas.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Variance {
+    /// The sample variance.
+    #[default]
+    Sample,
+    /// The square of the median absolute deviation, scaled to match the
+    /// variance of normally distributed data.
+    Mad,
+    /// The sample variance after dropping the given fraction of values from
+    /// each tail.
+    Trimmed(f64),
+}
+
+/// A formula for the strength of a component.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum StrengthMethod {
+    /// The variance ratio of Hyndman and Athanasopoulos,
+    /// max(0, 1 - Var(R) / Var(S + R)), with sample variances.
+    #[default]
+    VarianceRatio,
+    /// The same ratio as commonly computed from statsmodels results with
+    /// NumPy: population variances and no clamping, so it can be negative.
+    Statsmodels,
+    /// Omega squared from the one-way ANOVA F-test of the detrended series
+    /// grouped by phase (seasonal components only).
+    FTest,
+}
+
+// The ratio with population variances, without clamping
+pub(crate) fn statsmodels_strength(component: &[f64], remainder: &[f64]) -> f64 {
+    let population_variance = |series: &[f64]| {
+        let series = series
+            .iter()
+            .copied()
+            .filter(|v| !v.is_nan())
+            .collect::<Vec<f64>>();
+        let mean = series.iter().sum::<f64>() / series.len() as f64;
+        series.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / series.len() as f64
+    };
+    let sr = component
+        .iter()
+        .zip(remainder)
+        .map(|(a, b)| a + b)
+        .collect::<Vec<f64>>();
+    1.0 - population_variance(remainder) / population_variance(&sr)
+}
+
+// missing observations leave NaN in the remainder and are skipped
+fn variance(series: &[f64], estimator: Variance) -> f64 {
+    let mut series = series
+        .iter()
+        .copied()
+        .filter(|v| !v.is_nan())
+        .collect::<Vec<f64>>();
+    match estimator {
+        Variance::Sample => {}
+        Variance::Mad => return mad(&series).powi(2),
+        Variance::Trimmed(fraction) => {
+            series.sort_unstable_by(|a, b| a.total_cmp(b));
+            let k = (series.len() as f64 * fraction.clamp(0.0, 0.5)) as usize;
+            let end = series.len() - k;
+            series = series[k.min(end)..end].to_vec();
+        }
+    }
+    let mean = series.iter().sum::<f64>() / series.len() as f64;
+    series.iter().map(|v| (v - mean).powf(2.0)).sum::<f64>() / (series.len() as f64 - 1.0)
+}
+
+/// Returns the strength of a component relative to the remainder,
+/// max(0, 1 - Var(R) / Var(C + R)).
+///
+/// Any combination of components can be measured, such as the sum of
+/// several seasonal components. Missing values are skipped.
+pub fn component_strength(component: &[f64], remainder: &[f64]) -> f64 {
+    strength_with(component, remainder, Variance::Sample)
+}
+
+pub(crate) fn strength_with(component: &[f64], remainder: &[f64], estimator: Variance) -> f64 {
+    let sr = component
+        .iter()
+        .zip(remainder)
+        .map(|(a, b)| a + b)
+        .collect::<Vec<f64>>();
+    (1.0 - variance(remainder, estimator) / variance(&sr, estimator)).max(0.0)
+}
+
+/// Summary statistics of strengths across many fits.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StrengthSummary {
+    pub(crate) count: usize,
+    pub(crate) mean: f64,
+    pub(crate) quantiles: [f64; 5],
+    pub(crate) histogram: Vec<usize>,
+}
+
+impl StrengthSummary {
+    /// Returns the number of strengths summarized, excluding NaN.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the mean strength.
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Returns the minimum, lower quartile, median, upper quartile, and maximum.
+    pub fn quantiles(&self) -> [f64; 5] {
+        self.quantiles
+    }
+
+    /// Returns the number of strengths in each of the equal-width bins
+    /// covering [0, 1].
+    pub fn histogram(&self) -> &[usize] {
+        &self.histogram
+    }
+}
+
+fn map_results<R, F>(results: &[R], f: F) -> Vec<f64>
+where
+    R: Borrow<StlResult> + Sync,
+    F: Fn(&StlResult) -> f64 + Sync,
+{
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        crate::parallel::install(|| results.par_iter().map(|r| f(r.borrow())).collect())
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    results.iter().map(|r| f(r.borrow())).collect()
+}
+
+/// Returns the seasonal strength of each result.
+///
+/// With the `parallel` feature, the strengths are computed in parallel.
+pub fn seasonal_strengths<R: Borrow<StlResult> + Sync>(results: &[R]) -> Vec<f64> {
+    map_results(results, StlResult::seasonal_strength)
+}
+
+/// Returns the trend strength of each result.
+///
+/// With the `parallel` feature, the strengths are computed in parallel.
+pub fn trend_strengths<R: Borrow<StlResult> + Sync>(results: &[R]) -> Vec<f64> {
+    map_results(results, StlResult::trend_strength)
+}
+
+/// Summarizes strengths with their mean, quartiles, and a histogram with the
+/// given number of bins.
+///
+/// NaN strengths are skipped.
+pub fn strength_summary(strengths: &[f64], bins: usize) -> StrengthSummary {
+    let mut sorted = strengths
+        .iter()
+        .copied()
+        .filter(|v| !v.is_nan())
+        .collect::<Vec<f64>>();
+    sorted.sort_unstable_by(|a, b| a.total_cmp(b));
+
+    let mut histogram = vec![0; bins];
+    if bins > 0 {
+        for v in &sorted {
+            let bin = (v.clamp(0.0, 1.0) * bins as f64) as usize;
+            histogram[bin.min(bins - 1)] += 1;
+        }
+    }
+
+    if sorted.is_empty() {
+        return StrengthSummary {
+            count: 0,
+            mean: f64::NAN,
+            quantiles: [f64::NAN; 5],
+            histogram,
+        };
+    }
+    StrengthSummary {
+        count: sorted.len(),
+        mean: sorted.iter().sum::<f64>() / sorted.len() as f64,
+        quantiles: [0.0, 0.25, 0.5, 0.75, 1.0].map(|q| quantile(&sorted, q)),
+        histogram,
+    }
+}
@@ -0,0 +1,272 @@
+//! Synthetic series with known components, generated reproducibly from a seed.
+
+use std::f64::consts::PI;
+
+/// The shape of a synthetic trend.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Trend {
+    /// A straight line.
+    Linear { intercept: f64, slope: f64 },
+    /// A parabola.
+    Quadratic {
+        intercept: f64,
+        slope: f64,
+        curvature: f64,
+    },
+    /// A level shift at `index`.
+    Step {
+        index: usize,
+        before: f64,
+        after: f64,
+    },
+}
+
+impl Trend {
+    fn value(&self, t: usize) -> f64 {
+        let x = t as f64;
+        match *self {
+            Trend::Linear { intercept, slope } => intercept + slope * x,
+            Trend::Quadratic {
+                intercept,
+                slope,
+                curvature,
+            } => intercept + slope * x + curvature * x * x,
+            Trend::Step {
+                index,
+                before,
+                after,
+            } => {
+                if t < index {
+                    before
+                } else {
+                    after
+                }
+            }
+        }
+    }
+}
+
+/// The distribution of synthetic noise.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Noise {
+    /// Independent normal noise.
+    Gaussian { sd: f64 },
+    /// Independent Laplace noise, which has heavier tails.
+    Laplace { scale: f64 },
+    /// First-order autoregressive noise with normal innovations.
+    Ar1 { phi: f64, sd: f64 },
+}
+
+// SplitMix64, which is small and good enough for test data
+pub(crate) struct Rng(pub(crate) u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    // uniform on (0, 1)
+    pub(crate) fn uniform(&mut self) -> f64 {
+        ((self.next_u64() >> 11) as f64 + 0.5) / (1u64 << 53) as f64
+    }
+
+    fn normal(&mut self) -> f64 {
+        let (u1, u2) = (self.uniform(), self.uniform());
+        (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+    }
+
+    fn laplace(&mut self) -> f64 {
+        let u = self.uniform() - 0.5;
+        -u.signum() * (1.0 - 2.0 * u.abs()).ln()
+    }
+
+    pub(crate) fn index(&mut self, len: usize) -> usize {
+        (self.uniform() * len as f64) as usize
+    }
+}
+
+/// A set of synthetic series parameters.
+#[derive(Clone, Debug)]
+pub struct SynthParams {
+    len: usize,
+    seed: u64,
+    seasonalities: Vec<(f64, f64)>,
+    trend: Option<Trend>,
+    noise: Option<Noise>,
+    anomalies: usize,
+    anomaly_magnitude: f64,
+    missing: Vec<(usize, usize)>,
+}
+
+impl SynthParams {
+    /// Creates a new set of parameters for a series of the given length.
+    pub fn new(len: usize) -> Self {
+        Self {
+            len,
+            seed: 0,
+            seasonalities: Vec::new(),
+            trend: None,
+            noise: None,
+            anomalies: 0,
+            anomaly_magnitude: 0.0,
+            missing: Vec::new(),
+        }
+    }
+
+    /// Sets the random seed.
+    pub fn seed(&mut self, seed: u64) -> &mut Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Adds a sinusoidal seasonality.
+    pub fn seasonality(&mut self, period: f64, amplitude: f64) -> &mut Self {
+        self.seasonalities.push((period, amplitude));
+        self
+    }
+
+    /// Sets the trend.
+    pub fn trend(&mut self, trend: Trend) -> &mut Self {
+        self.trend = Some(trend);
+        self
+    }
+
+    /// Sets the noise model.
+    pub fn noise(&mut self, noise: Noise) -> &mut Self {
+        self.noise = Some(noise);
+        self
+    }
+
+    /// Injects anomalies of the given magnitude at random positions.
+    pub fn anomalies(&mut self, count: usize, magnitude: f64) -> &mut Self {
+        self.anomalies = count;
+        self.anomaly_magnitude = magnitude;
+        self
+    }
+
+    /// Replaces a block of observations with NaN.
+    pub fn missing_block(&mut self, start: usize, len: usize) -> &mut Self {
+        self.missing.push((start, len));
+        self
+    }
+
+    /// Generates the series.
+    pub fn generate(&self) -> SynthSeries {
+        let n = self.len;
+        let mut rng = Rng(self.seed);
+
+        let seasonal = self
+            .seasonalities
+            .iter()
+            .map(|&(period, amplitude)| {
+                let phase = 2.0 * PI * rng.uniform();
+                (0..n)
+                    .map(|t| amplitude * (2.0 * PI * t as f64 / period + phase).sin())
+                    .collect::<Vec<f64>>()
+            })
+            .collect::<Vec<Vec<f64>>>();
+
+        let trend = (0..n)
+            .map(|t| self.trend.map(|tr| tr.value(t)).unwrap_or(0.0))
+            .collect::<Vec<f64>>();
+
+        let mut noise = vec![0.0; n];
+        let mut prev = 0.0;
+        for v in noise.iter_mut() {
+            *v = match self.noise {
+                Some(Noise::Gaussian { sd }) => sd * rng.normal(),
+                Some(Noise::Laplace { scale }) => scale * rng.laplace(),
+                Some(Noise::Ar1 { phi, sd }) => {
+                    prev = phi * prev + sd * rng.normal();
+                    prev
+                }
+                None => 0.0,
+            };
+        }
+
+        let mut values = (0..n)
+            .map(|t| trend[t] + seasonal.iter().map(|s| s[t]).sum::<f64>() + noise[t])
+            .collect::<Vec<f64>>();
+
+        let mut anomalies = Vec::new();
+        if n > 0 {
+            while anomalies.len() < self.anomalies.min(n) {
+                let i = rng.index(n);
+                if !anomalies.contains(&i) {
+                    anomalies.push(i);
+                }
+            }
+        }
+        anomalies.sort_unstable();
+        for &i in &anomalies {
+            let sign = if rng.uniform() < 0.5 { -1.0 } else { 1.0 };
+            values[i] += sign * self.anomaly_magnitude;
+        }
+
+        let mut missing = Vec::new();
+        for &(start, len) in &self.missing {
+            missing.extend(start.min(n)..(start + len).min(n));
+        }
+        missing.sort_unstable();
+        missing.dedup();
+        for &i in &missing {
+            values[i] = f64::NAN;
+        }
+
+        SynthSeries {
+            values,
+            seasonal,
+            trend,
+            noise,
+            anomalies,
+            missing,
+        }
+    }
+}
+
+/// A synthetic series and its known components.
+#[derive(Clone, Debug)]
+pub struct SynthSeries {
+    values: Vec<f64>,
+    seasonal: Vec<Vec<f64>>,
+    trend: Vec<f64>,
+    noise: Vec<f64>,
+    anomalies: Vec<usize>,
+    missing: Vec<usize>,
+}
+
+impl SynthSeries {
+    /// Returns the observed values, with NaN for missing observations.
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// Returns the seasonal components, in the order they were added.
+    pub fn seasonal(&self) -> &[Vec<f64>] {
+        &self.seasonal
+    }
+
+    /// Returns the trend component.
+    pub fn trend(&self) -> &[f64] {
+        &self.trend
+    }
+
+    /// Returns the noise component.
+    pub fn noise(&self) -> &[f64] {
+        &self.noise
+    }
+
+    /// Returns the indices of injected anomalies.
+    pub fn anomalies(&self) -> &[usize] {
+        &self.anomalies
+    }
+
+    /// Returns the indices of missing observations.
+    pub fn missing(&self) -> &[usize] {
+        &self.missing
+    }
+}
@@ -0,0 +1,317 @@
+use super::missing::fill_missing;
+use super::regression::ols;
+use super::{Error, Mstl, MstlResult, Stl, StlResult};
+
+/// A series of observations with optional timestamps and sampling interval.
+///
+/// Timestamps are integers in any unit, such as seconds or milliseconds since
+/// the epoch, and the interval is in the same unit.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimeSeries {
+    values: Vec<f64>,
+    timestamps: Option<Vec<i64>>,
+    interval: Option<i64>,
+}
+
+impl TimeSeries {
+    /// Creates a series of equally spaced observations without timestamps.
+    pub fn new(values: Vec<f64>) -> Self {
+        Self {
+            values,
+            timestamps: None,
+            interval: None,
+        }
+    }
+
+    /// Creates a series with a timestamp for each observation.
+    ///
+    /// The sampling interval is the median difference between consecutive
+    /// timestamps, after sorting, unless set with
+    /// [`interval`](Self::interval).
+    pub fn with_timestamps(values: Vec<f64>, timestamps: Vec<i64>) -> Result<Self, Error> {
+        if timestamps.len() != values.len() {
+            return Err(Error::Series(
+                "timestamps must have one value per observation".to_string(),
+            ));
+        }
+        let mut sorted = timestamps.clone();
+        sorted.sort_unstable();
+        let mut steps = sorted
+            .windows(2)
+            .map(|w| w[1] - w[0])
+            .filter(|&d| d > 0)
+            .collect::<Vec<i64>>();
+        steps.sort_unstable();
+        Ok(Self {
+            values,
+            timestamps: Some(timestamps),
+            interval: steps.get(steps.len() / 2).copied(),
+        })
+    }
+
+    /// Sets the sampling interval.
+    pub fn interval(&mut self, interval: i64) -> &mut Self {
+        self.interval = Some(interval);
+        self
+    }
+
+    /// Returns the observations.
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// Returns the timestamps, if any.
+    pub fn timestamps(&self) -> Option<&[i64]> {
+        self.timestamps.as_deref()
+    }
+
+    /// Returns the sampling interval, if known.
+    pub fn sampling_interval(&self) -> Option<i64> {
+        self.interval
+    }
+
+    /// Returns the number of observations.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns whether the series has no observations.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Decomposes the series with STL and default parameters.
+    pub fn stl(&self, period: usize) -> Result<StlResult, Error> {
+        Stl::fit(&self.values, period)
+    }
+
+    /// Decomposes the series with MSTL and default parameters.
+    pub fn mstl(&self, periods: &[usize]) -> Result<MstlResult, Error> {
+        Mstl::fit(&self.values, periods)
+    }
+
+    /// Detects seasonal periods in increasing order.
+    pub fn detect_periods(&self) -> Vec<usize> {
+        let n = self.values.len();
+        if n < 6 {
+            return Vec::new();
+        }
+
+        let time = (0..n).map(|i| i as f64).collect::<Vec<f64>>();
+        let coef = ols(&self.values, &[vec![1.0; n], time]);
+        let residual = self
+            .values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| v - coef[0] - coef[1] * i as f64)
+            .collect::<Vec<f64>>();
+
+        // After removing a linear trend, candidate periods are scanned from
+        // the shortest, and each is scored by the share of the variance
+        // explained by the means at each phase, adjusted for the number of
+        // phases. A period is accepted when its score peaks among its
+        // neighbours, explains at least a tenth of the variance, is well above
+        // what white noise would score, and the pattern repeats, so that twice
+        // the period doesn't explain a fifth of what the period leaves. The
+        // phase means of an accepted period are removed before scanning on, so
+        // that multiples and aliases of it are only accepted for the variance
+        // they add, as a weekly cycle does in hourly data with a daily cycle.
+        // Periods of up to a third of the series are considered, so that each
+        // is seen at least three times.
+        //
+        // Scores are computed as the scan reaches them and kept until phase
+        // means are removed, after which the scan resumes past the accepted
+        // period, so each score is computed about once in all.
+        let max_period = n / 3;
+        let mut scores = PhaseScores::new(residual);
+        let observed = scores.observed;
+        // neighbouring periods explain nearly the same with few cycles, so
+        // peaks are taken over a window around each period
+        let width = |p: usize| (p / 20).max(1);
+        let mut periods: Vec<usize> = Vec::new();
+        let mut start = 2;
+        while start <= max_period {
+            let accepted = (start..=max_period).find(|&p| {
+                let score = scores.get(p);
+                // four standard deviations of the score of white noise
+                let noise = 4.0 * (2.0 * (p - 1) as f64).sqrt() / observed.saturating_sub(p) as f64;
+                score >= noise.max(0.1)
+                    && score > scores.get(p - 1)
+                    && (p - width(p)..=p + width(p)).all(|q| scores.get(q) <= score)
+                    && (2 * p > n / 2 || scores.get(2 * p) - score < 0.2 * (1.0 - score))
+            });
+            let Some(peak) = accepted else {
+                break;
+            };
+            // nested cycles are common, so a multiple of a period found
+            // earlier is preferred when it explains nearly as much
+            let best = scores.get(peak);
+            let period = (peak - width(peak)..=peak + width(peak))
+                .filter(|&m| periods.iter().any(|&q| m.is_multiple_of(q)))
+                .filter(|&m| scores.get(m) >= 0.98 * best)
+                .min_by_key(|&m| m.abs_diff(peak))
+                .unwrap_or(peak);
+
+            scores.remove(period);
+            periods.push(period);
+            start = period.max(peak) + 1;
+        }
+        periods
+    }
+
+    /// Returns a copy with the observations on a regular grid and missing
+    /// values filled.
+    ///
+    /// With timestamps and a sampling interval, observations are sorted and
+    /// moved to the nearest point of a grid with that interval from the first
+    /// timestamp, averaging observations at the same point. Points without
+    /// observations and missing values (NaN) are then filled by linear
+    /// interpolation.
+    pub fn clean(&self) -> TimeSeries {
+        let (mut values, timestamps) = match (&self.timestamps, self.interval) {
+            (Some(timestamps), Some(interval)) if interval > 0 && !timestamps.is_empty() => {
+                let start = *timestamps.iter().min().unwrap();
+                let end = *timestamps.iter().max().unwrap();
+                let slot = |t: i64| ((t - start) as f64 / interval as f64).round() as usize;
+
+                let mut sums = vec![0.0; slot(end) + 1];
+                let mut counts = vec![0; sums.len()];
+                for (&t, &v) in timestamps.iter().zip(&self.values) {
+                    if !v.is_nan() {
+                        sums[slot(t)] += v;
+                        counts[slot(t)] += 1;
+                    }
+                }
+                let values = sums
+                    .iter()
+                    .zip(&counts)
+                    .map(|(s, &c)| if c > 0 { s / c as f64 } else { f64::NAN })
+                    .collect::<Vec<f64>>();
+                let grid = (0..values.len())
+                    .map(|i| start + i as i64 * interval)
+                    .collect();
+                (values, Some(grid))
+            }
+            _ => (self.values.clone(), self.timestamps.clone()),
+        };
+
+        // a series with no observations at all is left as it is
+        if values.iter().any(|v| !v.is_nan())
+            && let Some((filled, _)) = fill_missing(&values)
+        {
+            values = filled;
+        }
+        TimeSeries {
+            values,
+            timestamps,
+            interval: self.interval,
+        }
+    }
+}
+
+// The mean at each phase, skipping missing values
+fn phase_means(series: &[f64], period: usize) -> Vec<f64> {
+    let mut sums = vec![0.0; period];
+    let mut counts = vec![0; period];
+    for (i, v) in series.iter().enumerate() {
+        if !v.is_nan() {
+            sums[i % period] += v;
+            counts[i % period] += 1;
+        }
+    }
+    sums.iter()
+        .zip(&counts)
+        .map(|(s, &c)| if c > 0 { s / c as f64 } else { 0.0 })
+        .collect()
+}
+
+// The adjusted R-squared of the phase means of a series, as in a one-way
+// ANOVA, computed on demand for each period and kept until the phase means
+// of a period are removed from the series
+struct PhaseScores {
+    series: Vec<f64>,
+    observed: usize,
+    // the sum of squares about the mean, and the sum of squares
+    total: f64,
+    squares: f64,
+    scores: Vec<Option<f64>>,
+}
+
+impl PhaseScores {
+    fn new(series: Vec<f64>) -> Self {
+        let observed = series.iter().filter(|v| !v.is_nan()).count();
+        let scores = vec![None; series.len() + 1];
+        let mut scores = Self {
+            series,
+            observed,
+            total: 0.0,
+            squares: 0.0,
+            scores,
+        };
+        scores.update();
+        scores
+    }
+
+    fn update(&mut self) {
+        let values = || self.series.iter().filter(|v| !v.is_nan());
+        let mean = values().sum::<f64>() / self.observed as f64;
+        self.total = values().map(|v| (v - mean).powi(2)).sum();
+        self.squares = values().map(|v| v * v).sum();
+        self.scores.fill(None);
+    }
+
+    fn get(&mut self, period: usize) -> f64 {
+        if period < 2 {
+            return f64::NEG_INFINITY;
+        }
+        if let Some(Some(score)) = self.scores.get(period) {
+            return *score;
+        }
+        let score = self.score(period);
+        if let Some(cached) = self.scores.get_mut(period) {
+            *cached = Some(score);
+        }
+        score
+    }
+
+    fn score(&self, period: usize) -> f64 {
+        if self.total <= 0.0 || self.observed <= period {
+            return 0.0;
+        }
+        let mut sums = vec![0.0; period];
+        let mut counts = vec![0; period];
+        for (phase, v) in self
+            .series
+            .chunks(period)
+            .flat_map(|c| c.iter().enumerate())
+        {
+            if !v.is_nan() {
+                sums[phase] += v;
+                counts[phase] += 1;
+            }
+        }
+        // the sum of squares within phases is what the phase means leave
+        let between = sums
+            .iter()
+            .zip(&counts)
+            .filter(|&(_, &c)| c > 0)
+            .map(|(s, &c)| s * s / c as f64)
+            .sum::<f64>();
+        let within = (self.squares - between).max(0.0);
+        1.0 - (within / (self.observed - period) as f64) / (self.total / (self.observed - 1) as f64)
+    }
+
+    fn remove(&mut self, period: usize) {
+        let means = phase_means(&self.series, period);
+        for (i, r) in self.series.iter_mut().enumerate() {
+            *r -= means[i % period];
+        }
+        self.update();
+    }
+}
+
+impl From<Vec<f64>> for TimeSeries {
+    fn from(values: Vec<f64>) -> Self {
+        Self::new(values)
+    }
+}
@@ -0,0 +1,71 @@
+/// A change in the direction of the trend.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TurningPoint {
+    /// The trend switches from increasing to decreasing at this index.
+    Peak(usize),
+    /// The trend switches from decreasing to increasing at this index.
+    Trough(usize),
+}
+
+impl TurningPoint {
+    /// Returns the index of the turning point.
+    pub fn index(&self) -> usize {
+        match *self {
+            TurningPoint::Peak(i) => i,
+            TurningPoint::Trough(i) => i,
+        }
+    }
+}
+
+// A direction change only counts once the new direction has held for
+// min_duration consecutive steps; shorter runs are treated as noise.
+pub(crate) fn turning_points(trend: &[f64], min_duration: usize) -> Vec<TurningPoint> {
+    let min_duration = min_duration.max(1);
+    let mut points = Vec::new();
+
+    let mut dir = 0.0;
+    let mut ext = 0;
+    let mut run_dir = 0.0;
+    let mut run_len = 0;
+
+    for i in 1..trend.len() {
+        let d = trend[i] - trend[i - 1];
+        if d == 0.0 {
+            continue;
+        }
+        let s = d.signum();
+
+        if s == run_dir {
+            run_len += 1;
+        } else {
+            run_dir = s;
+            run_len = 1;
+        }
+
+        if dir == 0.0 {
+            if run_len >= min_duration {
+                dir = s;
+                ext = i;
+            }
+        } else if s == dir {
+            if (trend[i] - trend[ext]) * dir > 0.0 {
+                ext = i;
+            }
+        } else if run_len >= min_duration {
+            points.push(if dir > 0.0 {
+                TurningPoint::Peak(ext)
+            } else {
+                TurningPoint::Trough(ext)
+            });
+            dir = s;
+            let start = ext;
+            for j in start..=i {
+                if (trend[j] - trend[ext]) * dir > 0.0 {
+                    ext = j;
+                }
+            }
+        }
+    }
+
+    points
+}
@@ -0,0 +1,421 @@
+// An X-11 style decomposition: centered and Henderson moving averages for
+// the trend, seasonal moving averages across cycles for the seasonal
+// component, and downweighting of extreme irregular values, iterated twice.
+// End points use Musgrave asymmetric Henderson weights and truncated seasonal
+// filters rather than forecast extension.
+
+use super::classical::{DecompositionModel, cma, extrapolate};
+use super::{Error, FitReport, StlResult};
+
+/// A seasonal moving average of the X-11 method, applied to each phase of the
+/// cycle across years.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SeasonalFilter {
+    /// A 3 term average of 3 term averages, for rapidly changing seasonality.
+    S3x3,
+    /// A 3 term average of 5 term averages.
+    #[default]
+    S3x5,
+    /// A 3 term average of 9 term averages, for stable seasonality.
+    S3x9,
+}
+
+impl SeasonalFilter {
+    fn weights(self) -> Vec<f64> {
+        let k = match self {
+            SeasonalFilter::S3x3 => 3,
+            SeasonalFilter::S3x5 => 5,
+            SeasonalFilter::S3x9 => 9,
+        };
+        let mut weights = vec![0.0; k + 2];
+        for i in 0..3 {
+            for w in &mut weights[i..i + k] {
+                *w += 1.0 / (3 * k) as f64;
+            }
+        }
+        weights
+    }
+}
+
+/// X-11 style decomposition.
+pub struct X11;
+
+impl X11 {
+    /// Decomposes a time series.
+    pub fn fit(series: &[f64], period: usize) -> Result<StlResult, Error> {
+        X11Params::new().fit(series, period)
+    }
+
+    /// Creates a new set of parameters.
+    pub fn params() -> X11Params {
+        X11Params::new()
+    }
+}
+
+/// A set of X-11 parameters.
+#[derive(Clone, Debug)]
+pub struct X11Params {
+    model: DecompositionModel,
+    henderson_length: Option<usize>,
+    seasonal_filter: SeasonalFilter,
+    sigma_limits: (f64, f64),
+}
+
+impl X11Params {
+    /// Creates a new set of parameters.
+    pub fn new() -> Self {
+        Self {
+            model: DecompositionModel::Additive,
+            henderson_length: None,
+            seasonal_filter: SeasonalFilter::S3x5,
+            sigma_limits: (1.5, 2.5),
+        }
+    }
+
+    /// Sets whether the components add or multiply.
+    pub fn model(&mut self, model: DecompositionModel) -> &mut Self {
+        self.model = model;
+        self
+    }
+
+    /// Sets the length of the Henderson trend filter (defaults to the
+    /// smallest odd number greater than the period).
+    pub fn henderson_length(&mut self, length: usize) -> &mut Self {
+        self.henderson_length = Some(length);
+        self
+    }
+
+    /// Sets the seasonal moving average of the final iteration (the first
+    /// iteration always uses a 3x3 average).
+    pub fn seasonal_filter(&mut self, filter: SeasonalFilter) -> &mut Self {
+        self.seasonal_filter = filter;
+        self
+    }
+
+    /// Sets the limits, in moving standard deviations of the irregular,
+    /// below which values get full weight and above which they get none.
+    pub fn sigma_limits(&mut self, lower: f64, upper: f64) -> &mut Self {
+        self.sigma_limits = (lower, upper);
+        self
+    }
+
+    /// Decomposes a time series.
+    ///
+    /// The weights of the result are the weights of the extreme value
+    /// adjustment in the final iteration.
+    pub fn fit(&self, series: &[f64], period: usize) -> Result<StlResult, Error> {
+        let n = series.len();
+        if period < 2 {
+            return Err(Error::Parameter("period must be at least 2".to_string()));
+        }
+        let henderson_length = self.henderson_length.unwrap_or((period + 1) | 1);
+        if henderson_length < 3 || henderson_length.is_multiple_of(2) {
+            return Err(Error::Parameter(
+                "henderson_length must be odd and at least 3".to_string(),
+            ));
+        }
+        let (lower, upper) = self.sigma_limits;
+        if !(lower > 0.0 && lower < upper) {
+            return Err(Error::Parameter(
+                "sigma limits must be positive and increasing".to_string(),
+            ));
+        }
+        if n < period * 3 {
+            return Err(Error::Series(
+                "series has less than three periods".to_string(),
+            ));
+        }
+        if n < henderson_length {
+            return Err(Error::Series(
+                "series is shorter than the henderson filter".to_string(),
+            ));
+        }
+        if series.iter().any(|v| v.is_nan()) {
+            return Err(Error::Series(
+                "x11 decomposition does not support missing values".to_string(),
+            ));
+        }
+        let multiplicative = self.model == DecompositionModel::Multiplicative;
+        if multiplicative && series.iter().any(|&v| v <= 0.0) {
+            return Err(Error::Series(
+                "multiplicative seasonality is not appropriate for zero and negative values"
+                    .to_string(),
+            ));
+        }
+
+        let x11 = Iteration {
+            period,
+            multiplicative,
+            limits: self.sigma_limits,
+        };
+
+        // first iteration, from a centered moving average trend
+        let mut trend = cma(series, period);
+        extrapolate(&mut trend, period / 2, period.min(n - 2 * (period / 2)));
+        let (seasonal, weights) = x11.seasonal(series, &trend, SeasonalFilter::S3x3);
+        let adjusted = x11.remove(series, &seasonal);
+        let trend = henderson(&x11.modify(&adjusted, &trend, &weights), henderson_length);
+
+        // second iteration, from the Henderson trend
+        let (seasonal, weights) = x11.seasonal(series, &trend, self.seasonal_filter);
+        let adjusted = x11.remove(series, &seasonal);
+        let trend = henderson(&x11.modify(&adjusted, &trend, &weights), henderson_length);
+        let remainder = x11.remove(&adjusted, &trend);
+
+        Ok(StlResult {
+            seasonal,
+            trend,
+            remainder,
+            weights,
+            period,
+            seasonal_length: self.seasonal_filter.weights().len(),
+            seasonal_degree: 0,
+            trend_length: henderson_length,
+            // Henderson filters reproduce cubic polynomials
+            trend_degree: 3,
+            partial_cycle: n % period,
+            regression: None,
+            report: FitReport::default(),
+            residual_seasonality: None,
+            clipped: Vec::new(),
+            params: None,
+        })
+    }
+}
+
+impl Default for X11Params {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct Iteration {
+    period: usize,
+    multiplicative: bool,
+    limits: (f64, f64),
+}
+
+impl Iteration {
+    fn remove(&self, a: &[f64], b: &[f64]) -> Vec<f64> {
+        a.iter()
+            .zip(b)
+            .map(|(a, b)| if self.multiplicative { a / b } else { a - b })
+            .collect()
+    }
+
+    // Shrinks the irregular of the seasonally adjusted series by the
+    // extreme value weights, so the trend filter doesn't follow outliers
+    fn modify(&self, adjusted: &[f64], trend: &[f64], weights: &[f64]) -> Vec<f64> {
+        adjusted
+            .iter()
+            .zip(trend)
+            .zip(weights)
+            .map(|((a, t), w)| {
+                if self.multiplicative {
+                    t * (1.0 + w * (a / t - 1.0))
+                } else {
+                    t + w * (a - t)
+                }
+            })
+            .collect()
+    }
+
+    // Estimates the seasonal component from the seasonal-irregular ratios or
+    // differences, replacing extreme values and estimating again
+    fn seasonal(
+        &self,
+        series: &[f64],
+        trend: &[f64],
+        filter: SeasonalFilter,
+    ) -> (Vec<f64>, Vec<f64>) {
+        let si = self.remove(series, trend);
+        let seasonal = self.normalize(&seasonal_ma(&si, self.period, filter));
+        let weights = self.extreme_weights(&si, &seasonal);
+        let si = self.replace_extremes(&si, &weights);
+        (
+            self.normalize(&seasonal_ma(&si, self.period, filter)),
+            weights,
+        )
+    }
+
+    // Moves values with less than full weight towards the average of the
+    // nearest two full-weight values on each side in the same phase
+    fn replace_extremes(&self, si: &[f64], weights: &[f64]) -> Vec<f64> {
+        let full = |i: &usize| weights[*i] == 1.0;
+        (0..si.len())
+            .map(|i| {
+                let w = weights[i];
+                if w == 1.0 {
+                    return si[i];
+                }
+                let before = (0..i / self.period)
+                    .rev()
+                    .map(|c| i - (i / self.period - c) * self.period)
+                    .filter(full)
+                    .take(2);
+                let after = (i + self.period..si.len())
+                    .step_by(self.period)
+                    .filter(full)
+                    .take(2);
+                let neighbors = before.chain(after).map(|j| si[j]).collect::<Vec<f64>>();
+                if neighbors.is_empty() {
+                    return si[i];
+                }
+                let average = neighbors.iter().sum::<f64>() / neighbors.len() as f64;
+                w * si[i] + (1.0 - w) * average
+            })
+            .collect()
+    }
+
+    // centers the seasonal component on zero (or one) over each cycle
+    fn normalize(&self, seasonal: &[f64]) -> Vec<f64> {
+        let n = seasonal.len();
+        let half = self.period / 2;
+        let mut level = cma(seasonal, self.period);
+        let (first, last) = (level[half], level[n - 1 - half]);
+        level[..half].fill(first);
+        level[n - half..].fill(last);
+        self.remove(seasonal, &level)
+    }
+
+    // Weights from the irregular relative to its standard deviation over a
+    // moving window of five cycles, which excludes the values beyond the
+    // upper limit
+    fn extreme_weights(&self, si: &[f64], seasonal: &[f64]) -> Vec<f64> {
+        let irregular = self.remove(si, seasonal);
+        let irregular = if self.multiplicative {
+            irregular.iter().map(|v| v - 1.0).collect()
+        } else {
+            irregular
+        };
+        let (lower, upper) = self.limits;
+        let cycles = irregular.len().div_ceil(self.period);
+        let span = cycles.min(5);
+
+        let sigmas = (0..cycles)
+            .map(|c| {
+                let start = c.saturating_sub(2).min(cycles - span) * self.period;
+                let end = ((start / self.period + span) * self.period).min(irregular.len());
+                let window = &irregular[start..end];
+                let rms = |values: &mut dyn Iterator<Item = &f64>| {
+                    let (sum, count) = values.fold((0.0, 0), |(s, c), v| (s + v * v, c + 1));
+                    (sum / count as f64).sqrt()
+                };
+                let sigma = rms(&mut window.iter());
+                let within = rms(&mut window.iter().filter(|v| v.abs() <= upper * sigma));
+                if within > 0.0 { within } else { sigma }
+            })
+            .collect::<Vec<f64>>();
+
+        irregular
+            .iter()
+            .enumerate()
+            .map(|(i, e)| {
+                let sigma = sigmas[i / self.period];
+                if sigma == 0.0 {
+                    return 1.0;
+                }
+                let z = e.abs() / sigma;
+                ((upper - z) / (upper - lower)).clamp(0.0, 1.0)
+            })
+            .collect()
+    }
+}
+
+// Applies the filter to each phase of the cycle, truncating and rescaling
+// the weights at the ends
+fn seasonal_ma(si: &[f64], period: usize, filter: SeasonalFilter) -> Vec<f64> {
+    let weights = filter.weights();
+    let half = (weights.len() / 2) as isize;
+    let mut out = vec![0.0; si.len()];
+    for phase in 0..period {
+        let sub = si.iter().skip(phase).step_by(period).collect::<Vec<&f64>>();
+        for i in 0..sub.len() {
+            let (mut sum, mut total) = (0.0, 0.0);
+            for (k, w) in weights.iter().enumerate() {
+                let j = i as isize + k as isize - half;
+                if j >= 0 && (j as usize) < sub.len() {
+                    sum += w * sub[j as usize];
+                    total += w;
+                }
+            }
+            out[phase + i * period] = sum / total;
+        }
+    }
+    out
+}
+
+fn henderson_weights(length: usize) -> Vec<f64> {
+    let h = (length / 2) as f64;
+    let m = h + 2.0;
+    let denominator =
+        8.0 * m * (m * m - 1.0) * (4.0 * m * m - 1.0) * (4.0 * m * m - 9.0) * (4.0 * m * m - 25.0);
+    (0..length)
+        .map(|i| {
+            let j = i as f64 - h;
+            315.0
+                * ((m - 1.0).powi(2) - j * j)
+                * (m * m - j * j)
+                * ((m + 1.0).powi(2) - j * j)
+                * (3.0 * m * m - 16.0 - 11.0 * j * j)
+                / denominator
+        })
+        .collect()
+}
+
+// Musgrave asymmetric weights for the first `available` terms of the
+// symmetric filter, which minimize revisions assuming a locally linear trend
+fn musgrave_weights(weights: &[f64], available: usize) -> Vec<f64> {
+    let length = weights.len();
+    // the ratio of the irregular to the trend-cycle assumed in X-11
+    let ic: f64 = if length <= 9 {
+        1.0
+    } else if length <= 13 {
+        3.5
+    } else {
+        4.5
+    };
+    let d = 4.0 / (std::f64::consts::PI * ic * ic);
+    let m = available as f64;
+    let center = (m + 1.0) / 2.0;
+    let dropped = weights[available..].iter().sum::<f64>();
+    let moment = weights[available..]
+        .iter()
+        .enumerate()
+        .map(|(i, w)| ((available + i + 1) as f64 - center) * w)
+        .sum::<f64>();
+    let slope = d / (1.0 + m * (m - 1.0) * (m + 1.0) * d / 12.0);
+    weights[..available]
+        .iter()
+        .enumerate()
+        .map(|(k, w)| w + dropped / m + ((k + 1) as f64 - center) * slope * moment)
+        .collect()
+}
+
+fn henderson(series: &[f64], length: usize) -> Vec<f64> {
+    let n = series.len();
+    let half = length / 2;
+    let weights = henderson_weights(length);
+    (0..n)
+        .map(|i| {
+            let before = i.min(half);
+            let after = (n - 1 - i).min(half);
+            let values = &series[i - before..=i + after];
+            if before == half && after == half {
+                values.iter().zip(&weights).map(|(v, w)| v * w).sum()
+            } else if before == half {
+                let asymmetric = musgrave_weights(&weights, half + 1 + after);
+                values.iter().zip(&asymmetric).map(|(v, w)| v * w).sum()
+            } else {
+                // mirror the filter at the start of the series
+                let asymmetric = musgrave_weights(&weights, half + 1 + before);
+                values
+                    .iter()
+                    .rev()
+                    .zip(&asymmetric)
+                    .map(|(v, w)| v * w)
+                    .sum()
+            }
+        })
+        .collect()
+}